@@ -0,0 +1,137 @@
+extern crate criterion;
+extern crate rand;
+
+extern crate procedural_towns;
+
+use std::env;
+use criterion::{ Criterion, BenchmarkId, BatchSize };
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ Population, PersonGenerator, AttributeList, Culture, Religion };
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::simulation::{ Pipeline, PhaseContext };
+use procedural_towns::town::Chronicle;
+use procedural_towns::utility::Date;
+
+const SIZES: [u32; 5] = [100, 1_000, 10_000, 100_000, 1_000_000];
+// TOWNS_BENCH_MAX_SIZE leaves out the larger sizes for quicker runs
+const DEFAULT_MAX_SIZE: u32 = 1_000_000;
+const SEED: u64 = 42;
+
+/// A population of the given size after one simulated year, with the date of the following year.
+struct Fixture {
+    size: u32,
+    population: Population,
+    person_generator: PersonGenerator,
+    today: Date
+}
+
+fn main() {
+    let mut criterion = Criterion::default()
+        .sample_size(10)
+        .configure_from_args();
+    for size in get_sizes() {
+        let fixture = Fixture::new(size);
+        bench_update(&mut criterion, &fixture);
+        bench_random_marriage(&mut criterion, &fixture);
+        bench_handle_births(&mut criterion, &fixture);
+        bench_get_random_by_attribute_list(&mut criterion, &fixture);
+    }
+    criterion.final_summary();
+}
+
+fn get_sizes() -> Vec<u32> {
+    let max_size = env::var("TOWNS_BENCH_MAX_SIZE").ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_MAX_SIZE);
+    SIZES.iter()
+        .cloned()
+        .filter(|&size| size <= max_size)
+        .collect()
+}
+
+impl Fixture {
+    fn new(size: u32) -> Self {
+        let config = SimulationConfig::default();
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let mut person_generator = PersonGenerator::new().expect("Could not load names");
+        let mut today = Date::default();
+        today.set_year(1000);
+        person_generator.set_date(today);
+        let settlers = [(Culture::default(), Religion::default())];
+        let mut population = Population::new(size, size * 2, today, &settlers, &config, &mut person_generator, &mut rng).expect("Could not found population");
+
+        // a settled year leaves pregnancies due in the next one
+        today.advance_years(1);
+        run_year(&mut population, &mut person_generator, today, &mut rng);
+        today.advance_years(1);
+        Self {
+            size,
+            population,
+            person_generator,
+            today
+        }
+    }
+}
+
+fn run_year(population: &mut Population, person_generator: &mut PersonGenerator, today: Date, rng: &mut StdRng) {
+    let mut chronicle = Chronicle::default();
+    let mut context = PhaseContext {
+        town_name: "Benchton",
+        date: today,
+        population,
+        chronicle: &mut chronicle,
+        person_generator,
+        rng
+    };
+    Pipeline::default().run(&mut context).expect("Could not run year");
+}
+
+fn bench_update(c: &mut Criterion, fixture: &Fixture) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    c.bench_with_input(BenchmarkId::new("population_update", fixture.size), fixture, |b, fixture| {
+        b.iter_batched(
+            || (fixture.population.clone(), fixture.person_generator.clone()),
+            |(mut population, mut person_generator)| {
+                run_year(&mut population, &mut person_generator, fixture.today, &mut rng);
+                population
+            },
+            BatchSize::LargeInput)
+    });
+}
+
+fn bench_random_marriage(c: &mut Criterion, fixture: &Fixture) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    c.bench_with_input(BenchmarkId::new("random_marriage", fixture.size), fixture, |b, fixture| {
+        b.iter_batched(
+            || fixture.population.clone(),
+            |mut population| {
+                population.random_marriage(fixture.today, &mut rng);
+                population
+            },
+            BatchSize::LargeInput)
+    });
+}
+
+fn bench_handle_births(c: &mut Criterion, fixture: &Fixture) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    c.bench_with_input(BenchmarkId::new("handle_births", fixture.size), fixture, |b, fixture| {
+        b.iter_batched(
+            || (fixture.population.clone(), fixture.person_generator.clone()),
+            |(mut population, mut person_generator)| {
+                population.handle_births(fixture.today, &mut person_generator, &mut rng).expect("Could not handle births");
+                population
+            },
+            BatchSize::LargeInput)
+    });
+}
+
+fn bench_get_random_by_attribute_list(c: &mut Criterion, fixture: &Fixture) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let wanted = AttributeList::builder().set_female().set_fertile().build();
+    let unwanted = AttributeList::builder().set_married().build();
+    c.bench_with_input(BenchmarkId::new("get_random_by_attribute_list", fixture.size), fixture, |b, fixture| {
+        b.iter(|| fixture.population.get_random_by_attribute_list(&wanted, &unwanted, &mut rng).map(|p| p.get_id()))
+    });
+}
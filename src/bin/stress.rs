@@ -0,0 +1,104 @@
+extern crate env_logger;
+
+extern crate procedural_towns;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use procedural_towns::world::world::World;
+use procedural_towns::utility::ResourceLocator;
+use procedural_towns::utility::application_error::ApplicationError;
+use procedural_towns::config::SimulationConfig;
+
+const DEFAULT_YEARS: u32 = 1000;
+const DEFAULT_TOWNS: u32 = 4;
+const DEFAULT_SEED: u64 = 0;
+const REPORT_INTERVAL: u32 = 100;
+
+/// Runs a seeded world headless and reports the throughput and peak memory.
+/// --years, --towns, --population, --seed, --threads, --preset and --resources change the scenario.
+pub fn main() {
+    env_logger::init();
+
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), ApplicationError> {
+    let years = get_arg_value("--years").and_then(|s| s.parse().ok()).unwrap_or(DEFAULT_YEARS);
+    let town_count = get_arg_value("--towns").and_then(|s| s.parse().ok()).unwrap_or(DEFAULT_TOWNS);
+    let resource_dir = get_arg_value("--resources");
+    let resources = ResourceLocator::new(resource_dir.as_ref().map(Path::new));
+
+    let preset = get_arg_value("--preset");
+    let mut config = SimulationConfig::from_preset(preset.as_deref().unwrap_or("default"))?;
+    config.seed = Some(get_arg_value("--seed").and_then(|s| s.parse().ok()).unwrap_or(DEFAULT_SEED));
+    if let Some(threads) = get_arg_value("--threads").and_then(|s| s.parse().ok()) {
+        config.threads = threads;
+    }
+    // the founding population of every town, the capacity still scales with the configured factors
+    if let Some(population) = get_arg_value("--population").and_then(|s| s.parse().ok()) {
+        config.town.initial_population_min = population;
+        config.town.initial_population_max = population + 1;
+    }
+
+    println!("Stress run: {} towns, {} years, seed {}, {} threads",
+        town_count,
+        years,
+        config.seed.unwrap_or_default(),
+        config.threads);
+
+    let start = Instant::now();
+    let mut world = World::create_with_config(town_count, &resources, &config)?;
+    println!("World created in {:.2}s, population {}", start.elapsed().as_secs_f64(), get_world_population(&world));
+
+    // every person alive at the end of a year counts as one simulated person-year
+    let mut person_years: u64 = 0;
+    let start = Instant::now();
+    for year in 1..=years {
+        world.progress()?;
+        person_years += get_world_population(&world);
+        if year % REPORT_INTERVAL == 0 || year == years {
+            println!("Year {:>5}: population {:>8}, {:.2}s elapsed",
+                year,
+                get_world_population(&world),
+                start.elapsed().as_secs_f64());
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    println!("Simulated {} years in {:.2}s", years, elapsed);
+    println!("Throughput: {:.2} years/s, {:.0} person-years/s",
+        years as f64 / elapsed,
+        person_years as f64 / elapsed);
+    match get_peak_memory_kb() {
+        Some(kb) => println!("Peak memory: {:.1} MiB", kb as f64 / 1024.),
+        None => println!("Peak memory: unavailable on this platform")
+    }
+    Ok(())
+}
+
+fn get_world_population(world: &World) -> u64 {
+    world.get_towns().iter()
+        .map(|town| town.get_population().size() as u64)
+        .sum()
+}
+
+// the resident set high water mark, only reported by linux
+fn get_peak_memory_kb() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status.lines()
+        .find(|line| line.starts_with("VmHWM:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse().ok())
+}
+
+fn get_arg_value(name: &str) -> Option<String> {
+    let mut args = env::args().skip_while(|arg| arg != name);
+    args.next();
+    args.next()
+}
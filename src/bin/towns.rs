@@ -5,21 +5,55 @@ extern crate chrono;
 
 extern crate procedural_towns;
 
+use std::env;
 use std::io::Write;
+use std::path::Path;
 use std::thread::sleep;
 use std::time::Duration;
 use env_logger::{ Builder, fmt::Formatter };
 use log::Record;
 
 use procedural_towns::world::world::World;
+use procedural_towns::utility::ResourceLocator;
+use procedural_towns::utility::application_error::ApplicationError;
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::utility::file_error::FileError;
 
 pub fn main() {
     init_custom_logger();
 
-    match World::create(1) {
+    let resource_dir = get_arg_value("--resources");
+    let resources = ResourceLocator::new(resource_dir.as_ref().map(Path::new));
+    // the world map and the layouts of all towns, updated after every year, are written to this directory
+    let layout_dir = get_arg_value("--layout");
+
+    let config = match load_config() {
+        Ok(config) => config,
+        Err(e) => {
+            error!("{}", e);
+            return;
+        }
+    };
+
+    match World::create_with_config(1, &resources, &config) {
         Ok(mut world) => {
+            if let Some(ref dir) = layout_dir {
+                if let Err(e) = world.save_map_svg(&Path::new(dir).join("world.svg")) {
+                    error!("{}", e);
+                    return;
+                }
+            }
             loop {
-                world.progress();
+                if let Err(e) = world.progress() {
+                    error!("{}", e);
+                    break;
+                }
+                if let Some(ref dir) = layout_dir {
+                    if let Err(e) = save_layouts(&world, Path::new(dir)) {
+                        error!("{}", e);
+                        break;
+                    }
+                }
                 sleep(Duration::from_millis(500));
             }
         },
@@ -27,6 +61,49 @@ pub fn main() {
     }
 }
 
+// --config <file> is applied on top of --preset <name>, --seed and --threads override both
+fn load_config() -> Result<SimulationConfig, ApplicationError> {
+    let preset = get_arg_value("--preset");
+    let mut config = match get_arg_value("--config") {
+        Some(path) => SimulationConfig::load(Path::new(&path), preset.as_deref())?,
+        None => SimulationConfig::from_preset(preset.as_deref().unwrap_or("default"))?
+    };
+    if let Some(seed) = get_arg_value("--seed").and_then(|s| s.parse().ok()) {
+        config.seed = Some(seed);
+    }
+    if let Some(threads) = get_arg_value("--threads").and_then(|s| s.parse().ok()) {
+        config.threads = threads;
+    }
+    Ok(config)
+}
+
+// <index>_<town>.svg and <index>_<town>.toml for every town, the index keeps towns of the same name apart
+fn save_layouts(world: &World, dir: &Path) -> Result<(), FileError> {
+    for (index, town) in world.get_towns().iter().enumerate() {
+        let title = format!("{}, {}", town.get_name(), town.get_date());
+        let file_name = format!("{}_{}", index, sanitize_file_name(town.get_name()));
+        town.get_layout().save_svg(&dir.join(format!("{}.svg", file_name)), &title)?;
+        town.get_layout().save_toml(&dir.join(format!("{}.toml", file_name)))?;
+    }
+    Ok(())
+}
+
+// letters and digits are kept, everything else could leave the directory or be invalid in a path
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_alphanumeric() || c == '-' {
+            true => c,
+            false => '_'
+        })
+        .collect()
+}
+
+fn get_arg_value(name: &str) -> Option<String> {
+    let mut args = env::args().skip_while(|arg| arg != name);
+    args.next();
+    args.next()
+}
+
 fn init_custom_logger() {
     let format = |buf: &mut Formatter, record: &Record| {
         let time = chrono::Local::now();
@@ -0,0 +1,346 @@
+use std::path::Path;
+use serde::{ Serialize, Deserialize };
+
+use crate::utility::config_error::ConfigError;
+use crate::utility::application_error::ApplicationError;
+use crate::utility::read_file::read_file;
+use crate::utility::{ DAYS_PER_MONTH, DAYS_PER_YEAR, CalendarKind };
+use crate::person::{ MarriageRules, Demography, AgeCurve, AgePreference, PregnancyModel, InheritanceRule, CultureRules, Seasonality, SeasonWeights };
+use crate::town::Festival;
+use crate::person::kinship::DEFAULT_FORBIDDEN_DEGREE;
+use crate::simulation::Pipeline;
+
+pub const PRESETS: [&str; 4] = ["default", "harsh", "prosperous", "pious"];
+const PRESET_KEY: &str = "preset";
+const MIN_WORLD_SIZE: u32 = 16;
+const MAX_OCEAN_RATIO: f32 = 0.9;
+// beyond these widowed persons could never remarry
+const MAX_REMARRIAGE_AGE_GAP: u32 = 100;
+const MAX_MOURNING_PERIOD: u32 = 5 * DAYS_PER_YEAR;
+
+/// All tunable parameters of a simulation. Missing values in a config file
+/// are taken from the preset named by the `preset` key, or from the defaults.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    // random seed of the world, a new one is drawn if missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    pub inheritance: InheritanceRule,
+    // order of the yearly phases, the built-in order if missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phases: Option<Vec<String>>,
+    // threads progressing the towns, 1 is sequential and 0 one per core
+    pub threads: usize,
+    pub town: TownConfig,
+    pub world: WorldConfig,
+    pub population: PopulationConfig,
+    pub marriage: MarriageRules,
+    pub demography: Demography,
+    pub pregnancy: PregnancyModel,
+    pub culture: CultureRules,
+    pub seasons: Seasonality,
+    pub calendar: CalendarKind,
+    // celebrated every year by every town
+    pub festivals: Vec<Festival>
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TownConfig {
+    pub initial_population_min: u32,
+    pub initial_population_max: u32,
+    // capacity is a multiple of the initial population, growing with the fertility of the site
+    pub capacity_factor_min: u32,
+    pub capacity_factor_max: u32,
+    // years of snapshots kept by every town
+    pub history_length: usize
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldConfig {
+    // size of the world map in tiles
+    pub width: u32,
+    pub height: u32,
+    // share of the map covered by the ocean
+    pub ocean_ratio: f32,
+    pub rivers: u32,
+    // towns are founded at least this many tiles apart, if there is enough land
+    pub town_distance: u32
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PopulationConfig {
+    pub target_marriage_ratio: f32,
+    pub growth_coefficient: f32,
+    pub founder_age_min: u32,
+    pub founder_age_max: u32,
+    pub forbidden_kinship_degree: u32,
+    // chance that a random pregnancy requires a married mother
+    pub mother_married_chance: f64,
+    // chance that the father of a random pregnancy may be married to someone else
+    pub father_extra_marriage_chance: f64
+}
+
+impl SimulationConfig {
+
+    pub fn from_preset(name: &str) -> Result<SimulationConfig, ConfigError> {
+        let mut config = SimulationConfig::default();
+        match name {
+            "default" => {},
+            "harsh" => {
+                config.town.initial_population_max = 25;
+                config.town.capacity_factor_max = 3;
+                config.population.growth_coefficient = 0.05;
+                config.pregnancy.miscarriage_chance = AgeCurve::new(vec![
+                    (15, 0.2), (25, 0.15), (30, 0.18), (35, 0.28), (40, 0.45), (45, 0.65)
+                ]);
+                config.pregnancy.stillbirth_chance = AgeCurve::new(vec![
+                    (15, 0.08), (25, 0.05), (35, 0.07), (45, 0.12)
+                ]);
+                config.marriage.remarriage_chance = 0.5;
+            },
+            "prosperous" => {
+                config.town.initial_population_min = 20;
+                config.town.initial_population_max = 60;
+                config.town.capacity_factor_min = 4;
+                config.town.capacity_factor_max = 8;
+                config.population.growth_coefficient = 0.15;
+                config.inheritance = InheritanceRule::Primogeniture;
+            },
+            "pious" => {
+                config.culture.conversion_chance = 0.05;
+                config.culture.cross_religion_marriage_weight = 0.05;
+                config.marriage.divorce_chance = 0.;
+                config.population.mother_married_chance = 0.9;
+                config.population.father_extra_marriage_chance = 0.05;
+            },
+            _ => return Err(ConfigError::UnknownPreset(name.to_owned()))
+        }
+        Ok(config)
+    }
+
+    /// Parses a TOML config, values are applied on top of the preset named in the
+    /// content or, if there is none, on top of the given fallback preset.
+    pub fn parse(content: &str, fallback_preset: Option<&str>) -> Result<SimulationConfig, ConfigError> {
+        let mut overrides: toml::Value = toml::from_str(content)?;
+        let preset = match overrides.as_table_mut().and_then(|t| t.remove(PRESET_KEY)) {
+            Some(toml::Value::String(name)) => name,
+            Some(value) => return Err(ConfigError::Invalid(format!("preset must be a string, got '{}'", value))),
+            None => fallback_preset.unwrap_or("default").to_owned()
+        };
+        let base = SimulationConfig::from_preset(&preset)?;
+        let mut merged = toml::Value::try_from(&base)?;
+        merge(&mut merged, overrides);
+        let config: SimulationConfig = merged.try_into()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn load(path: &Path, fallback_preset: Option<&str>) -> Result<SimulationConfig, ApplicationError> {
+        let path_str = path.to_string_lossy().into_owned();
+        let content = read_file(&path_str)?;
+        let config = SimulationConfig::parse(&content, fallback_preset)
+            .map_err(|err| ConfigError::File(path_str, Box::new(err)))?;
+        info!("Loaded simulation config '{}'", path.display());
+        Ok(config)
+    }
+
+    pub fn to_toml(&self) -> Result<String, ConfigError> {
+        // a value puts the plain keys before the tables, which toml requires
+        Ok(toml::to_string(&toml::Value::try_from(self)?)?)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(ref phases) = self.phases {
+            for name in phases {
+                check(Pipeline::is_known_phase(name), &format!("unknown phase '{}'", name))?;
+            }
+        }
+
+        let town = &self.town;
+        check(town.initial_population_min > 0, "town.initial_population_min must be positive")?;
+        check(town.initial_population_min < town.initial_population_max,
+            "town.initial_population_min must be less than town.initial_population_max")?;
+        check(town.capacity_factor_min > 0, "town.capacity_factor_min must be positive")?;
+        check(town.capacity_factor_min < town.capacity_factor_max,
+            "town.capacity_factor_min must be less than town.capacity_factor_max")?;
+
+        let world = &self.world;
+        check(world.width >= MIN_WORLD_SIZE && world.height >= MIN_WORLD_SIZE,
+            &format!("world.width and world.height must be at least {}", MIN_WORLD_SIZE))?;
+        check((0. ..MAX_OCEAN_RATIO).contains(&world.ocean_ratio),
+            &format!("world.ocean_ratio must be at least 0 and less than {}", MAX_OCEAN_RATIO))?;
+
+        let population = &self.population;
+        check_ratio(population.target_marriage_ratio as f64, "population.target_marriage_ratio")?;
+        check(population.growth_coefficient >= 0., "population.growth_coefficient must not be negative")?;
+        check(population.founder_age_min < population.founder_age_max,
+            "population.founder_age_min must be less than population.founder_age_max")?;
+        check_ratio(population.mother_married_chance, "population.mother_married_chance")?;
+        check_ratio(population.father_extra_marriage_chance, "population.father_extra_marriage_chance")?;
+
+        check_ratio(self.marriage.divorce_chance, "marriage.divorce_chance")?;
+        check_ratio(self.marriage.remarriage_chance, "marriage.remarriage_chance")?;
+        check(self.marriage.remarriage_max_age_gap <= MAX_REMARRIAGE_AGE_GAP,
+            &format!("marriage.remarriage_max_age_gap must be at most {}", MAX_REMARRIAGE_AGE_GAP))?;
+        check(self.marriage.mourning_period <= MAX_MOURNING_PERIOD,
+            &format!("marriage.mourning_period must be at most {} days", MAX_MOURNING_PERIOD))?;
+
+        check_curve(&self.demography.fertility_male, "demography.fertility_male")?;
+        check_curve(&self.demography.fertility_female, "demography.fertility_female")?;
+        check_preference(&self.demography.marriage_age_male, "demography.marriage_age_male")?;
+        check_preference(&self.demography.marriage_age_female, "demography.marriage_age_female")?;
+        check_preference(&self.demography.spouse_age_gap, "demography.spouse_age_gap")?;
+
+        let pregnancy = &self.pregnancy;
+        check(pregnancy.length / 2 > DAYS_PER_MONTH, "pregnancy.length must be longer than two months")?;
+        check(pregnancy.length_deviation > 0 && pregnancy.length_deviation < pregnancy.length / 2,
+            "pregnancy.length_deviation must be positive and less than half of pregnancy.length")?;
+        check_ratio(pregnancy.twin_chance + pregnancy.triplet_chance, "pregnancy.twin_chance + pregnancy.triplet_chance")?;
+        check_curve(&pregnancy.miscarriage_chance, "pregnancy.miscarriage_chance")?;
+        check_curve(&pregnancy.stillbirth_chance, "pregnancy.stillbirth_chance")?;
+        // the sum of both piecewise linear curves peaks at a point of one of them
+        let ages = pregnancy.miscarriage_chance.get_points().iter()
+            .chain(pregnancy.stillbirth_chance.get_points())
+            .map(|&(age, _)| age);
+        for age in ages {
+            let sum = pregnancy.miscarriage_chance.get_rate(age) + pregnancy.stillbirth_chance.get_rate(age);
+            check(sum <= 1., "pregnancy.miscarriage_chance + pregnancy.stillbirth_chance must not exceed 1")?;
+        }
+
+        check_ratio(self.culture.conversion_chance, "culture.conversion_chance")?;
+        check_ratio(self.culture.assimilation_chance, "culture.assimilation_chance")?;
+        check_ratio(self.culture.cross_culture_marriage_weight as f64, "culture.cross_culture_marriage_weight")?;
+        check_ratio(self.culture.cross_religion_marriage_weight as f64, "culture.cross_religion_marriage_weight")?;
+
+        check_weights(&self.seasons.marriage, "seasons.marriage")?;
+        check_weights(&self.seasons.conception, "seasons.conception")?;
+        check_weights(&self.seasons.mortality, "seasons.mortality")?;
+
+        if let CalendarKind::Custom(ref calendar) = self.calendar {
+            check(!calendar.months.is_empty(), "calendar.months must not be empty")?;
+            check(calendar.months.iter().all(|month| month.days > 0), "calendar.months must have at least one day each")?;
+        }
+        let calendar = self.calendar.create();
+        for festival in &self.festivals {
+            let valid_month = festival.month > 0 && festival.month <= calendar.get_months_per_year();
+            check(valid_month, &format!("festival '{}' has no month {}", festival.name, festival.month))?;
+            // the longest version of the month, shorter years celebrate on its last day
+            let max_day = (0..400)
+                .map(|year| calendar.get_days_in_month(festival.month - 1, year))
+                .max()
+                .unwrap_or(0);
+            check(festival.day > 0 && festival.day <= max_day,
+                &format!("festival '{}' has no day {} in month {}", festival.name, festival.day, festival.month))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            inheritance: InheritanceRule::default(),
+            phases: None,
+            threads: 1,
+            town: TownConfig::default(),
+            world: WorldConfig::default(),
+            population: PopulationConfig::default(),
+            marriage: MarriageRules::default(),
+            demography: Demography::default(),
+            pregnancy: PregnancyModel::default(),
+            culture: CultureRules::default(),
+            seasons: Seasonality::default(),
+            calendar: CalendarKind::default(),
+            festivals: Vec::new()
+        }
+    }
+}
+
+impl Default for TownConfig {
+    fn default() -> Self {
+        Self {
+            initial_population_min: 10,
+            initial_population_max: 40,
+            capacity_factor_min: 2,
+            capacity_factor_max: 5,
+            history_length: 0
+        }
+    }
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            width: 96,
+            height: 64,
+            ocean_ratio: 0.4,
+            rivers: 8,
+            town_distance: 10
+        }
+    }
+}
+
+impl Default for PopulationConfig {
+    fn default() -> Self {
+        Self {
+            target_marriage_ratio: 0.66,
+            growth_coefficient: 0.1,
+            founder_age_min: 5,
+            founder_age_max: 40,
+            forbidden_kinship_degree: DEFAULT_FORBIDDEN_DEGREE,
+            mother_married_chance: 0.6,
+            father_extra_marriage_chance: 0.2
+        }
+    }
+}
+
+// tables are merged recursively, everything else is replaced
+fn merge(base: &mut toml::Value, overrides: toml::Value) {
+    match (base, overrides) {
+        (toml::Value::Table(base), toml::Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        },
+        (base, overrides) => *base = overrides
+    }
+}
+
+fn check(condition: bool, msg: &str) -> Result<(), ConfigError> {
+    match condition {
+        true => Ok(()),
+        false => Err(ConfigError::Invalid(msg.to_owned()))
+    }
+}
+
+fn check_ratio(value: f64, name: &str) -> Result<(), ConfigError> {
+    check((0. ..=1.).contains(&value), &format!("{} must be between 0 and 1, got {}", name, value))
+}
+
+fn check_curve(curve: &AgeCurve, name: &str) -> Result<(), ConfigError> {
+    check(!curve.get_points().is_empty(), &format!("{} needs at least one point", name))?;
+    for &(_, rate) in curve.get_points() {
+        check_ratio(rate as f64, name)?;
+    }
+    Ok(())
+}
+
+fn check_preference(preference: &AgePreference, name: &str) -> Result<(), ConfigError> {
+    check(preference.deviation > 0., &format!("{}.deviation must be positive", name))
+}
+
+fn check_weights(weights: &SeasonWeights, name: &str) -> Result<(), ConfigError> {
+    let all = [weights.winter, weights.spring, weights.summer, weights.autumn];
+    check(all.iter().all(|&weight| weight >= 0.), &format!("{} must not be negative", name))?;
+    check(all.iter().any(|&weight| weight > 0.), &format!("{} needs a positive weight", name))
+}
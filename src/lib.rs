@@ -6,9 +6,17 @@ extern crate rand;
 extern crate chrono;
 extern crate regex;
 extern crate statrs;
+extern crate serde;
+extern crate toml;
+extern crate rhai;
+extern crate rayon;
 
 pub mod world;
 pub mod town;
 pub mod person;
 pub mod utility;
+pub mod config;
+pub mod script;
+pub mod simulation;
+pub mod map;
 
@@ -0,0 +1,91 @@
+use serde::{ Serialize, Deserialize };
+
+/// Column and row of a tile, counted from the top left.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct Position {
+    pub x: u32,
+    pub y: u32
+}
+
+/// Rectangular field of tiles, stored row by row.
+#[derive(Clone)]
+pub struct Grid<T> {
+    width: u32,
+    height: u32,
+    tiles: Vec<T>
+}
+
+impl Position {
+    pub fn new(x: u32, y: u32) -> Self {
+        Self {
+            x,
+            y
+        }
+    }
+
+    pub fn distance_squared(&self, other: Position) -> u32 {
+        let dx = self.x as i64 - other.x as i64;
+        let dy = self.y as i64 - other.y as i64;
+        (dx * dx + dy * dy) as u32
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: u32, height: u32, tile: T) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![tile; (width * height) as usize]
+        }
+    }
+}
+
+impl<T> Grid<T> {
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && x < self.width as i64 && y < self.height as i64
+    }
+
+    pub fn get(&self, position: Position) -> &T {
+        &self.tiles[self.index(position)]
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> &mut T {
+        let index = self.index(position);
+        &mut self.tiles[index]
+    }
+
+    pub fn set(&mut self, position: Position, tile: T) {
+        *self.get_mut(position) = tile;
+    }
+
+    /// The tiles above, below, left and right of the position which lie within the grid.
+    pub fn get_neighbours(&self, position: Position) -> Vec<Position> {
+        [(0, -1), (0, 1), (-1, 0), (1, 0)].iter()
+            .map(|&(dx, dy)| (position.x as i64 + dx, position.y as i64 + dy))
+            .filter(|&(x, y)| self.contains(x, y))
+            .map(|(x, y)| Position::new(x as u32, y as u32))
+            .collect()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |index| Position::new(index % width, index / width))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.tiles.chunks(self.width.max(1) as usize)
+    }
+
+    fn index(&self, position: Position) -> usize {
+        debug_assert!(position.x < self.width && position.y < self.height);
+        (position.y * self.width + position.x) as usize
+    }
+}
@@ -0,0 +1,15 @@
+pub mod grid;
+pub mod tile;
+pub mod svg;
+pub mod town_layout;
+pub mod noise;
+pub mod terrain;
+pub mod world_map;
+
+pub use self::grid::{ Grid, Position };
+pub use self::tile::Tile;
+pub use self::svg::Svg;
+pub use self::town_layout::{ TownLayout, Dwelling, Street, Quarter };
+pub use self::noise::ValueNoise;
+pub use self::terrain::Terrain;
+pub use self::world_map::{ WorldMap, Site };
@@ -0,0 +1,63 @@
+/// Seeded value noise, random values on an integer lattice smoothly interpolated in between.
+#[derive(Clone, Copy)]
+pub struct ValueNoise {
+    seed: u64
+}
+
+impl ValueNoise {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed
+        }
+    }
+
+    /// Value between 0 and 1 at the given point.
+    pub fn get(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (smoothstep(x - x0), smoothstep(y - y0));
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let top = lerp(self.lattice(x0, y0), self.lattice(x0 + 1, y0), fx);
+        let bottom = lerp(self.lattice(x0, y0 + 1), self.lattice(x0 + 1, y0 + 1), fx);
+        lerp(top, bottom, fy)
+    }
+
+    /// Sum of octaves with doubling frequency and halving amplitude, scaled to values between 0 and 1.
+    pub fn fractal(&self, x: f32, y: f32, octaves: u32) -> f32 {
+        let mut sum = 0.;
+        let mut total = 0.;
+        let mut amplitude = 1.;
+        let mut frequency = 1.;
+        for octave in 0..octaves {
+            // every octave is offset so lattice points don't line up
+            let offset = octave as f32 * 17.31;
+            sum += amplitude * self.get(x * frequency + offset, y * frequency + offset);
+            total += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.;
+        }
+        match total > 0. {
+            true => sum / total,
+            false => 0.
+        }
+    }
+
+    fn lattice(&self, x: i64, y: i64) -> f32 {
+        let hash = splitmix(self.seed ^ splitmix((x as u64).wrapping_mul(0x9e37_79b9) ^ (y as u64).wrapping_shl(32)));
+        (hash >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+fn splitmix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3. - 2. * t)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
@@ -0,0 +1,62 @@
+use std::fmt::Write;
+
+use super::{ Grid, Position };
+
+/// Minimal SVG document of rectangles and text, built in memory.
+pub struct Svg {
+    width: u32,
+    height: u32,
+    content: String
+}
+
+impl Svg {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            content: String::new()
+        }
+    }
+
+    pub fn rect(&mut self, x: u32, y: u32, width: u32, height: u32, fill: &str) {
+        let _ = writeln!(self.content, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#, x, y, width, height, fill);
+    }
+
+    pub fn text(&mut self, x: u32, y: u32, size: u32, text: &str) {
+        let _ = writeln!(self.content, r#"<text x="{}" y="{}" font-family="serif" font-size="{}" fill="black">{}</text>"#,
+            x, y, size, escape(text));
+    }
+
+    /// Draws every tile as a square of `tile_size` pixels, runs of equal colour in a row become one rectangle.
+    pub fn tiles<T, F: Fn(&T) -> &'static str>(&mut self, grid: &Grid<T>, tile_size: u32, color: F) {
+        for (y, row) in grid.rows().enumerate() {
+            let mut start = 0;
+            while start < row.len() {
+                let fill = color(&row[start]);
+                let mut end = start + 1;
+                while end < row.len() && color(&row[end]) == fill {
+                    end += 1;
+                }
+                self.rect(start as u32 * tile_size, y as u32 * tile_size, (end - start) as u32 * tile_size, tile_size, fill);
+                start = end;
+            }
+        }
+    }
+
+    /// Marks a single tile with a smaller, inset square.
+    pub fn marker(&mut self, position: Position, tile_size: u32, fill: &str) {
+        let inset = tile_size / 5;
+        self.rect(position.x * tile_size + inset, position.y * tile_size + inset, tile_size - 2 * inset, tile_size - 2 * inset, fill);
+    }
+
+    pub fn finish(self) -> String {
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n{2}</svg>\n",
+            self.width, self.height, self.content)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
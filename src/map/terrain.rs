@@ -0,0 +1,88 @@
+use std::fmt;
+use serde::{ Serialize, Deserialize };
+
+/// Biome or water of a world map tile.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Terrain {
+    Ocean,
+    Coast,
+    River,
+    Grassland,
+    Steppe,
+    Forest,
+    Marsh,
+    Hills,
+    Mountains
+}
+
+impl Terrain {
+
+    /// Share of a farming community the terrain can feed, between 0 and 1.
+    pub fn get_fertility(&self) -> f32 {
+        match *self {
+            Terrain::Ocean | Terrain::River => 0.,
+            Terrain::Coast => 0.45,
+            Terrain::Grassland => 0.8,
+            Terrain::Steppe => 0.35,
+            Terrain::Forest => 0.5,
+            Terrain::Marsh => 0.25,
+            Terrain::Hills => 0.35,
+            Terrain::Mountains => 0.05
+        }
+    }
+
+    pub fn is_water(&self) -> bool {
+        matches!(*self, Terrain::Ocean | Terrain::River)
+    }
+
+    /// Whether a town can be founded on the tile.
+    pub fn is_habitable(&self) -> bool {
+        !self.is_water() && *self != Terrain::Mountains
+    }
+
+    /// Character of the terrain in plain text maps.
+    pub fn get_symbol(&self) -> char {
+        match *self {
+            Terrain::Ocean => '~',
+            Terrain::Coast => ',',
+            Terrain::River => '=',
+            Terrain::Grassland => '.',
+            Terrain::Steppe => ':',
+            Terrain::Forest => 'f',
+            Terrain::Marsh => '%',
+            Terrain::Hills => 'n',
+            Terrain::Mountains => '^'
+        }
+    }
+
+    pub fn get_color(&self) -> &'static str {
+        match *self {
+            Terrain::Ocean => "#2f5f8f",
+            Terrain::Coast => "#e3d39a",
+            Terrain::River => "#4a86c5",
+            Terrain::Grassland => "#9bc26b",
+            Terrain::Steppe => "#c8c27a",
+            Terrain::Forest => "#4f7d3a",
+            Terrain::Marsh => "#6f8f6a",
+            Terrain::Hills => "#a39a6b",
+            Terrain::Mountains => "#8a8580"
+        }
+    }
+}
+
+impl fmt::Display for Terrain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Terrain::Ocean => write!(f, "ocean"),
+            Terrain::Coast => write!(f, "coast"),
+            Terrain::River => write!(f, "river"),
+            Terrain::Grassland => write!(f, "grassland"),
+            Terrain::Steppe => write!(f, "steppe"),
+            Terrain::Forest => write!(f, "forest"),
+            Terrain::Marsh => write!(f, "marsh"),
+            Terrain::Hills => write!(f, "hills"),
+            Terrain::Mountains => write!(f, "mountains")
+        }
+    }
+}
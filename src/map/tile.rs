@@ -0,0 +1,66 @@
+use std::fmt;
+use serde::{ Serialize, Deserialize };
+
+/// Ground of a town layout tile.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tile {
+    Grass,
+    Farmland,
+    Water,
+    Road,
+    Bridge,
+    Square,
+    Dwelling,
+    Temple
+}
+
+impl Tile {
+
+    /// Character of the tile in plain text maps.
+    pub fn get_symbol(&self) -> char {
+        match *self {
+            Tile::Grass => '.',
+            Tile::Farmland => ':',
+            Tile::Water => '~',
+            Tile::Road => '#',
+            Tile::Bridge => '=',
+            Tile::Square => 'M',
+            Tile::Dwelling => 'h',
+            Tile::Temple => 'T'
+        }
+    }
+
+    pub fn get_color(&self) -> &'static str {
+        match *self {
+            Tile::Grass => "#9bc26b",
+            Tile::Farmland => "#d9c36a",
+            Tile::Water => "#4a86c5",
+            Tile::Road => "#a08c70",
+            Tile::Bridge => "#7a5c3a",
+            Tile::Square => "#c9b89a",
+            Tile::Dwelling => "#b5563c",
+            Tile::Temple => "#e8e2d0"
+        }
+    }
+
+    /// Whether the tile can be built on or farmed.
+    pub fn is_open(&self) -> bool {
+        matches!(*self, Tile::Grass | Tile::Farmland)
+    }
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Tile::Grass => write!(f, "grass"),
+            Tile::Farmland => write!(f, "farmland"),
+            Tile::Water => write!(f, "water"),
+            Tile::Road => write!(f, "road"),
+            Tile::Bridge => write!(f, "bridge"),
+            Tile::Square => write!(f, "market square"),
+            Tile::Dwelling => write!(f, "dwelling"),
+            Tile::Temple => write!(f, "temple")
+        }
+    }
+}
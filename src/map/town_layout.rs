@@ -0,0 +1,498 @@
+use std::fmt;
+use std::path::Path;
+use std::collections::HashMap;
+use rand::Rng;
+use serde::{ Serialize, Serializer };
+
+use crate::person::Household;
+use crate::utility::file_error::FileError;
+use crate::utility::read_file::write_file;
+use super::{ Grid, Position, Tile, Svg };
+
+// the map is sized for the capacity of the town in households of this size
+const HOUSEHOLD_SIZE: u32 = 4;
+// room for a dwelling, its share of the streets and its fields
+const TILES_PER_HOUSEHOLD: u32 = 16;
+const MIN_SIZE: u32 = 32;
+const SQUARE_SIZE: u32 = 4;
+const RIVER_WIDTH: u32 = 2;
+// streets run along every fourth row and column from the main roads through the square
+const BLOCK_SIZE: i64 = 4;
+const FIELDS_PER_HOUSEHOLD: u32 = 4;
+// grass between the outermost buildings and the fields
+const FIELD_MARGIN: u32 = 2;
+const SVG_TILE_SIZE: u32 = 8;
+
+/// Streets, market square, river, buildings and fields of a town on a grid of tiles.
+/// The town grows outward from the square as households need dwellings.
+#[derive(Clone, Serialize)]
+pub struct TownLayout {
+    width: u32,
+    height: u32,
+    #[serde(rename = "rows", serialize_with = "serialize_rows")]
+    tiles: Grid<Tile>,
+    // top left tile of the market square
+    square: Position,
+    // empty lists would follow the tables above, which TOML does not allow
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    dwellings: Vec<Dwelling>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    temples: Vec<Position>,
+    #[serde(skip)]
+    center: Position,
+    // building sites along planned streets, nearest to the square first
+    #[serde(skip)]
+    plots: Vec<Position>,
+    #[serde(skip)]
+    next_plot: usize,
+    // planned street tiles, nearest to the square first
+    #[serde(skip)]
+    streets: Vec<Position>,
+    #[serde(skip)]
+    laid_streets: usize,
+    // all tiles, nearest to the square first
+    #[serde(skip)]
+    by_distance: Vec<Position>,
+    // distance of the outermost building from the square
+    #[serde(skip)]
+    radius: u32
+}
+
+/// Home of a household, vacant once the household is gone.
+#[derive(Clone, Serialize)]
+pub struct Dwelling {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    household: Option<u32>,
+    residents: Vec<u32>,
+    position: Position
+}
+
+/// Row or column along which a street runs, counted from the main roads so that it
+/// stays the same street when the map grows.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Street {
+    Row(i32),
+    Column(i32)
+}
+
+/// Part of the town between the main roads.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Quarter {
+    NorthWest,
+    NorthEast,
+    SouthWest,
+    SouthEast
+}
+
+impl TownLayout {
+
+    /// Plans a town with room for the given number of inhabitants, optionally on a river.
+    pub fn generate<R: Rng + ?Sized>(capacity: u32, river: bool, rng: &mut R) -> Self {
+        let households = capacity.div_ceil(HOUSEHOLD_SIZE).max(1);
+        let size = (((households * TILES_PER_HOUSEHOLD) as f64).sqrt().ceil() as u32).max(MIN_SIZE);
+        let center = Position::new(size / 2, size / 2);
+        let square = Position::new(center.x - SQUARE_SIZE / 2, center.y - SQUARE_SIZE / 2);
+        let mut tiles = Grid::new(size, size, Tile::Grass);
+
+        for y in square.y..square.y + SQUARE_SIZE {
+            for x in square.x..square.x + SQUARE_SIZE {
+                tiles.set(Position::new(x, y), Tile::Square);
+            }
+        }
+        if river {
+            add_river(&mut tiles, center, rng);
+        }
+        // the main roads lead out of town from the start
+        for position in tiles.positions().filter(|&position| is_main_road(position, center)) {
+            let tile = match *tiles.get(position) {
+                Tile::Square => continue,
+                Tile::Water => Tile::Bridge,
+                _ => Tile::Road
+            };
+            tiles.set(position, tile);
+        }
+
+        let mut layout = Self {
+            width: size,
+            height: size,
+            tiles,
+            square,
+            dwellings: Vec::new(),
+            temples: Vec::new(),
+            center,
+            plots: Vec::new(),
+            next_plot: 0,
+            streets: Vec::new(),
+            laid_streets: 0,
+            by_distance: Vec::new(),
+            radius: SQUARE_SIZE
+        };
+        layout.plan(rng);
+        layout.lay_streets();
+        layout
+    }
+
+    /// Moves the households into dwellings and builds missing dwellings and temples.
+    /// A dwelling stays with the household of its first remaining resident.
+    /// The map grows on all sides when it has no plots left.
+    pub fn update<R: Rng + ?Sized>(&mut self, households: &[Household], temple_count: usize, rng: &mut R) {
+        let household_of: HashMap<u32, usize> = households.iter()
+            .enumerate()
+            .flat_map(|(index, household)| household.get_members().iter().map(move |&id| (id, index)))
+            .collect();
+        let mut housed = vec![false; households.len()];
+        for dwelling in self.dwellings.iter_mut() {
+            let kept = dwelling.residents.iter()
+                .filter_map(|id| household_of.get(id).cloned())
+                .find(|&index| !housed[index]);
+            match kept {
+                Some(index) => {
+                    housed[index] = true;
+                    dwelling.move_in(&households[index]);
+                },
+                None => dwelling.vacate()
+            }
+        }
+
+        let mut vacant = self.dwellings.iter()
+            .enumerate()
+            .filter(|(_, dwelling)| dwelling.is_vacant())
+            .map(|(index, _)| index)
+            .collect::<Vec<usize>>()
+            .into_iter();
+        for (household, _) in households.iter().zip(housed).filter(|&(_, housed)| !housed) {
+            if let Some(dwelling) = vacant.next() {
+                self.dwellings[dwelling].move_in(household);
+                continue;
+            }
+            let mut dwelling = Dwelling::new(self.build(Tile::Dwelling, rng));
+            dwelling.move_in(household);
+            self.dwellings.push(dwelling);
+        }
+
+        while self.temples.len() < temple_count {
+            let position = self.build(Tile::Temple, rng);
+            self.temples.push(position);
+        }
+        self.lay_streets();
+        self.update_fields(households.len() as u32);
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_tiles(&self) -> &Grid<Tile> {
+        &self.tiles
+    }
+
+    pub fn get_square(&self) -> Position {
+        self.square
+    }
+
+    pub fn get_dwellings(&self) -> &[Dwelling] {
+        &self.dwellings
+    }
+
+    pub fn get_dwelling_of(&self, head: u32) -> Option<&Dwelling> {
+        self.dwellings.iter().find(|dwelling| dwelling.household == Some(head))
+    }
+
+    pub fn get_vacant_count(&self) -> usize {
+        self.dwellings.iter().filter(|dwelling| dwelling.is_vacant()).count()
+    }
+
+    pub fn get_temples(&self) -> &[Position] {
+        &self.temples
+    }
+
+    /// Streets with at least one laid tile, the main roads first and then outward from the square.
+    pub fn get_streets(&self) -> Vec<Street> {
+        let mut streets = vec![Street::Row(0), Street::Column(0)];
+        for position in self.streets[..self.laid_streets].iter() {
+            let dx = position.x as i32 - self.center.x as i32;
+            let dy = position.y as i32 - self.center.y as i32;
+            if dy % BLOCK_SIZE as i32 == 0 && !streets.contains(&Street::Row(dy)) {
+                streets.push(Street::Row(dy));
+            }
+            if dx % BLOCK_SIZE as i32 == 0 && !streets.contains(&Street::Column(dx)) {
+                streets.push(Street::Column(dx));
+            }
+        }
+        streets
+    }
+
+    /// Quarter of the position, tiles on the main roads count to the south and east.
+    pub fn get_quarter(&self, position: Position) -> Quarter {
+        match (position.y < self.center.y, position.x < self.center.x) {
+            (true, true) => Quarter::NorthWest,
+            (true, false) => Quarter::NorthEast,
+            (false, true) => Quarter::SouthWest,
+            (false, false) => Quarter::SouthEast
+        }
+    }
+
+    /// Quarters with at least one dwelling, in the order they were settled.
+    pub fn get_settled_quarters(&self) -> Vec<Quarter> {
+        let mut quarters = Vec::new();
+        for dwelling in self.dwellings.iter() {
+            let quarter = self.get_quarter(dwelling.position);
+            if !quarters.contains(&quarter) {
+                quarters.push(quarter);
+            }
+        }
+        quarters
+    }
+
+    /// Rows of tile symbols, see `Tile::get_symbol`.
+    pub fn get_rows(&self) -> Vec<String> {
+        self.tiles.rows()
+            .map(|row| row.iter().map(Tile::get_symbol).collect())
+            .collect()
+    }
+
+    pub fn to_toml(&self) -> Result<String, FileError> {
+        toml::to_string(self).map_err(|err| FileError::Serialize(err.to_string()))
+    }
+
+    /// Renders the layout with the given title, every tile as a square of 8 pixels.
+    pub fn to_svg(&self, title: &str) -> String {
+        let mut svg = Svg::new(self.width * SVG_TILE_SIZE, self.height * SVG_TILE_SIZE);
+        svg.tiles(&self.tiles, SVG_TILE_SIZE, Tile::get_color);
+        for dwelling in self.dwellings.iter() {
+            let roof = match dwelling.is_vacant() {
+                true => "#8c8c8c",
+                false => "#7a2e1e"
+            };
+            svg.marker(dwelling.position, SVG_TILE_SIZE, roof);
+        }
+        for &temple in self.temples.iter() {
+            svg.marker(temple, SVG_TILE_SIZE, "#c9a227");
+        }
+        svg.text(SVG_TILE_SIZE / 2, 2 * SVG_TILE_SIZE, 2 * SVG_TILE_SIZE, title);
+        svg.finish()
+    }
+
+    pub fn save_svg(&self, path: &Path, title: &str) -> Result<(), FileError> {
+        write_file(&path.to_string_lossy(), &self.to_svg(title))
+    }
+
+    pub fn save_toml(&self, path: &Path) -> Result<(), FileError> {
+        write_file(&path.to_string_lossy(), &self.to_toml()?)
+    }
+
+    // takes the next open plot, growing the map when all are taken
+    fn build<R: Rng + ?Sized>(&mut self, tile: Tile, rng: &mut R) -> Position {
+        loop {
+            while let Some(&position) = self.plots.get(self.next_plot) {
+                self.next_plot += 1;
+                if self.tiles.get(position).is_open() {
+                    self.tiles.set(position, tile);
+                    self.radius = self.radius.max(get_distance(position, self.center));
+                    return position;
+                }
+            }
+            self.grow(rng);
+        }
+    }
+
+    // doubles the size of the map, with the old map in the middle and the river and main roads running on
+    fn grow<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let margin = self.width / 2;
+        let size = self.width + 2 * margin;
+        let shift = |position: Position| Position::new(position.x + margin, position.y + margin);
+        let mut tiles = Grid::new(size, size, Tile::Grass);
+        for position in self.tiles.positions() {
+            tiles.set(shift(position), *self.tiles.get(position));
+        }
+        for x in 0..self.width {
+            if *self.tiles.get(Position::new(x, 0)) == Tile::Water {
+                for y in 0..margin {
+                    tiles.set(Position::new(x + margin, y), Tile::Water);
+                }
+            }
+            if *self.tiles.get(Position::new(x, self.height - 1)) == Tile::Water {
+                for y in margin + self.height..size {
+                    tiles.set(Position::new(x + margin, y), Tile::Water);
+                }
+            }
+        }
+        debug!("Growing the map from {} to {} tiles across", self.width, size);
+
+        self.center = shift(self.center);
+        self.square = shift(self.square);
+        for dwelling in self.dwellings.iter_mut() {
+            dwelling.position = shift(dwelling.position);
+        }
+        for temple in self.temples.iter_mut() {
+            *temple = shift(*temple);
+        }
+        let old = (margin..margin + self.width, margin..margin + self.height);
+        for position in tiles.positions().filter(|&position| is_main_road(position, self.center)) {
+            if old.0.contains(&position.x) && old.1.contains(&position.y) {
+                continue;
+            }
+            let tile = match *tiles.get(position) {
+                Tile::Water => Tile::Bridge,
+                _ => Tile::Road
+            };
+            tiles.set(position, tile);
+        }
+        self.width = size;
+        self.height = size;
+        self.tiles = tiles;
+        self.plan(rng);
+    }
+
+    // plans streets and plots of the whole map, only plots which are still open are taken
+    fn plan<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let tiles = &self.tiles;
+        let center = self.center;
+        self.streets = sort_by_distance(tiles.positions()
+            .filter(|&position| is_planned_street(tiles, position, center) && !is_main_road(position, center))
+            .collect(), center, |_| 0);
+        self.laid_streets = 0;
+        self.plots = sort_by_distance(tiles.positions()
+            .filter(|&position| tiles.get(position).is_open() && !is_planned_street(tiles, position, center))
+            .filter(|&position| tiles.get_neighbours(position).iter()
+                .any(|&neighbour| is_planned_street(tiles, neighbour, center)))
+            .collect(), center, |_| rng.gen_range(0, (BLOCK_SIZE * BLOCK_SIZE) as u32));
+        self.next_plot = 0;
+        self.by_distance = sort_by_distance(tiles.positions().collect(), center, |_| 0);
+    }
+
+    // streets reaching one tile beyond the outermost building, every plot touches one of them
+    fn lay_streets(&mut self) {
+        let reach = (self.radius + 1) * (self.radius + 1);
+        while let Some(&position) = self.streets.get(self.laid_streets) {
+            if position.distance_squared(self.center) > reach {
+                break;
+            }
+            self.tiles.set(position, Tile::Road);
+            self.laid_streets += 1;
+        }
+    }
+
+    // fields of all households closest to the town, beyond a margin of grass
+    fn update_fields(&mut self, household_count: u32) {
+        let mut wanted = household_count * FIELDS_PER_HOUSEHOLD;
+        let start = (self.radius + FIELD_MARGIN) * (self.radius + FIELD_MARGIN);
+        for &position in self.by_distance.iter() {
+            if !self.tiles.get(position).is_open() {
+                continue;
+            }
+            let tile = match wanted > 0 && position.distance_squared(self.center) > start {
+                true => {
+                    wanted -= 1;
+                    Tile::Farmland
+                },
+                false => Tile::Grass
+            };
+            self.tiles.set(position, tile);
+        }
+    }
+}
+
+impl Dwelling {
+    fn new(position: Position) -> Self {
+        Self {
+            household: None,
+            residents: Vec::new(),
+            position
+        }
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.position
+    }
+
+    /// Head of the household living here.
+    pub fn get_household(&self) -> Option<u32> {
+        self.household
+    }
+
+    pub fn get_residents(&self) -> &[u32] {
+        &self.residents
+    }
+
+    pub fn is_vacant(&self) -> bool {
+        self.household.is_none()
+    }
+
+    fn move_in(&mut self, household: &Household) {
+        self.household = Some(household.get_head());
+        self.residents = household.get_members().to_vec();
+    }
+
+    fn vacate(&mut self) {
+        self.household = None;
+        self.residents.clear();
+    }
+}
+
+impl fmt::Display for TownLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.get_rows() {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+// a river meanders from north to south, keeping clear of the square
+fn add_river<R: Rng + ?Sized>(tiles: &mut Grid<Tile>, center: Position, rng: &mut R) {
+    let size = tiles.get_width() as i64;
+    let clearance = (SQUARE_SIZE + 2) as i64;
+    let side = match rng.gen_bool(0.5) {
+        true => 1,
+        false => -1
+    };
+    let mut offset = rng.gen_range(clearance, (size / 4).max(clearance + 1));
+    for y in 0..tiles.get_height() {
+        offset = (offset + rng.gen_range(-1, 2)).max(clearance).min(size / 2 - RIVER_WIDTH as i64 - 1);
+        let x = center.x as i64 + side * offset;
+        for dx in 0..RIVER_WIDTH as i64 {
+            if tiles.contains(x + dx, y as i64) {
+                tiles.set(Position::new((x + dx) as u32, y), Tile::Water);
+            }
+        }
+    }
+}
+
+fn is_main_road(position: Position, center: Position) -> bool {
+    position.x == center.x || position.y == center.y
+}
+
+// streets end at the river banks, only the main roads cross it
+fn is_planned_street(tiles: &Grid<Tile>, position: Position, center: Position) -> bool {
+    let dx = position.x as i64 - center.x as i64;
+    let dy = position.y as i64 - center.y as i64;
+    match *tiles.get(position) {
+        Tile::Square => false,
+        Tile::Water | Tile::Bridge => is_main_road(position, center),
+        _ => dx % BLOCK_SIZE == 0 || dy % BLOCK_SIZE == 0
+    }
+}
+
+// nearest first, with an added random distance for a less regular outline
+fn sort_by_distance<F: FnMut(Position) -> u32>(positions: Vec<Position>, center: Position, mut jitter: F) -> Vec<Position> {
+    let mut keyed: Vec<(u32, Position)> = positions.into_iter()
+        .map(|position| (position.distance_squared(center) + jitter(position), position))
+        .collect();
+    keyed.sort_by_key(|&(key, position)| (key, position.y, position.x));
+    keyed.into_iter().map(|(_, position)| position).collect()
+}
+
+fn get_distance(position: Position, center: Position) -> u32 {
+    (position.distance_squared(center) as f64).sqrt().ceil() as u32
+}
+
+fn serialize_rows<S: Serializer>(tiles: &Grid<Tile>, serializer: S) -> Result<S::Ok, S::Error> {
+    let rows: Vec<String> = tiles.rows()
+        .map(|row| row.iter().map(Tile::get_symbol).collect())
+        .collect();
+    rows.serialize(serializer)
+}
@@ -0,0 +1,277 @@
+use std::fmt;
+use std::path::Path;
+use std::collections::HashSet;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::config::WorldConfig;
+use crate::utility::file_error::FileError;
+use crate::utility::read_file::write_file;
+use super::{ Grid, Position, Terrain, ValueNoise, Svg };
+
+// tiles per lattice cell of the lowest octave
+const ELEVATION_SCALE: f32 = 24.;
+const MOISTURE_SCALE: f32 = 16.;
+const OCTAVES: u32 = 5;
+// share of the elevation given by the distance from the map border, which keeps the border at sea
+const FALLOFF_WEIGHT: f32 = 0.4;
+// elevation above sea level, relative to the highest peak
+const HILLS_ELEVATION: f32 = 0.55;
+const MOUNTAINS_ELEVATION: f32 = 0.75;
+const MARSH_ELEVATION: f32 = 0.15;
+const FOREST_MOISTURE: f32 = 0.55;
+const MARSH_MOISTURE: f32 = 0.6;
+const STEPPE_MOISTURE: f32 = 0.42;
+// rivers rise in the hills or mountains
+const SOURCE_ELEVATION: f32 = 0.45;
+// farmland around a site which counts towards its fertility
+const SITE_RADIUS: i64 = 2;
+const RIVER_BONUS: f32 = 0.3;
+const COAST_BONUS: f32 = 0.15;
+const SVG_TILE_SIZE: u32 = 8;
+
+/// Terrain of the world, generated from seeded elevation and moisture noise.
+#[derive(Clone)]
+pub struct WorldMap {
+    // 0 at sea level and 1 at the highest peak, negative below the sea
+    elevation: Grid<f32>,
+    terrain: Grid<Terrain>
+}
+
+/// Location of a town and how well it can feed its people.
+#[derive(Clone, Copy, Debug)]
+pub struct Site {
+    pub position: Position,
+    pub terrain: Terrain,
+    // between 0 and 1, grows with the farmland around the site, a river or the sea
+    pub fertility: f32,
+    pub river: bool,
+    pub coast: bool
+}
+
+impl WorldMap {
+
+    pub fn generate<R: Rng + ?Sized>(config: &WorldConfig, rng: &mut R) -> Self {
+        let elevation_noise = ValueNoise::new(rng.gen());
+        let moisture_noise = ValueNoise::new(rng.gen());
+        let (width, height) = (config.width, config.height);
+
+        let mut elevation = Grid::new(width, height, 0.);
+        for position in elevation.positions() {
+            let nx = 2. * position.x as f32 / width as f32 - 1.;
+            let ny = 2. * position.y as f32 / height as f32 - 1.;
+            let falloff = 1. - (nx * nx + ny * ny).sqrt().min(1.);
+            let noise = elevation_noise.fractal(position.x as f32 / ELEVATION_SCALE, position.y as f32 / ELEVATION_SCALE, OCTAVES);
+            elevation.set(position, (1. - FALLOFF_WEIGHT) * noise + FALLOFF_WEIGHT * falloff);
+        }
+        normalize_to_sea_level(&mut elevation, config.ocean_ratio);
+
+        let mut terrain = Grid::new(width, height, Terrain::Ocean);
+        for position in terrain.positions() {
+            let moisture = moisture_noise.fractal(position.x as f32 / MOISTURE_SCALE, position.y as f32 / MOISTURE_SCALE, OCTAVES);
+            terrain.set(position, get_biome(*elevation.get(position), moisture));
+        }
+        let coasts: Vec<Position> = terrain.positions()
+            .filter(|&position| !terrain.get(position).is_water())
+            .filter(|&position| terrain.get_neighbours(position).iter().any(|&n| *terrain.get(n) == Terrain::Ocean))
+            .collect();
+        for position in coasts {
+            terrain.set(position, Terrain::Coast);
+        }
+
+        let mut map = Self {
+            elevation,
+            terrain
+        };
+        map.add_rivers(config.rivers, rng);
+        map
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.terrain.get_width()
+    }
+    pub fn get_height(&self) -> u32 {
+        self.terrain.get_height()
+    }
+
+    pub fn get_terrain(&self) -> &Grid<Terrain> {
+        &self.terrain
+    }
+
+    pub fn get_elevation(&self) -> &Grid<f32> {
+        &self.elevation
+    }
+
+    pub fn get_site(&self, position: Position) -> Site {
+        let mut fertility = 0.;
+        let mut tiles = 0;
+        let mut river = false;
+        let mut coast = false;
+        for (x, y) in get_area(position, SITE_RADIUS) {
+            if !self.terrain.contains(x, y) {
+                continue;
+            }
+            let terrain = *self.terrain.get(Position::new(x as u32, y as u32));
+            fertility += terrain.get_fertility();
+            tiles += 1;
+            // water right next to the town
+            if (x - position.x as i64).abs() <= 1 && (y - position.y as i64).abs() <= 1 {
+                river |= terrain == Terrain::River;
+                coast |= terrain == Terrain::Ocean;
+            }
+        }
+        let mut score = fertility / tiles.max(1) as f32;
+        if river {
+            score += RIVER_BONUS;
+        }
+        if coast {
+            score += COAST_BONUS;
+        }
+        Site {
+            position,
+            terrain: *self.terrain.get(position),
+            fertility: score.min(1.),
+            river,
+            coast
+        }
+    }
+
+    /// The most fertile sites at least `min_distance` tiles apart, best first. The distance is
+    /// halved until enough sites are found, fewer are returned if there are not enough habitable tiles.
+    pub fn find_sites(&self, count: u32, min_distance: u32) -> Vec<Site> {
+        let mut candidates: Vec<Site> = self.terrain.positions()
+            .filter(|&position| self.terrain.get(position).is_habitable())
+            .map(|position| self.get_site(position))
+            .collect();
+        candidates.sort_by(|a, b| b.fertility.partial_cmp(&a.fertility)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| (a.position.y, a.position.x).cmp(&(b.position.y, b.position.x))));
+
+        let mut distance = min_distance;
+        loop {
+            let mut sites: Vec<Site> = Vec::new();
+            for candidate in candidates.iter() {
+                if sites.len() as u32 == count {
+                    break;
+                }
+                if sites.iter().all(|site| site.position != candidate.position && site.distance(candidate) >= distance as f32) {
+                    sites.push(*candidate);
+                }
+            }
+            if sites.len() as u32 == count || distance == 0 {
+                return sites;
+            }
+            debug!("Only {} of {} town sites are {} tiles apart", sites.len(), count, distance);
+            distance /= 2;
+        }
+    }
+
+    pub fn to_svg(&self, towns: &[(&str, Position)]) -> String {
+        let mut svg = Svg::new(self.get_width() * SVG_TILE_SIZE, self.get_height() * SVG_TILE_SIZE);
+        svg.tiles(&self.terrain, SVG_TILE_SIZE, Terrain::get_color);
+        for &(name, position) in towns {
+            svg.marker(position, SVG_TILE_SIZE, "#b5563c");
+            svg.text((position.x + 1) * SVG_TILE_SIZE, position.y * SVG_TILE_SIZE, 2 * SVG_TILE_SIZE, name);
+        }
+        svg.finish()
+    }
+
+    /// Renders the map with the given towns, every tile as a square of 8 pixels.
+    pub fn save_svg(&self, path: &Path, towns: &[(&str, Position)]) -> Result<(), FileError> {
+        write_file(&path.to_string_lossy(), &self.to_svg(towns))
+    }
+
+    // rivers flow downhill from random sources in the highlands until they reach the sea or another river
+    fn add_rivers<R: Rng + ?Sized>(&mut self, count: u32, rng: &mut R) {
+        let mut sources: Vec<Position> = self.terrain.positions()
+            .filter(|&position| *self.elevation.get(position) > SOURCE_ELEVATION)
+            .collect();
+        sources.shuffle(rng);
+        for &source in sources.iter().take(count as usize) {
+            let mut visited = HashSet::new();
+            let mut position = source;
+            loop {
+                match *self.terrain.get(position) {
+                    Terrain::Ocean => break,
+                    Terrain::River if position != source => break,
+                    _ => self.terrain.set(position, Terrain::River)
+                }
+                visited.insert(position);
+                // out of a sink the river carves through the lowest neighbour
+                let next = self.terrain.get_neighbours(position).into_iter()
+                    .filter(|n| !visited.contains(n))
+                    .min_by(|&a, &b| self.elevation.get(a).partial_cmp(self.elevation.get(b)).unwrap_or(std::cmp::Ordering::Equal));
+                match next {
+                    Some(next) => position = next,
+                    None => break
+                }
+            }
+        }
+    }
+}
+
+impl Site {
+    /// Distance to the other site in tiles.
+    pub fn distance(&self, other: &Site) -> f32 {
+        (self.position.distance_squared(other.position) as f32).sqrt()
+    }
+}
+
+impl fmt::Display for Site {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.terrain {
+            Terrain::Coast => write!(f, "on the coast")?,
+            terrain => write!(f, "in the {}", terrain)?
+        }
+        match (self.river, self.coast && self.terrain != Terrain::Coast) {
+            (true, _) => write!(f, " by a river"),
+            (false, true) => write!(f, " by the sea"),
+            (false, false) => Ok(())
+        }
+    }
+}
+
+impl fmt::Display for WorldMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.terrain.rows() {
+            writeln!(f, "{}", row.iter().map(Terrain::get_symbol).collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+// shifts the elevation so the given share of tiles lies below 0, and scales the land to peak at 1
+fn normalize_to_sea_level(elevation: &mut Grid<f32>, ocean_ratio: f32) {
+    let mut sorted: Vec<f32> = elevation.positions().map(|position| *elevation.get(position)).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let index = ((sorted.len() as f32 * ocean_ratio) as usize).min(sorted.len() - 1);
+    let sea_level = sorted[index];
+    let peak = (sorted[sorted.len() - 1] - sea_level).max(f32::EPSILON);
+    for position in elevation.positions() {
+        let value = (*elevation.get(position) - sea_level) / peak;
+        elevation.set(position, value);
+    }
+}
+
+fn get_biome(elevation: f32, moisture: f32) -> Terrain {
+    if elevation < 0. {
+        Terrain::Ocean
+    } else if elevation > MOUNTAINS_ELEVATION {
+        Terrain::Mountains
+    } else if elevation > HILLS_ELEVATION {
+        Terrain::Hills
+    } else if moisture > MARSH_MOISTURE && elevation < MARSH_ELEVATION {
+        Terrain::Marsh
+    } else if moisture > FOREST_MOISTURE {
+        Terrain::Forest
+    } else if moisture < STEPPE_MOISTURE {
+        Terrain::Steppe
+    } else {
+        Terrain::Grassland
+    }
+}
+
+fn get_area(center: Position, radius: i64) -> impl Iterator<Item = (i64, i64)> {
+    let (cx, cy) = (center.x as i64, center.y as i64);
+    (cy - radius..=cy + radius).flat_map(move |y| (cx - radius..=cx + radius).map(move |x| (x, y)))
+}
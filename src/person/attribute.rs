@@ -1,12 +1,15 @@
 use crate::utility::Date;
+use super::PregnancyOutcome;
 
 #[derive(Eq, Clone, Copy)]
 pub enum Attribute {
     Male,
     Female,
     Married(u32),
-    Pregnant { father_id: u32, birth: Date, count: u32 },
-    Fertile
+    Pregnant { father_id: u32, birth: Date, count: u32, outcome: PregnancyOutcome },
+    Fertile,
+    Mourning(Date),
+    Postpartum(Date)
 }
 
 impl PartialEq for Attribute {
@@ -17,6 +20,8 @@ impl PartialEq for Attribute {
             (Attribute::Married(_), Attribute::Married(_)) => true,
             (Attribute::Pregnant { .. }, Attribute::Pregnant { .. }) => true,
             (Attribute::Fertile, Attribute::Fertile) => true,
+            (Attribute::Mourning(_), Attribute::Mourning(_)) => true,
+            (Attribute::Postpartum(_), Attribute::Postpartum(_)) => true,
             (_, _) => false
         }
     }
@@ -1,11 +1,12 @@
 use crate::utility::Date;
-use super::Attribute;
+use super::{ Attribute, PregnancyOutcome };
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct AttributeList {
     attributes: Vec<Attribute>
 }
 
+#[derive(Default)]
 pub struct AttributeListBuilder {
     list: AttributeList
 }
@@ -31,6 +32,10 @@ impl AttributeList {
         self.has_attribute(&Attribute::Fertile)
     }
 
+    pub fn is_mourning(&self) -> bool {
+        self.has_attribute(&Attribute::Mourning(Date::default()))
+    }
+
     pub fn get_spouse(&self) -> Option<u32> {
         match self.get_attr(&Attribute::Married(0)) {
             Some(&Attribute::Married(spouse_id)) => Some(spouse_id),
@@ -39,6 +44,22 @@ impl AttributeList {
         }
     }
 
+    pub fn get_mourning_end(&self) -> Option<Date> {
+        match self.get_attr(&Attribute::Mourning(Date::default())) {
+            Some(&Attribute::Mourning(until)) => Some(until),
+            Some(_) => unreachable!("Attribute should have been Attribute::Mourning"),
+            None => None,
+        }
+    }
+
+    pub fn get_due_date(&self) -> Option<Date> {
+        match self.get_attr(&Attribute::Pregnant { father_id: 0, birth: Date::default(), count: 0, outcome: PregnancyOutcome::LiveBirth }) {
+            Some(&Attribute::Pregnant { birth, .. }) => Some(birth),
+            Some(_) => unreachable!("Attribute should have been Attribute::Pregnant"),
+            None => None,
+        }
+    }
+
     pub fn set_male(&mut self) {
         if !self.is_male() {
             self.remove_attribute(&Attribute::Female);
@@ -61,12 +82,21 @@ impl AttributeList {
         self.remove_attribute(&Attribute::Married(0))
     }
 
-    pub fn set_pregnant(&mut self, father_id: u32, birth: Date, count: u32) {
-        self.attributes.push(Attribute::Pregnant { father_id: father_id, birth: birth, count: count });
+    pub fn set_pregnant(&mut self, father_id: u32, birth: Date, count: u32, outcome: PregnancyOutcome) {
+        self.attributes.push(Attribute::Pregnant { father_id, birth, count, outcome });
     }
 
     pub fn pop_pregnancy(&mut self) -> Option<Attribute> {
-        self.remove_attribute(&Attribute::Pregnant { father_id: 0, birth: Date::default(), count: 0 })
+        self.remove_attribute(&Attribute::Pregnant { father_id: 0, birth: Date::default(), count: 0, outcome: PregnancyOutcome::LiveBirth })
+    }
+
+    pub fn set_postpartum(&mut self, until: Date) {
+        self.remove_attribute(&Attribute::Postpartum(Date::default()));
+        self.attributes.push(Attribute::Postpartum(until));
+    }
+
+    pub fn is_postpartum(&self) -> bool {
+        self.has_attribute(&Attribute::Postpartum(Date::default()))
     }
 
     pub fn set_fertile(&mut self) {
@@ -79,6 +109,33 @@ impl AttributeList {
         self.remove_attribute(&Attribute::Fertile);
     }
 
+    pub fn set_mourning(&mut self, until: Date) {
+        self.remove_attribute(&Attribute::Mourning(Date::default()));
+        self.attributes.push(Attribute::Mourning(until));
+    }
+
+    pub fn clear_mourning(&mut self) {
+        self.remove_attribute(&Attribute::Mourning(Date::default()));
+    }
+
+    /// Whether any time limited attribute ended before the given date.
+    pub fn has_expired(&self, today: Date) -> bool {
+        self.attributes.iter().any(|attr| match *attr {
+            Attribute::Mourning(until) | Attribute::Postpartum(until) => until <= today,
+            _ => false
+        })
+    }
+
+    /// Removes all time limited attributes which ended before the given date.
+    pub fn clear_expired(&mut self, today: Date) -> bool {
+        let count = self.attributes.len();
+        self.attributes.retain(|attr| match *attr {
+            Attribute::Mourning(until) | Attribute::Postpartum(until) => until > today,
+            _ => true
+        });
+        count != self.attributes.len()
+    }
+
     pub fn add(&mut self, attr: Attribute) {
         self.attributes.push(attr);
     }
@@ -130,15 +187,6 @@ impl AttributeList {
     }
 }
 
-
-impl Default for AttributeList {
-    fn default() -> AttributeList {
-        Self {
-            attributes: Vec::new()
-        }
-    }
-}
-
 impl AttributeListBuilder {
     pub fn set_male(mut self) -> Self {
         self.list.set_male();
@@ -153,24 +201,23 @@ impl AttributeListBuilder {
         self
     }
     pub fn set_pregnant(mut self) -> Self {
-        self.list.set_pregnant(0, Date::default(), 0);
+        self.list.set_pregnant(0, Date::default(), 0, PregnancyOutcome::LiveBirth);
+        self
+    }
+    pub fn set_postpartum(mut self) -> Self {
+        self.list.set_postpartum(Date::default());
         self
     }
     pub fn set_fertile(mut self) -> Self {
         self.list.set_fertile();
         self
     }
+    pub fn set_mourning(mut self) -> Self {
+        self.list.set_mourning(Date::default());
+        self
+    }
 
     pub fn build(self) -> AttributeList {
         self.list
     }
 }
-
-
-impl Default for AttributeListBuilder {
-    fn default() -> AttributeListBuilder {
-        Self {
-            list: AttributeList::default()
-        }
-    }
-}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use serde::{ Serialize, Deserialize };
+
+/// Index of a culture registered in the PersonGenerator.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Culture(pub u16);
+
+/// Index of a religion registered in the PersonGenerator.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Religion(pub u16);
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CultureRules {
+    // yearly chance to adopt the religion of the town majority
+    pub conversion_chance: f64,
+    // yearly chance to adopt the culture of the town majority
+    pub assimilation_chance: f64,
+    // relative preference for a partner of another culture or religion
+    pub cross_culture_marriage_weight: f32,
+    pub cross_religion_marriage_weight: f32
+}
+
+impl CultureRules {
+    pub fn get_marriage_weight(&self, a: (Culture, Religion), b: (Culture, Religion)) -> f32 {
+        let culture_weight = if a.0 == b.0 { 1. } else { self.cross_culture_marriage_weight };
+        let religion_weight = if a.1 == b.1 { 1. } else { self.cross_religion_marriage_weight };
+        culture_weight * religion_weight
+    }
+}
+
+impl Default for CultureRules {
+    fn default() -> Self {
+        Self {
+            conversion_chance: 0.02,
+            assimilation_chance: 0.01,
+            cross_culture_marriage_weight: 0.3,
+            cross_religion_marriage_weight: 0.2
+        }
+    }
+}
+
+/// Counts occurrences, most common first.
+pub fn count_by<T, I>(values: I) -> Vec<(T, u32)>
+where T: Ord + Hash + Copy, I: Iterator<Item = T> {
+    let mut counts: HashMap<T, u32> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    let mut counts: Vec<(T, u32)> = counts.into_iter().collect();
+    counts.sort_by_key(|&(value, count)| (std::cmp::Reverse(count), value));
+    counts
+}
@@ -0,0 +1,123 @@
+use serde::{ Serialize, Deserialize };
+
+use super::{ Person, MIN_MARRIAGE_AGE };
+
+/// Rate by age, linearly interpolated between (age, rate) points and
+/// held constant outside of them. Fertility curves use 1.0 for the most fertile age.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "Vec<(u32, f32)>", into = "Vec<(u32, f32)>")]
+pub struct AgeCurve {
+    points: Vec<(u32, f32)>
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct AgePreference {
+    pub mean: f32,
+    pub deviation: f32
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Demography {
+    pub fertility_male: AgeCurve,
+    pub fertility_female: AgeCurve,
+    pub marriage_age_male: AgePreference,
+    pub marriage_age_female: AgePreference,
+    // age of the groom minus age of the bride
+    pub spouse_age_gap: AgePreference
+}
+
+impl AgeCurve {
+    pub fn new(mut points: Vec<(u32, f32)>) -> Self {
+        points.sort_by_key(|&(age, _)| age);
+        Self {
+            points
+        }
+    }
+
+    pub fn get_points(&self) -> &[(u32, f32)] {
+        &self.points
+    }
+
+    pub fn get_rate(&self, age: u32) -> f32 {
+        let upper = match self.points.iter().position(|&(a, _)| a >= age) {
+            Some(i) => i,
+            None => return self.points.last().map_or(0., |&(_, rate)| rate)
+        };
+        let (upper_age, upper_rate) = self.points[upper];
+        if upper_age == age || upper == 0 {
+            return upper_rate;
+        }
+        let (lower_age, lower_rate) = self.points[upper - 1];
+        let t = (age - lower_age) as f32 / (upper_age - lower_age) as f32;
+        lower_rate + t * (upper_rate - lower_rate)
+    }
+}
+
+impl From<Vec<(u32, f32)>> for AgeCurve {
+    fn from(points: Vec<(u32, f32)>) -> Self {
+        AgeCurve::new(points)
+    }
+}
+
+impl From<AgeCurve> for Vec<(u32, f32)> {
+    fn from(curve: AgeCurve) -> Self {
+        curve.points
+    }
+}
+
+impl AgePreference {
+    pub fn new(mean: f32, deviation: f32) -> Self {
+        Self {
+            mean,
+            deviation
+        }
+    }
+
+    // unnormalized gaussian, 1.0 at the preferred age
+    pub fn get_weight(&self, value: f32) -> f32 {
+        let z = (value - self.mean) / self.deviation;
+        f32::exp(-0.5 * z * z)
+    }
+}
+
+impl Demography {
+    pub fn get_fertility(&self, person: &Person, age: u32) -> f32 {
+        if person.get_attr().is_male() {
+            self.fertility_male.get_rate(age)
+        } else {
+            self.fertility_female.get_rate(age)
+        }
+    }
+
+    /// Preference for marrying at the given age, none below the marriage age.
+    pub fn get_marriage_age_weight(&self, person: &Person, age: u32) -> f32 {
+        if age < MIN_MARRIAGE_AGE {
+            0.
+        } else if person.get_attr().is_male() {
+            self.marriage_age_male.get_weight(age as f32)
+        } else {
+            self.marriage_age_female.get_weight(age as f32)
+        }
+    }
+
+    pub fn get_age_gap_weight(&self, groom_age: u32, bride_age: u32) -> f32 {
+        self.spouse_age_gap.get_weight(groom_age as f32 - bride_age as f32)
+    }
+}
+
+impl Default for Demography {
+    fn default() -> Self {
+        Self {
+            fertility_male: AgeCurve::new(vec![
+                (14, 0.), (18, 0.6), (25, 1.), (35, 0.9), (45, 0.6), (55, 0.3), (65, 0.)
+            ]),
+            fertility_female: AgeCurve::new(vec![
+                (13, 0.), (16, 0.4), (20, 0.9), (25, 1.), (30, 0.9), (35, 0.65), (40, 0.3), (45, 0.05), (48, 0.)
+            ]),
+            marriage_age_male: AgePreference::new(25., 5.),
+            marriage_age_female: AgePreference::new(20., 4.),
+            spouse_age_gap: AgePreference::new(3., 4.)
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use crate::utility::Date;
+use super::Person;
+
+/// Something that happened to the population during the last update.
+/// Persons are shared as they were at the time of the event, so they stay available after a death.
+#[derive(Clone)]
+pub enum Event {
+    Birth { child: Arc<Person> },
+    Death { person: Arc<Person>, date: Date },
+    Marriage { groom: Arc<Person>, bride: Arc<Person> },
+    Pregnancy { mother: Arc<Person>, father_id: u32, due: Date },
+    // raised by rule scripts
+    Custom { name: String, person: Option<Arc<Person>> }
+}
+
+/// Receives the events of a population while it is updated, right after each change.
+pub trait EventListener: Send + Sync {
+    fn on_event(&self, event: &Event);
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use crate::utility::Date;
+use super::Person;
+
+// children below this age live with a parent
+pub const HOUSEHOLD_AGE: u32 = 16;
+
+/// Persons sharing a home, the head is listed first.
+#[derive(Clone)]
+pub struct Household {
+    head: u32,
+    members: Vec<u32>
+}
+
+impl Household {
+
+    pub fn get_head(&self) -> u32 {
+        self.head
+    }
+
+    pub fn get_members(&self) -> &[u32] {
+        &self.members
+    }
+
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+}
+
+/// Groups the persons into households. Married couples live together, usually headed by the husband,
+/// and children live with their mother or, if she is gone, their father. Everyone else heads a household.
+pub fn group_households<'a, I: Iterator<Item = &'a Person>>(persons: I, today: Date) -> Vec<Household> {
+    let persons: HashMap<u32, &Person> = persons.map(|p| (p.get_id(), p)).collect();
+    let mut households: HashMap<u32, Vec<u32>> = HashMap::new();
+    for person in persons.values() {
+        let head = get_head(person, &persons, today);
+        households.entry(head).or_default().push(person.get_id());
+    }
+
+    let mut households: Vec<Household> = households.into_iter()
+        .map(|(head, mut members)| {
+            members.sort_by_key(|&id| (id != head, id));
+            Household {
+                head,
+                members
+            }
+        })
+        .collect();
+    households.sort_by_key(|household| household.head);
+    households
+}
+
+fn get_head(person: &Person, persons: &HashMap<u32, &Person>, today: Date) -> u32 {
+    if person.get_age(&today) < HOUSEHOLD_AGE {
+        let parent = person.get_mother()
+            .and_then(|id| persons.get(&id))
+            .or_else(|| person.get_father().and_then(|id| persons.get(&id)));
+        if let Some(parent) = parent {
+            return get_spouse_head(parent, persons);
+        }
+    }
+    get_spouse_head(person, persons)
+}
+
+// the husband heads a married couple living in town, otherwise the spouse with the lower id
+fn get_spouse_head(person: &Person, persons: &HashMap<u32, &Person>) -> u32 {
+    match person.get_spouse().and_then(|id| persons.get(&id)) {
+        Some(spouse) => [person, *spouse].iter()
+            .map(|p| (!p.get_attr().is_male(), p.get_id()))
+            .min()
+            .map_or(person.get_id(), |(_, id)| id),
+        None => person.get_id()
+    }
+}
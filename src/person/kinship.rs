@@ -0,0 +1,172 @@
+use std::collections::{ HashMap, VecDeque };
+
+use super::Person;
+
+// ancestors further back are ignored when computing kinship coefficients
+const KINSHIP_MAX_GENERATIONS: u32 = 8;
+
+// parent-child = 1, siblings = 2, uncle/niece = 3, first cousins = 4
+pub const DEFAULT_FORBIDDEN_DEGREE: u32 = 4;
+
+#[derive(Clone, Default)]
+pub struct Lineage {
+    parents: HashMap<u32, (Option<u32>, Option<u32>)>,
+    children: HashMap<u32, Vec<u32>>,
+    // ids in the order they were recorded, to undo the latest records
+    recorded: Vec<u32>
+}
+
+impl Lineage {
+
+    /// Records the parents of the person. Parents must have lower ids than their children,
+    /// which holds as ids are handed out in order of birth.
+    pub fn record(&mut self, person: &Person) {
+        debug_assert!(person.get_father().into_iter().chain(person.get_mother()).all(|parent| parent < person.get_id()),
+            "parent of {} has a higher id", person.get_id());
+        let id = person.get_id();
+        if self.parents.contains_key(&id) {
+            return;
+        }
+        self.parents.insert(id, (person.get_father(), person.get_mother()));
+        for parent in person.get_father().into_iter().chain(person.get_mother()) {
+            self.children.entry(parent).or_default().push(id);
+        }
+        self.recorded.push(id);
+    }
+
+    /// Forgets the latest records until only `size` persons are left.
+    pub fn truncate(&mut self, size: usize) {
+        while self.recorded.len() > size {
+            let id = self.recorded.pop().unwrap();
+            let (father, mother) = self.parents.remove(&id).unwrap_or((None, None));
+            // a child recorded later than this one has been removed already
+            for parent in father.into_iter().chain(mother) {
+                if let Some(children) = self.children.get_mut(&parent) {
+                    children.pop();
+                    if children.is_empty() {
+                        self.children.remove(&parent);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn get_parents(&self, id: u32) -> (Option<u32>, Option<u32>) {
+        match self.parents.get(&id) {
+            Some(&parents) => parents,
+            None => (None, None)
+        }
+    }
+
+    /// Children of the person ever recorded, in order of their ids.
+    pub fn get_children(&self, id: u32) -> &[u32] {
+        match self.children.get(&id) {
+            Some(children) => children,
+            None => &[]
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.recorded.len()
+    }
+
+    /// Degree of relationship by counting generations up to the closest common ancestor
+    /// and down again. Returns None if no common ancestor is found within max_degree.
+    pub fn degree_of_relationship(&self, a: u32, b: u32, max_degree: u32) -> Option<u32> {
+        let ancestors_a = self.get_ancestors(a, max_degree);
+        let ancestors_b = self.get_ancestors(b, max_degree);
+        ancestors_a.iter()
+            .filter_map(|(id, dist_a)| ancestors_b.get(id).map(|dist_b| dist_a + dist_b))
+            .filter(|&degree| degree <= max_degree)
+            .min()
+    }
+
+    /// Relatives of the person within max_degree and their degree of relationship,
+    /// nearest first and by id within a degree. The person itself is left out.
+    pub fn get_relatives(&self, id: u32, max_degree: u32) -> Vec<(u32, u32)> {
+        let mut degrees: HashMap<u32, u32> = HashMap::new();
+        for (&ancestor, &up) in self.get_ancestors(id, max_degree).iter() {
+            // down again from every ancestor
+            let mut queue = VecDeque::new();
+            queue.push_back((ancestor, up));
+            while let Some((curr, degree)) = queue.pop_front() {
+                let known = degrees.entry(curr).or_insert(degree);
+                *known = (*known).min(degree);
+                if degree < max_degree {
+                    queue.extend(self.get_children(curr).iter().map(|&child| (child, degree + 1)));
+                }
+            }
+        }
+        degrees.remove(&id);
+        let mut relatives: Vec<(u32, u32)> = degrees.into_iter().collect();
+        relatives.sort_by_key(|&(relative, degree)| (degree, relative));
+        relatives
+    }
+
+    /// Whether the first person is a parent, grandparent and so on of the second, up to max_depth generations.
+    pub fn is_ancestor(&self, ancestor: u32, id: u32, max_depth: u32) -> bool {
+        ancestor != id && self.get_ancestors(id, max_depth).contains_key(&ancestor)
+    }
+
+    pub fn is_related(&self, a: u32, b: u32, max_degree: u32) -> bool {
+        self.degree_of_relationship(a, b, max_degree).is_some()
+    }
+
+    /// Probability that two alleles drawn from a and b are identical by descent.
+    pub fn coefficient_of_kinship(&self, a: u32, b: u32) -> f32 {
+        self.kinship_recursive(a, b, KINSHIP_MAX_GENERATIONS)
+    }
+
+    /// Inbreeding coefficient of a person, which equals the kinship of its parents.
+    pub fn inbreeding_coefficient(&self, id: u32) -> f32 {
+        match self.get_parents(id) {
+            (Some(father), Some(mother)) => self.coefficient_of_kinship(father, mother),
+            _ => 0.
+        }
+    }
+
+    // ids are handed out in order, so the higher id can never be an ancestor of the lower one
+    fn kinship_recursive(&self, a: u32, b: u32, depth: u32) -> f32 {
+        if depth == 0 {
+            return 0.;
+        }
+        if a == b {
+            let inbreeding = match self.get_parents(a) {
+                (Some(father), Some(mother)) => self.kinship_recursive(father, mother, depth - 1),
+                _ => 0.
+            };
+            return 0.5 * (1. + inbreeding);
+        }
+        let (younger, older) = if a > b { (a, b) } else { (b, a) };
+        let (father, mother) = self.get_parents(younger);
+        let via_father = match father {
+            Some(f) => self.kinship_recursive(f, older, depth - 1),
+            None => 0.
+        };
+        let via_mother = match mother {
+            Some(m) => self.kinship_recursive(m, older, depth - 1),
+            None => 0.
+        };
+        0.5 * (via_father + via_mother)
+    }
+
+    // includes the person itself with distance 0
+    fn get_ancestors(&self, id: u32, max_depth: u32) -> HashMap<u32, u32> {
+        let mut ancestors = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((id, 0));
+        while let Some((curr, dist)) = queue.pop_front() {
+            if ancestors.contains_key(&curr) {
+                continue;
+            }
+            ancestors.insert(curr, dist);
+            if dist < max_depth {
+                let (father, mother) = self.get_parents(curr);
+                father.into_iter()
+                    .chain(mother)
+                    .for_each(|p| queue.push_back((p, dist + 1)));
+            }
+        }
+        ancestors
+    }
+}
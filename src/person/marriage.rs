@@ -0,0 +1,88 @@
+use std::fmt;
+use serde::{ Serialize, Deserialize };
+
+use crate::utility::{ Date, DAYS_PER_YEAR };
+
+/// Age in years from which a person may marry.
+pub const MIN_MARRIAGE_AGE: u32 = 14;
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum MarriageEnd {
+    Divorce,
+    Widowed
+}
+
+#[derive(Clone, Copy)]
+pub struct Marriage {
+    spouse_id: u32,
+    start: Date,
+    end: Option<(Date, MarriageEnd)>
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarriageRules {
+    pub divorce_chance: f64,
+    pub mourning_period: u32,
+    pub remarriage_chance: f64,
+    pub remarriage_max_age: u32,
+    pub remarriage_max_age_gap: u32
+}
+
+impl Marriage {
+    pub fn new(spouse_id: u32, start: Date) -> Self {
+        Self {
+            spouse_id,
+            start,
+            end: None
+        }
+    }
+
+    pub fn get_spouse_id(&self) -> u32 {
+        self.spouse_id
+    }
+    pub fn get_start(&self) -> Date {
+        self.start
+    }
+    pub fn get_end(&self) -> Option<(Date, MarriageEnd)> {
+        self.end
+    }
+    pub fn is_active(&self) -> bool {
+        self.end.is_none()
+    }
+
+    pub fn close(&mut self, date: Date, reason: MarriageEnd) {
+        debug_assert!(self.is_active());
+        self.end = Some((date, reason));
+    }
+}
+
+impl Default for MarriageRules {
+    fn default() -> Self {
+        Self {
+            divorce_chance: 0.002,
+            mourning_period: DAYS_PER_YEAR,
+            remarriage_chance: 0.3,
+            remarriage_max_age: 60,
+            remarriage_max_age_gap: 10
+        }
+    }
+}
+
+impl fmt::Display for MarriageEnd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarriageEnd::Divorce => write!(f, "divorce"),
+            MarriageEnd::Widowed => write!(f, "widowed")
+        }
+    }
+}
+
+impl fmt::Display for Marriage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.end {
+            Some((end, reason)) => write!(f, "spouse = {}, {} - {} ({})", self.spouse_id, self.start, end, reason),
+            None => write!(f, "spouse = {}, since {}", self.spouse_id, self.start)
+        }
+    }
+}
@@ -1,11 +1,37 @@
+// part of the public path, as in person::person::Person
+#[allow(clippy::module_inception)]
 pub mod person;
 pub mod population;
 pub mod person_generator;
 pub mod attribute;
 pub mod attribute_list;
+pub mod kinship;
+pub mod marriage;
+pub mod demography;
+pub mod pregnancy;
+pub mod wealth;
+pub mod occupation;
+pub mod social_class;
+pub mod culture;
+pub mod name_pack;
+pub mod event;
+pub mod seasonality;
+pub mod household;
 
 pub use self::person::Person;
 pub use self::population::Population;
 pub use self::person_generator::PersonGenerator;
 pub use self::attribute::Attribute;
 pub use self::attribute_list::AttributeList;
+pub use self::kinship::Lineage;
+pub use self::event::{ Event, EventListener };
+pub use self::marriage::{ Marriage, MarriageEnd, MarriageRules, MIN_MARRIAGE_AGE };
+pub use self::demography::{ Demography, AgeCurve, AgePreference };
+pub use self::pregnancy::{ PregnancyModel, PregnancyOutcome };
+pub use self::wealth::{ Wealth, InheritanceRule };
+pub use self::occupation::Occupation;
+pub use self::social_class::SocialClass;
+pub use self::culture::{ Culture, Religion, CultureRules };
+pub use self::name_pack::NamePack;
+pub use self::seasonality::{ Seasonality, SeasonWeights };
+pub use self::household::Household;
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use lazy_static::lazy_static;
+
+use crate::utility::application_error::ApplicationError;
+use crate::utility::file_error::FileError;
+use crate::utility::read_file::{ read_file, read_non_empty_file };
+use crate::utility::{ Symbol, SymbolTable };
+
+const MANIFEST_FILE: &str = "manifest.txt";
+const LEGACY_MALE_FILE: &str = "names_first_male.txt";
+
+macro_rules! include_pack {
+    ($dir:literal, $symbols:expr) => {
+        NamePack::parse(
+            parse_manifest(include_str!(concat!("../../resources/cultures/", $dir, "/manifest.txt")))
+                .get("name").map(|s| s.as_str()).unwrap_or($dir),
+            include_str!(concat!("../../resources/cultures/", $dir, "/first_male.txt")),
+            include_str!(concat!("../../resources/cultures/", $dir, "/first_female.txt")),
+            include_str!(concat!("../../resources/cultures/", $dir, "/last.txt")),
+            $symbols)
+            .with_nicknames(include_str!(concat!("../../resources/cultures/", $dir, "/nicknames.txt")), $symbols)
+            .with_town_names(
+                include_str!(concat!("../../resources/cultures/", $dir, "/town_prefixes.txt")),
+                include_str!(concat!("../../resources/cultures/", $dir, "/town_suffixes.txt")))
+            .with_places(include_str!(concat!("../../resources/cultures/", $dir, "/places.txt")))
+    }
+}
+
+// used for every kind of place a pack leaves out
+const DEFAULT_PLACES: &str = "
+street: {} Lane, {} Street, {} Row, {} Way
+square: {} Market, Market Square, {} Square
+inn: The {} Inn, The Golden {}, The {} and Crown
+church: St. {}'s Church, Chapel of St. {}, St. {}'s
+district: {} End, {}side, Old {}
+emblems: Boar, Stag, Swan, Plough, Anchor, Bell, Lion
+trades: Miller, Smith, Baker, Tanner, Weaver, Cooper, Mason
+river: River, Bridge, Ford
+sea: Harbour, Sea, Quay
+coast: Shore, Sand
+grassland: Meadow, Field, Green
+steppe: Heath, Moor
+forest: Oak, Wood, Ash
+marsh: Fen, Willow
+hills: Hill, Ridge, Down
+mountains: Crag, Stone
+";
+
+lazy_static! {
+    static ref DEFAULT_PLACE_WORDS: HashMap<String, Vec<String>> = parse_places(DEFAULT_PLACES);
+}
+
+/// Names with optional frequencies, one per line as `Name` or `Name,count`.
+#[derive(Default)]
+pub struct WeightedNames {
+    names: Vec<Symbol>,
+    cumulative: Vec<u32>
+}
+
+/// All names of one culture, loaded from a directory with a manifest.
+pub struct NamePack {
+    name: String,
+    first_names_male: WeightedNames,
+    first_names_female: WeightedNames,
+    last_names: WeightedNames,
+    nicknames: HashMap<Symbol, Vec<Symbol>>,
+    town_prefixes: Vec<String>,
+    town_suffixes: Vec<String>,
+    // templates and words for naming places, by kind
+    places: HashMap<String, Vec<String>>
+}
+
+impl WeightedNames {
+
+    pub fn parse(content: &str, symbols: &mut SymbolTable) -> Self {
+        let mut list = WeightedNames::default();
+        for line in content.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let (name, weight) = match line.rfind(',') {
+                Some(i) => match line[i + 1..].trim().parse::<u32>() {
+                    Ok(weight) => (line[..i].trim(), weight),
+                    Err(_) => {
+                        warn!("Invalid frequency in line '{}', using 1", line);
+                        (line[..i].trim(), 1)
+                    }
+                },
+                None => (line, 1)
+            };
+            list.push(symbols.intern(name), weight);
+        }
+        list
+    }
+
+    pub fn push(&mut self, name: Symbol, weight: u32) {
+        let total = self.get_total_weight();
+        self.names.push(name);
+        self.cumulative.push(total + weight.max(1));
+    }
+
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Symbol> {
+        if self.names.is_empty() {
+            return None;
+        }
+        let target = rng.gen_range(0, self.get_total_weight());
+        let index = match self.cumulative.binary_search(&target) {
+            Ok(i) => i + 1,
+            Err(i) => i
+        };
+        self.names.get(index).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn get_names(&self) -> &[Symbol] {
+        &self.names
+    }
+
+    fn get_total_weight(&self) -> u32 {
+        self.cumulative.last().cloned().unwrap_or(0)
+    }
+}
+
+impl NamePack {
+
+    /// Parses the name lists of a pack, the names are added to the given table.
+    pub fn parse(name: &str, first_names_male: &str, first_names_female: &str, last_names: &str, symbols: &mut SymbolTable) -> NamePack {
+        NamePack {
+            name: name.to_owned(),
+            first_names_male: WeightedNames::parse(first_names_male, symbols),
+            first_names_female: WeightedNames::parse(first_names_female, symbols),
+            last_names: WeightedNames::parse(last_names, symbols),
+            nicknames: HashMap::new(),
+            town_prefixes: Vec::new(),
+            town_suffixes: Vec::new(),
+            places: HashMap::new()
+        }
+    }
+
+    /// Adds the nicknames of first names, one `Name: Nick, Other Nick` line per name.
+    pub fn with_nicknames(mut self, nicknames: &str, symbols: &mut SymbolTable) -> NamePack {
+        self.nicknames = parse_nicknames(nicknames, symbols);
+        self
+    }
+
+    /// Adds the beginnings and endings of town names, one per line.
+    pub fn with_town_names(mut self, town_prefixes: &str, town_suffixes: &str) -> NamePack {
+        self.town_prefixes = parse_lines(town_prefixes);
+        self.town_suffixes = parse_lines(town_suffixes);
+        self
+    }
+
+    /// Adds the templates and words for naming places, one `kind: word, word` line per kind.
+    pub fn with_places(mut self, places: &str) -> NamePack {
+        self.places = parse_places(places);
+        self
+    }
+
+    /// Loads a pack from a directory. Missing manifest entries use the default file names,
+    /// missing optional files leave the corresponding list empty.
+    pub fn load(dir: &Path, symbols: &mut SymbolTable) -> Result<NamePack, ApplicationError> {
+        let default_name = dir.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| String::from("Unknown"));
+        let manifest = match dir.join(MANIFEST_FILE).exists() {
+            true => parse_manifest(&read_file(&path_str(&dir.join(MANIFEST_FILE)))?),
+            false => HashMap::new()
+        };
+        let file = |key: &str, default: &str| dir.join(manifest.get(key).map(|s| s.as_str()).unwrap_or(default));
+
+        let pack = NamePack::parse(
+            manifest.get("name").unwrap_or(&default_name),
+            &read_non_empty_file(&path_str(&file("first_names_male", "first_male.txt")))?,
+            &read_non_empty_file(&path_str(&file("first_names_female", "first_female.txt")))?,
+            &read_non_empty_file(&path_str(&file("last_names", "last.txt")))?,
+            symbols)
+            .with_nicknames(&read_optional(&file("nicknames", "nicknames.txt"))?, symbols)
+            .with_town_names(
+                &read_optional(&file("town_prefixes", "town_prefixes.txt"))?,
+                &read_optional(&file("town_suffixes", "town_suffixes.txt"))?)
+            .with_places(&read_optional(&file("places", "places.txt"))?);
+        info!("Loaded name pack '{}': {} male, {} female first names, {} last names",
+            pack.name,
+            pack.first_names_male.len(),
+            pack.first_names_female.len(),
+            pack.last_names.len());
+        Ok(pack)
+    }
+
+    /// Loads the plain name lists used before name packs existed.
+    pub fn load_legacy(dir: &Path, symbols: &mut SymbolTable) -> Result<NamePack, ApplicationError> {
+        Ok(NamePack::parse(
+            "Common",
+            &read_non_empty_file(&path_str(&dir.join(LEGACY_MALE_FILE)))?,
+            &read_non_empty_file(&path_str(&dir.join("names_first_female.txt")))?,
+            &read_non_empty_file(&path_str(&dir.join("names_last.txt")))?,
+            symbols))
+    }
+
+    /// Checks if the directory contains the plain name lists.
+    pub fn has_legacy(dir: &Path) -> bool {
+        dir.join(LEGACY_MALE_FILE).is_file()
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn get_first_names_male(&self) -> &WeightedNames {
+        &self.first_names_male
+    }
+    pub fn get_first_names_female(&self) -> &WeightedNames {
+        &self.first_names_female
+    }
+    pub fn get_last_names(&self) -> &WeightedNames {
+        &self.last_names
+    }
+
+    pub fn get_random_nickname<R: Rng + ?Sized>(&self, first_name: Symbol, rng: &mut R) -> Option<Symbol> {
+        self.nicknames.get(&first_name)
+            .and_then(|nicks| nicks.choose(rng))
+            .cloned()
+    }
+
+    /// Templates or words of the given kind of place, empty if the pack has none.
+    pub fn get_place_words(&self, kind: &str) -> &[String] {
+        match self.places.get(kind) {
+            Some(words) => words,
+            None => &[]
+        }
+    }
+
+    pub fn generate_town_name<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<String> {
+        match (self.town_prefixes.choose(rng), self.town_suffixes.choose(rng)) {
+            (Some(prefix), Some(suffix)) => Some(format!("{}{}", prefix, suffix)),
+            (_, _) => None
+        }
+    }
+}
+
+/// Templates or words of the given kind of place used if a pack has none.
+pub fn get_default_place_words(kind: &str) -> &'static [String] {
+    match DEFAULT_PLACE_WORDS.get(kind) {
+        Some(words) => words,
+        None => &[]
+    }
+}
+
+/// Loads every pack directory below the given directory, sorted by directory name.
+pub fn load_packs(dir: &Path, symbols: &mut SymbolTable) -> Result<Vec<NamePack>, ApplicationError> {
+    let mut dirs: Vec<_> = fs::read_dir(dir)
+        .map_err(|err| FileError::Read(path_str(dir), err))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs.iter()
+        .map(|d| NamePack::load(d, symbols))
+        .collect()
+}
+
+/// Name packs compiled into the binary, used when no resource directory is available.
+pub fn load_embedded_packs(symbols: &mut SymbolTable) -> Vec<NamePack> {
+    vec![
+        include_pack!("latin", symbols),
+        include_pack!("norse", symbols),
+        include_pack!("slavic", symbols)
+    ]
+}
+
+fn parse_manifest(content: &str) -> HashMap<String, String> {
+    let mut manifest = HashMap::new();
+    for line in content.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        match line.find('=') {
+            Some(i) => {
+                manifest.insert(line[..i].trim().to_owned(), line[i + 1..].trim().to_owned());
+            },
+            None => warn!("Ignoring invalid manifest line '{}'", line)
+        }
+    }
+    manifest
+}
+
+// one name per line: `Name: Nick, Other Nick`
+fn parse_nicknames(content: &str, symbols: &mut SymbolTable) -> HashMap<Symbol, Vec<Symbol>> {
+    let mut nicknames = HashMap::new();
+    for line in content.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        match line.find(':') {
+            Some(i) => {
+                let nicks = line[i + 1..].split(',')
+                    .map(|n| n.trim())
+                    .filter(|n| !n.is_empty())
+                    .map(|n| symbols.intern(n))
+                    .collect();
+                nicknames.insert(symbols.intern(line[..i].trim()), nicks);
+            },
+            None => warn!("Ignoring invalid nickname line '{}'", line)
+        }
+    }
+    nicknames
+}
+
+// one kind per line: `kind: Word, {} Template`
+fn parse_places(content: &str) -> HashMap<String, Vec<String>> {
+    let mut places = HashMap::new();
+    for line in content.lines().map(|l| l.trim()).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        match line.find(':') {
+            Some(i) => {
+                let words = line[i + 1..].split(',')
+                    .map(|w| w.trim())
+                    .filter(|w| !w.is_empty())
+                    .map(String::from)
+                    .collect();
+                places.insert(line[..i].trim().to_owned(), words);
+            },
+            None => warn!("Ignoring invalid place line '{}'", line)
+        }
+    }
+    places
+}
+
+fn parse_lines(content: &str) -> Vec<String> {
+    content.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn read_optional(path: &Path) -> Result<String, ApplicationError> {
+    match path.exists() {
+        true => Ok(read_file(&path_str(path))?),
+        false => Ok(String::new())
+    }
+}
+
+fn path_str(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
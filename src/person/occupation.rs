@@ -0,0 +1,55 @@
+use std::fmt;
+use rand::Rng;
+
+use super::Wealth;
+
+// land a farmer's household works itself, the rest is let to tenants
+const FARMED_LAND: u32 = 10;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Occupation {
+    Farmer,
+    Laborer,
+    Craftsman,
+    Merchant
+}
+
+impl Occupation {
+
+    /// Landowners farm their own land, everybody else takes what work there is.
+    pub fn choose<R: Rng + ?Sized>(wealth: &Wealth, rng: &mut R) -> Occupation {
+        if wealth.get_land() > 0 {
+            return Occupation::Farmer;
+        }
+        match rng.gen_range(0, 10) {
+            0 ..= 4 => Occupation::Laborer,
+            5 ..= 7 => Occupation::Craftsman,
+            8 => Occupation::Merchant,
+            _ => Occupation::Farmer
+        }
+    }
+
+    pub fn get_yearly_income(&self, wealth: &Wealth) -> u32 {
+        match self {
+            // rents grow with the square root of the let land, so large estates don't compound
+            Occupation::Farmer => {
+                let land = wealth.get_land();
+                2 + 2 * land.min(FARMED_LAND) + 2 * land.saturating_sub(FARMED_LAND).isqrt()
+            },
+            Occupation::Laborer => 3,
+            Occupation::Craftsman => 6,
+            Occupation::Merchant => 10
+        }
+    }
+}
+
+impl fmt::Display for Occupation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Occupation::Farmer => write!(f, "farmer"),
+            Occupation::Laborer => write!(f, "laborer"),
+            Occupation::Craftsman => write!(f, "craftsman"),
+            Occupation::Merchant => write!(f, "merchant")
+        }
+    }
+}
@@ -1,72 +1,156 @@
 use std::fmt;
+use std::collections::BTreeMap;
 
 use crate::utility::date::Date;
-use super::AttributeList;
+use crate::utility::{ Symbol, SymbolTable };
+use super::{ AttributeList, Wealth, Occupation, SocialClass, Culture, Religion };
+use super::marriage::{ Marriage, MarriageEnd, MIN_MARRIAGE_AGE };
 
 #[derive(Clone)]
 pub struct Person {
     id: u32,
     birthday: Date,
-    first_name: String,
-    last_name: String,
+    first_name: Symbol,
+    last_name: Symbol,
+    nickname: Option<Symbol>,
     father: Option<u32>,
     mother: Option<u32>,
-    attributes: AttributeList
+    attributes: AttributeList,
+    marriage_history: Vec<Marriage>,
+    parity: u32,
+    last_delivery: Option<Date>,
+    wealth: Wealth,
+    occupation: Option<Occupation>,
+    social_class: SocialClass,
+    culture: Culture,
+    religion: Religion,
+    // values set by rule scripts
+    custom: BTreeMap<String, i64>
 }
 
 impl Person {
     pub fn new(id: u32) -> Self {
         Self {
-            id: id,
+            id,
             birthday: Date::default(),
-            first_name: String::from("Unknown"),
-            last_name: String::from("McUnknownFace"),
+            first_name: Symbol::UNKNOWN,
+            last_name: Symbol::UNKNOWN,
+            nickname: None,
             father: None,
             mother: None,
-            attributes: AttributeList::default()
+            attributes: AttributeList::default(),
+            marriage_history: Vec::new(),
+            parity: 0,
+            last_delivery: None,
+            wealth: Wealth::default(),
+            occupation: None,
+            social_class: SocialClass::default(),
+            culture: Culture::default(),
+            religion: Religion::default(),
+            custom: BTreeMap::new()
         }
     }
 
     pub fn get_id(&self) -> u32 {
         self.id
     }
-    pub fn get_first_name(&self) -> &str {
-        &self.first_name
+    pub fn get_first_name(&self) -> Symbol {
+        self.first_name
     }
-    pub fn get_last_name(&self) -> &str {
-        &self.last_name
+    pub fn get_last_name(&self) -> Symbol {
+        self.last_name
     }
-    pub fn get_full_name(&self) -> String {
-        format!("{} {}", self.first_name, self.last_name)
+    pub fn get_nickname(&self) -> Option<Symbol> {
+        self.nickname
+    }
+    /// First and last name, resolved through the table of the generator that created the person.
+    pub fn get_full_name(&self, symbols: &SymbolTable) -> String {
+        format!("{} {}", symbols.resolve(self.first_name), symbols.resolve(self.last_name))
     }
     pub fn get_birthday(&self) -> Date {
         self.birthday
     }
+    pub fn get_father(&self) -> Option<u32> {
+        self.father
+    }
+    pub fn get_mother(&self) -> Option<u32> {
+        self.mother
+    }
 
     pub fn get_spouse(&self) -> Option<u32> {
         self.attributes.get_spouse()
     }
+    pub fn get_marriage_history(&self) -> &[Marriage] {
+        &self.marriage_history
+    }
+    pub fn was_married(&self) -> bool {
+        !self.marriage_history.is_empty()
+    }
+    pub fn get_wealth(&self) -> &Wealth {
+        &self.wealth
+    }
+    pub fn get_wealth_mut(&mut self) -> &mut Wealth {
+        &mut self.wealth
+    }
+    pub fn get_occupation(&self) -> Option<Occupation> {
+        self.occupation
+    }
+    pub fn get_social_class(&self) -> SocialClass {
+        self.social_class
+    }
+    pub fn get_culture(&self) -> Culture {
+        self.culture
+    }
+    pub fn get_religion(&self) -> Religion {
+        self.religion
+    }
+    /// Number of deliveries, including stillbirths.
+    pub fn get_parity(&self) -> u32 {
+        self.parity
+    }
+    /// Completed years in the calendar of the date, a birthday missing in the current year is passed on the following day.
     pub fn get_age(&self, curr_date: &Date) -> u32 {
         let had_birthday =
             curr_date.get_month() > self.birthday.get_month() ||
             (curr_date.get_month() == self.birthday.get_month() &&
              curr_date.get_day() >= self.birthday.get_day());
-        let age = curr_date.get_year() - self.birthday.get_year() - 1;
+        let age = (curr_date.get_year() - self.birthday.get_year()).max(0) as u32;
         if had_birthday {
-            age + 1
-        } else {
             age
+        } else {
+            age.saturating_sub(1)
         }
     }
     
     pub fn set_birthday(&mut self, birthday: Date) {
         self.birthday = birthday;
     }
-    pub fn set_first_name(&mut self, first_name: &str) {
-        self.first_name = String::from(first_name);
+    pub fn set_first_name(&mut self, first_name: Symbol) {
+        self.first_name = first_name;
+    }
+    pub fn set_last_name(&mut self, last_name: Symbol) {
+        self.last_name = last_name;
+    }
+    pub fn set_nickname(&mut self, nickname: Symbol) {
+        self.nickname = Some(nickname);
+    }
+    pub fn set_social_class(&mut self, social_class: SocialClass) {
+        self.social_class = social_class;
     }
-    pub fn set_last_name(&mut self, last_name: &str) {
-        self.last_name = String::from(last_name);
+    pub fn set_culture(&mut self, culture: Culture) {
+        self.culture = culture;
+    }
+    pub fn set_religion(&mut self, religion: Religion) {
+        self.religion = religion;
+    }
+    pub fn get_custom(&self, key: &str) -> Option<i64> {
+        self.custom.get(key).cloned()
+    }
+    pub fn set_custom(&mut self, key: &str, value: i64) {
+        self.custom.insert(key.to_owned(), value);
+    }
+    pub fn set_occupation(&mut self, occupation: Occupation) {
+        self.occupation = Some(occupation);
     }
     pub fn set_father(&mut self, father_id: u32) {
         self.father = Some(father_id);
@@ -82,6 +166,46 @@ impl Person {
         self.attributes.set_female();
     }
 
+    pub fn add_delivery(&mut self, date: Date) {
+        self.parity += 1;
+        self.last_delivery = Some(date);
+    }
+
+    /// Date of the latest birth, wedding, end of a marriage or delivery in the life of the person.
+    pub fn get_last_event(&self) -> Date {
+        self.marriage_history.iter()
+            .flat_map(|m| Some(m.get_start()).into_iter().chain(m.get_end().map(|(date, _)| date)))
+            .chain(self.last_delivery)
+            .fold(self.birthday, Date::max)
+    }
+
+    /// First day the person may marry, after reaching the marriage age and,
+    /// if widowed, after mourning the last spouse.
+    pub fn get_earliest_wedding(&self, mourning_period: u32) -> Date {
+        let mut of_age = self.birthday;
+        of_age.advance_years(MIN_MARRIAGE_AGE);
+        match self.marriage_history.last().and_then(|m| m.get_end()) {
+            Some((end, MarriageEnd::Widowed)) => of_age.max(end + mourning_period),
+            Some((end, MarriageEnd::Divorce)) => of_age.max(end),
+            None => of_age
+        }
+    }
+
+    pub fn begin_marriage(&mut self, spouse_id: u32, date: Date) {
+        debug_assert!(self.get_spouse().is_none());
+        self.attributes.set_married(spouse_id);
+        self.marriage_history.push(Marriage::new(spouse_id, date));
+    }
+
+    pub fn end_marriage(&mut self, date: Date, reason: MarriageEnd) -> Option<u32> {
+        let spouse_id = self.get_spouse()?;
+        self.attributes.pop_marriage();
+        if let Some(marriage) = self.marriage_history.iter_mut().rev().find(|m| m.is_active()) {
+            marriage.close(date, reason);
+        }
+        Some(spouse_id)
+    }
+
     pub fn satisfies(&self, wanted_attributes: &AttributeList, unwanted_attributes: &AttributeList) -> bool {
         self.attributes.satisfies(wanted_attributes, unwanted_attributes)
     }
@@ -96,6 +220,6 @@ impl Person {
 
 impl fmt::Display for Person {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "id = {}, name = {} {}, birthday = {}", self.id, self.first_name, self.last_name, self.birthday)
+        write!(f, "id = {}, birthday = {}", self.id, self.birthday)
     }
 }
@@ -1,100 +1,242 @@
+use std::sync::Arc;
+use std::convert::TryFrom;
 use rand::Rng;
-use rand::seq::SliceRandom;
 
 use crate::utility::application_error::ApplicationError;
-use crate::utility::read_file::read_file;
+use crate::utility::file_error::FileError;
+use crate::utility::simulation_error::SimulationError;
+use crate::utility::ResourceLocator;
+use crate::utility::read_file::read_non_empty_file;
 use crate::utility::date::Date;
+use crate::utility::{ Symbol, SymbolTable };
 
-use super::Person;
+use super::{ Person, Culture, Religion };
+use super::name_pack::{ NamePack, load_packs, load_embedded_packs, get_default_place_words };
 
+const NICKNAME_CHANCE: f64 = 0.1;
+const RELIGIONS_FILE: &str = "religions.txt";
+// used when the resource directory has no religions
+const EMBEDDED_RELIGIONS: &str = include_str!("../../resources/religions.txt");
+
+/// Creates persons with unique ids. Clones share the loaded names,
+/// towns get their own generator with a distinct id sequence by `split`.
+/// The names of persons are symbols in the table of the generator that created them.
+#[derive(Clone)]
 pub struct PersonGenerator {
-    next_id: u32,
+    // wider than ids, so running out is noticed instead of wrapping around
+    next_id: u64,
+    id_step: u64,
     curr_date: Date,
-    first_names_male: Vec<String>,
-    first_names_female: Vec<String>,
-    last_names: Vec<String>
+    cultures: Arc<Vec<NamePack>>,
+    religions: Arc<Vec<String>>,
+    // shared with the clones until one of them adds a name
+    symbols: Arc<SymbolTable>
 }
 
 impl PersonGenerator {
     pub fn new() -> Result<Self, ApplicationError> {
+        Self::with_resources(&ResourceLocator::new(None))
+    }
+
+    pub fn with_resources(resources: &ResourceLocator) -> Result<Self, ApplicationError> {
         let mut pg = Self {
             next_id: 0,
+            id_step: 1,
             curr_date: Date::default(),
-            first_names_male: Vec::new(),
-            first_names_female: Vec::new(),
-            last_names: Vec::new()
+            cultures: Arc::new(Vec::new()),
+            religions: Arc::new(Vec::new()),
+            symbols: Arc::new(SymbolTable::default())
         };
 
-        pg.load_names()?;
+        pg.load_names(resources)?;
+        pg.load_religions(resources)?;
 
         Ok(pg)
     }
 
+    /// Generator number `index` of `count`, handing out every `count`-th id of this one.
+    /// The ids of all split generators are disjoint, no matter in which order they are used.
+    pub fn split(&self, index: u32, count: u32) -> PersonGenerator {
+        debug_assert!(index < count);
+        let mut generator = self.clone();
+        generator.next_id = self.next_id.saturating_add((index as u64).saturating_mul(self.id_step));
+        generator.id_step = (count as u64).saturating_mul(self.id_step);
+        generator
+    }
+
     pub fn set_date(&mut self, new_date: Date) {
         self.curr_date = new_date;
     }
 
-    pub fn generate_random_person<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Person {
-        let mut p = Person::new(self.next_id);
-        self.next_id += 1;
-        
+    pub fn get_culture_count(&self) -> usize {
+        self.cultures.len()
+    }
+    pub fn get_religion_count(&self) -> usize {
+        self.religions.len()
+    }
+
+    pub fn get_culture_name(&self, culture: Culture) -> &str {
+        match self.cultures.get(culture.0 as usize) {
+            Some(pack) => pack.get_name(),
+            None => "Unknown"
+        }
+    }
+    pub fn get_religion_name(&self, religion: Religion) -> &str {
+        match self.religions.get(religion.0 as usize) {
+            Some(name) => name,
+            None => "Unknown"
+        }
+    }
+
+    pub fn get_symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    /// The table of names, shared with the generator until either of them adds a name.
+    pub fn share_symbols(&self) -> Arc<SymbolTable> {
+        self.symbols.clone()
+    }
+
+    pub fn get_name(&self, symbol: Symbol) -> &str {
+        self.symbols.resolve(symbol)
+    }
+
+    /// The symbol of the name, which is added to the table of this generator if it is new.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        match self.symbols.find(name) {
+            Some(symbol) => symbol,
+            None => Arc::make_mut(&mut self.symbols).intern(name)
+        }
+    }
+
+    pub fn random_culture<R: Rng + ?Sized>(&self, rng: &mut R) -> Culture {
+        Culture(rng.gen_range(0, self.cultures.len().max(1)) as u16)
+    }
+    pub fn find_culture(&self, name: &str) -> Option<Culture> {
+        self.cultures.iter()
+            .position(|pack| pack.get_name().eq_ignore_ascii_case(name))
+            .map(|i| Culture(i as u16))
+    }
+
+    pub fn generate_town_name<R: Rng + ?Sized>(&self, culture: Culture, rng: &mut R) -> String {
+        match self.get_culture_names(culture).and_then(|pack| pack.generate_town_name(rng)) {
+            Some(name) => name,
+            None => String::from("Townshire")
+        }
+    }
+
+    /// Templates or words for naming places of the culture, see `NamePack::get_place_words`.
+    /// Kinds the culture leaves out use common english ones.
+    pub fn get_place_words(&self, culture: Culture, kind: &str) -> &[String] {
+        match self.get_culture_names(culture).map(|pack| pack.get_place_words(kind)) {
+            Some(words) if !words.is_empty() => words,
+            _ => get_default_place_words(kind)
+        }
+    }
+
+    pub fn random_male_first_name<R: Rng + ?Sized>(&self, culture: Culture, rng: &mut R) -> Symbol {
+        self.get_random_male_first_name(culture, rng)
+    }
+
+    pub fn random_religion<R: Rng + ?Sized>(&self, rng: &mut R) -> Religion {
+        Religion(rng.gen_range(0, self.religions.len().max(1)) as u16)
+    }
+
+    /// A new person of the culture, fails once all ids of this generator are used.
+    pub fn generate_random_person<R: Rng + ?Sized>(&mut self, culture: Culture, rng: &mut R) -> Result<Person, SimulationError> {
+        let id = u32::try_from(self.next_id).map_err(|_| SimulationError::IdsExhausted)?;
+        let mut p = Person::new(id);
+        self.next_id = self.next_id.saturating_add(self.id_step);
+
         if rng.gen_bool(0.5) {
             p.set_male();
-            p.set_first_name(self.get_random_male_first_name(rng));
+            p.set_first_name(self.get_random_male_first_name(culture, rng));
         } else {
             p.set_female();
-            p.set_first_name(self.get_random_female_first_name(rng));
+            p.set_first_name(self.get_random_female_first_name(culture, rng));
         }
         p.set_birthday(self.curr_date);
-        p.set_last_name(&self.get_random_last_name(rng));
+        p.set_last_name(self.get_random_last_name(culture, rng));
+        p.set_culture(culture);
+        if rng.gen_bool(NICKNAME_CHANCE) {
+            let nickname = self.get_culture_names(culture)
+                .and_then(|pack| pack.get_random_nickname(p.get_first_name(), rng));
+            if let Some(nickname) = nickname {
+                p.set_nickname(nickname);
+            }
+        }
 
-        p
+        Ok(p)
     }
 
-    fn load_names(&mut self) -> Result<(), ApplicationError> {
+    fn load_names(&mut self, resources: &ResourceLocator) -> Result<(), ApplicationError> {
         info!("Loading names");
-        let first_names_male = read_file("resources/names_first_male.txt")?;
-        for name in first_names_male.split("\n") {
-            self.first_names_male.push(String::from(name));
+        let mut cultures = Vec::new();
+        let mut symbols = SymbolTable::default();
+        if let Some(dir) = resources.get_dir() {
+            let packs_dir = dir.join("cultures");
+            if packs_dir.is_dir() {
+                cultures = load_packs(&packs_dir, &mut symbols)?;
+            } else if NamePack::has_legacy(dir) {
+                info!("No name packs found, using plain name lists");
+                cultures.push(NamePack::load_legacy(dir, &mut symbols)?);
+            } else if resources.is_explicit() {
+                return Err(FileError::MissingResource(packs_dir.to_string_lossy().into_owned()).into());
+            }
         }
-        info!("Loaded {} male first names", self.first_names_male.len());
-        
-        let first_names_female = read_file("resources/names_first_female.txt")?;
-        for name in first_names_female.split("\n") {
-            self.first_names_female.push(String::from(name));
+        if cultures.is_empty() {
+            info!("Using embedded name packs");
+            cultures = load_embedded_packs(&mut symbols);
         }
-        info!("Loaded {} female first names", self.first_names_female.len());
+        info!("Finished loading of names, {} cultures and {} distinct names", cultures.len(), symbols.len());
+        self.cultures = Arc::new(cultures);
+        self.symbols = Arc::new(symbols);
+        Ok(())
+    }
 
-        let last_names = read_file("resources/names_last.txt")?;
-        for name in last_names.split("\n") {
-            self.last_names.push(String::from(name));
-        }
-        info!("Loaded {} last names", self.last_names.len());
-        info!("Finished loading of names");
+    // one religion per line
+    fn load_religions(&mut self, resources: &ResourceLocator) -> Result<(), ApplicationError> {
+        let path = resources.get_dir()
+            .map(|dir| dir.join(RELIGIONS_FILE))
+            .filter(|path| path.is_file());
+        let content = match path {
+            Some(path) => read_non_empty_file(&path.to_string_lossy())?,
+            None => String::from(EMBEDDED_RELIGIONS)
+        };
+        let religions: Vec<String> = content.lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect();
+        info!("Loaded {} religions", religions.len());
+        self.religions = Arc::new(religions);
         Ok(())
     }
 
-    fn get_random_male_first_name<R: Rng + ?Sized>(&self, rng: &mut R) -> &str {
-        match self.first_names_male.choose(rng) {
+    // unknown cultures fall back to the first registered one
+    fn get_culture_names(&self, culture: Culture) -> Option<&NamePack> {
+        self.cultures.get(culture.0 as usize)
+            .or_else(|| self.cultures.first())
+    }
+
+    fn get_random_male_first_name<R: Rng + ?Sized>(&self, culture: Culture, rng: &mut R) -> Symbol {
+        match self.get_culture_names(culture).and_then(|pack| pack.get_first_names_male().choose(rng)) {
             Some(name) => name,
-            None => "Nameless"
+            None => Symbol::UNKNOWN
         }
     }
 
-    fn get_random_female_first_name<R: Rng + ?Sized>(&self, rng: &mut R) -> &str {
-        match self.first_names_female.choose(rng) {
+    fn get_random_female_first_name<R: Rng + ?Sized>(&self, culture: Culture, rng: &mut R) -> Symbol {
+        match self.get_culture_names(culture).and_then(|pack| pack.get_first_names_female().choose(rng)) {
             Some(name) => name,
-            None => "Nameless"
+            None => Symbol::UNKNOWN
         }
     }
 
-    fn get_random_last_name<R: Rng + ?Sized>(&mut self, rng: &mut R) -> String {
-        let name = self.last_names.choose(rng);
-        match name {
-            Some(n) => n.clone(),
-            None => String::from("McNamelessFace")
-        }  
+    fn get_random_last_name<R: Rng + ?Sized>(&self, culture: Culture, rng: &mut R) -> Symbol {
+        match self.get_culture_names(culture).and_then(|pack| pack.get_last_names().choose(rng)) {
+            Some(name) => name,
+            None => Symbol::UNKNOWN
+        }
     }
-
-
 }
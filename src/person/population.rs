@@ -1,34 +1,107 @@
 use std::fmt;
-use rand:: { Rng, seq::IteratorRandom };
+use std::cmp::Reverse;
+use std::sync::Arc;
+use std::collections::{ HashMap, HashSet, BTreeSet };
+use rand:: { Rng, seq::IteratorRandom, seq::SliceRandom };
 
-use crate::utility::{ Date, DAYS_PER_MONTH, DAYS_PER_YEAR };
-use super::{ Person, AttributeList, PersonGenerator, Attribute };
+use crate::utility::{ Date, Symbol };
+use crate::config::{ SimulationConfig, PopulationConfig };
+use crate::utility::simulation_error::SimulationError;
+use super::{ Person, AttributeList, PersonGenerator, Attribute, Lineage, MarriageEnd, MarriageRules, MIN_MARRIAGE_AGE, Demography, PregnancyModel, PregnancyOutcome, Event, Wealth, InheritanceRule, Occupation, SocialClass, Culture, Religion, CultureRules, Seasonality, Household, EventListener };
+use super::household::group_households;
+use super::culture::count_by;
+use super::wealth::{ Heirs, YEARLY_LIVING_COST, gini };
+
+// draws of a related partner after which a person stays without one this year
+const MAX_PARTNER_DRAWS: u32 = 16;
 
 #[derive(Clone)]
 pub struct Population {
-    population: Vec<Person>,
+    // shared with snapshots and checkpoints, the list is copied when it first changes
+    // after that and a person only when it changes
+    population: Arc<Vec<Arc<Person>>>,
+    // position of every person in the population by id
+    positions: HashMap<u32, usize>,
+    // pregnant persons by due date, entries of persons who died are left until the date
+    pregnancies: BTreeSet<(Date, u32)>,
+    lineage: Lineage,
+    recent_deaths: Vec<u32>,
+    recent_events: Vec<Event>,
+    // shared with clones of the population
+    listeners: Vec<Arc<dyn EventListener>>,
+    capacity: u32,
+    growth_accumulator: f32,
+    config: PopulationConfig,
+    marriage_rules: MarriageRules,
+    demography: Demography,
+    pregnancy_model: PregnancyModel,
+    inheritance_rule: InheritanceRule,
+    culture_rules: CultureRules,
+    seasonality: Seasonality
+}
+
+/// Earlier state of a population, see `Population::checkpoint`.
+pub struct Checkpoint {
+    population: Arc<Vec<Arc<Person>>>,
+    lineage_size: usize,
+    recent_deaths: Vec<u32>,
+    recent_events: Vec<Event>,
     capacity: u32,
     growth_accumulator: f32
 }
 
+// unmarried women who are equally likely to be chosen by any groom
+struct BrideGroup {
+    age: u32,
+    social_class: SocialClass,
+    identity: (Culture, Religion),
+    // weight of the age of the brides
+    weight: f32,
+    brides: Vec<usize>
+}
+
+// indices chosen with a chance proportional to their weight, in logarithmic time
+#[derive(Default)]
+struct WeightedIndices {
+    indices: Vec<usize>,
+    cumulative: Vec<f64>
+}
+
+// ids of persons by their age in years
+#[derive(Default)]
+struct AgeBuckets {
+    buckets: Vec<Vec<u32>>
+}
+
 impl Population {
 
-    pub fn new<R: Rng + ?Sized>(size: u32, capacity: u32, today: Date, generator: &mut PersonGenerator, rng: &mut R) -> Population {
+    /// Founds a population of the given size, settlers are picked at random from the given cultures and religions.
+    pub fn new<R: Rng + ?Sized>(size: u32, capacity: u32, today: Date, settlers: &[(Culture, Religion)], config: &SimulationConfig, generator: &mut PersonGenerator, rng: &mut R) -> Result<Population, SimulationError> {
         let mut population = Population::default();
+        population.apply_config(config);
         population.set_capacity(capacity);
+        let founder_ages = (config.population.founder_age_min, config.population.founder_age_max);
         for _ in 0..size {
-            let mut person = generator.generate_random_person(rng);
-            person.set_birthday(today.random_past_years_range((5, 40), rng));
+            let (culture, religion) = match settlers.choose(rng) {
+                Some(&settler) => settler,
+                None => (Culture::default(), Religion::default())
+            };
+            let mut person = generator.generate_random_person(culture, rng)?;
+            person.set_religion(religion);
+            person.set_birthday(today.random_past_years_range(founder_ages, rng));
+            let social_class = SocialClass::random_founder(rng);
+            person.set_social_class(social_class);
+            if person.get_age(&today) >= 18 {
+                let mut wealth = Wealth::random_founder(rng);
+                wealth.scale(social_class.get_founding_wealth_factor());
+                *person.get_wealth_mut() = wealth;
+            }
             population.add(person);
         }
         population.handle_fertility(today);
-
-        while population.get_marriage_ratio() < 0.66 {
-            if !population.random_marriage(rng) {
-                break;
-            }
-        }
-        population
+        population.marry_couples(population.get_wanted_marriages(), today, rng);
+        population.recent_events.clear();
+        Ok(population)
     }
 
     pub fn can_grow(&self) -> bool {
@@ -36,8 +109,7 @@ impl Population {
     }
 
     pub fn calculate_growth(&self) -> f32 {
-        const COEFF: f32 = 0.1;
-        logistic(self.size() as f32, self.capacity as f32, COEFF)
+        logistic(self.size() as f32, self.capacity as f32, self.config.growth_coefficient)
     }
 
     pub fn apply_growth(&mut self) {
@@ -52,17 +124,155 @@ impl Population {
     }
 
     pub fn add(&mut self, person: Person) {
-        self.population.push(person);
+        self.add_shared(Arc::new(person));
+    }
+
+    // adds a person who is also shared with an event
+    fn add_shared(&mut self, person: Arc<Person>) {
+        self.lineage.record(&person);
+        self.push_shared(person);
+    }
+
+    // copies the list of persons if a snapshot or checkpoint still shares it
+    fn persons_mut(&mut self) -> &mut Vec<Arc<Person>> {
+        Arc::make_mut(&mut self.population)
+    }
+
+    // puts back a person who was taken out
+    fn push(&mut self, person: Person) {
+        self.push_shared(Arc::new(person));
+    }
+
+    fn push_shared(&mut self, person: Arc<Person>) {
+        if let Some(due) = person.get_attr().get_due_date() {
+            self.pregnancies.insert((due, person.get_id()));
+        }
+        self.positions.insert(person.get_id(), self.population.len());
+        self.persons_mut().push(person);
     }
 
     pub fn set_capacity(&mut self, new_cap: u32) {
         self.capacity = new_cap;
     }
 
-    pub fn get_population(&self) -> &[Person] {
+    pub fn set_forbidden_kinship_degree(&mut self, degree: u32) {
+        self.config.forbidden_kinship_degree = degree;
+    }
+
+    /// Replaces all rules and tuning parameters with the ones of the config.
+    pub fn apply_config(&mut self, config: &SimulationConfig) {
+        self.config = config.population;
+        self.marriage_rules = config.marriage;
+        self.demography = config.demography.clone();
+        self.pregnancy_model = config.pregnancy.clone();
+        self.inheritance_rule = config.inheritance;
+        self.culture_rules = config.culture;
+        self.seasonality = config.seasons;
+    }
+
+    pub fn set_marriage_rules(&mut self, rules: MarriageRules) {
+        self.marriage_rules = rules;
+    }
+
+    pub fn set_demography(&mut self, demography: Demography) {
+        self.demography = demography;
+    }
+
+    pub fn set_pregnancy_model(&mut self, model: PregnancyModel) {
+        self.pregnancy_model = model;
+    }
+
+    pub fn set_culture_rules(&mut self, rules: CultureRules) {
+        self.culture_rules = rules;
+    }
+
+    pub fn set_inheritance_rule(&mut self, rule: InheritanceRule) {
+        self.inheritance_rule = rule;
+    }
+
+    pub fn set_seasonality(&mut self, seasonality: Seasonality) {
+        self.seasonality = seasonality;
+    }
+
+    /// Ids of the persons who died during the last update.
+    pub fn get_recent_deaths(&self) -> &[u32] {
+        &self.recent_deaths
+    }
+
+    /// Births, deaths, marriages and pregnancies of the last update, in order of occurrence.
+    pub fn get_recent_events(&self) -> &[Event] {
+        &self.recent_events
+    }
+
+    /// Reports the event to the listeners and keeps it with the events of the update.
+    pub fn add_event(&mut self, event: Event) {
+        for listener in self.listeners.iter() {
+            listener.on_event(&event);
+        }
+        self.recent_events.push(event);
+    }
+
+    /// Registers a listener which receives every following event as it happens.
+    pub fn add_listener(&mut self, listener: Arc<dyn EventListener>) {
+        self.listeners.push(listener);
+    }
+
+    pub fn get_lineage(&self) -> &Lineage {
+        &self.lineage
+    }
+
+    pub fn are_related(&self, a: u32, b: u32) -> bool {
+        self.lineage.is_related(a, b, self.config.forbidden_kinship_degree)
+    }
+
+    pub fn get_population(&self) -> &[Arc<Person>] {
         &self.population
     }
 
+    /// The persons as a list shared with the population until either of them changes.
+    pub fn share_population(&self) -> Arc<Vec<Arc<Person>>> {
+        self.population.clone()
+    }
+
+    /// State to return to with `restore`. The persons are shared and the lineage
+    /// is only remembered by its size, as it only ever grows.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            population: self.population.clone(),
+            lineage_size: self.lineage.size(),
+            recent_deaths: self.recent_deaths.clone(),
+            recent_events: self.recent_events.clone(),
+            capacity: self.capacity,
+            growth_accumulator: self.growth_accumulator
+        }
+    }
+
+    /// Undoes all changes since the checkpoint was taken.
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.population = checkpoint.population;
+        self.positions = self.population.iter()
+            .enumerate()
+            .map(|(index, person)| (person.get_id(), index))
+            .collect();
+        self.pregnancies = self.population.iter()
+            .filter_map(|person| person.get_attr().get_due_date().map(|due| (due, person.get_id())))
+            .collect();
+        self.lineage.truncate(checkpoint.lineage_size);
+        self.recent_deaths = checkpoint.recent_deaths;
+        self.recent_events = checkpoint.recent_events;
+        self.capacity = checkpoint.capacity;
+        self.growth_accumulator = checkpoint.growth_accumulator;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Person> {
+        self.population.iter().map(|p| p.as_ref())
+    }
+
+    /// Households of the current population, ordered by their heads.
+    pub fn get_households(&self, today: Date) -> Vec<Household> {
+        group_households(self.iter(), today)
+    }
+
     pub fn get_female_ratio(&self) -> f32 {
         self.population.iter()
             .fold(0, |acc, p|
@@ -93,17 +303,107 @@ impl Population {
                 }) as f32 / self.population.len() as f32
     }
 
+    pub fn get_mean_inbreeding_coefficient(&self) -> f32 {
+        self.population.iter()
+            .fold(0., |acc, p| acc + self.lineage.inbreeding_coefficient(p.get_id())) / self.population.len() as f32
+    }
+
+    /// Mean number of deliveries of mothers past their fertile years.
+    pub fn get_mean_completed_parity(&self) -> f32 {
+        let mothers: Vec<u32> = self.population.iter()
+            .filter(|p| p.get_attr().is_female() && !p.get_attr().is_fertile() && p.get_parity() > 0)
+            .map(|p| p.get_parity())
+            .collect();
+        if mothers.is_empty() {
+            return 0.;
+        }
+        mothers.iter().sum::<u32>() as f32 / mothers.len() as f32
+    }
+
+    /// Number of women per parity, with the index being the parity.
+    pub fn get_parity_distribution(&self) -> Vec<u32> {
+        let mut distribution = Vec::new();
+        for p in self.population.iter().filter(|p| p.get_attr().is_female()) {
+            let parity = p.get_parity() as usize;
+            if distribution.len() <= parity {
+                distribution.resize(parity + 1, 0);
+            }
+            distribution[parity] += 1;
+        }
+        distribution
+    }
+
+    pub fn get_class_counts(&self) -> Vec<(SocialClass, u32)> {
+        SocialClass::ALL.iter()
+            .map(|&class| (class, self.population.iter().filter(|p| p.get_social_class() == class).count() as u32))
+            .collect()
+    }
+
+    pub fn get_culture_counts(&self) -> Vec<(Culture, u32)> {
+        count_by(self.population.iter().map(|p| p.get_culture()))
+    }
+
+    pub fn get_religion_counts(&self) -> Vec<(Religion, u32)> {
+        count_by(self.population.iter().map(|p| p.get_religion()))
+    }
+
+    pub fn get_clergy_counts(&self) -> Vec<(Religion, u32)> {
+        count_by(self.population.iter()
+            .filter(|p| p.get_social_class() == SocialClass::Clergy)
+            .map(|p| p.get_religion()))
+    }
+
+    pub fn get_majority_culture(&self) -> Option<Culture> {
+        self.get_culture_counts().first().map(|&(culture, _)| culture)
+    }
+
+    pub fn get_majority_religion(&self) -> Option<Religion> {
+        self.get_religion_counts().first().map(|&(religion, _)| religion)
+    }
+
+    /// Share of persons belonging to the majority culture, 1.0 means fully assimilated.
+    pub fn get_assimilation_ratio(&self) -> f32 {
+        match self.get_culture_counts().first() {
+            Some(&(_, count)) => count as f32 / self.population.len() as f32,
+            None => 1.
+        }
+    }
+
+    pub fn get_wealth_gini(&self) -> f32 {
+        let mut values: Vec<u64> = self.population.iter()
+            .map(|p| p.get_wealth().get_value())
+            .collect();
+        gini(&mut values)
+    }
+
+    /// Summed wealth per family name, richest first.
+    pub fn get_family_wealth(&self) -> Vec<(Symbol, u64)> {
+        let mut families: Vec<(Symbol, u64)> = Vec::new();
+        // positions in the list, which keeps the order of first appearance for equal wealth
+        let mut positions: HashMap<Symbol, usize> = HashMap::new();
+        for person in self.population.iter() {
+            let value = person.get_wealth().get_value();
+            match positions.get(&person.get_last_name()) {
+                Some(&position) => families[position].1 = families[position].1.saturating_add(value),
+                None => {
+                    positions.insert(person.get_last_name(), families.len());
+                    families.push((person.get_last_name(), value));
+                }
+            }
+        }
+        families.sort_by_key(|&(_, value)| Reverse(value));
+        families
+    }
+
     pub fn get_random_by_attribute_list<R: Rng + ?Sized>(&self, wanted: &AttributeList, unwanted: &AttributeList, rng: &mut R) -> Option<&Person> {
         match self.get_random_index_by_attribute_list(wanted, unwanted, rng) {
-            Some(index) => Some(&self.population[index]),
+            Some(index) => Some(self.population[index].as_ref()),
             None => None
         }
     }
     pub fn pop_random_by_attribute_list<R: Rng + ?Sized>(&mut self, wanted: &AttributeList, unwanted: &AttributeList, rng: &mut R) -> Option<Person> {
-        match self.get_random_index_by_attribute_list(wanted, unwanted, rng) {
-            Some(index) => Some(self.pop_by_index(index)),
-            None => None
-        }
+        self.get_random_index_by_attribute_list(wanted, unwanted, rng)
+            .map(|index| self.pop_by_index(index))
     }
 
     pub fn pop_by_attribute_list(&mut self, wanted: &AttributeList, unwanted: &AttributeList) -> Vec<Person> {
@@ -115,72 +415,394 @@ impl Population {
     }
 
     pub fn get_by_id(&self, id: u32) -> Option<&Person> {
-        self.population.iter()
-            .find(|p| p.get_id() == id)
+        self.positions.get(&id)
+            .map(|&index| self.population[index].as_ref())
     }
 
-    fn pop_by_id(&mut self, id: u32) -> Person {
-        let opt_index = self.population.iter()
-            .enumerate()
-            .find_map(|(i, p)|
-                if p.get_id() == id {
-                    Some(i)
-                } else {
-                    None
-                });
-        match opt_index {
-            Some(i) => self.pop_by_index(i),
-            None => unreachable!("Id could not be found")
+    /// The person as it is now, shared with the population until either of them changes.
+    pub fn get_shared_by_id(&self, id: u32) -> Option<Arc<Person>> {
+        self.positions.get(&id)
+            .map(|&index| self.population[index].clone())
+    }
+
+    fn pop_by_id(&mut self, id: u32) -> Result<Person, SimulationError> {
+        self.take_by_id(id).map(Arc::unwrap_or_clone)
+    }
+
+    // removes the person without copying it if it is shared
+    fn take_by_id(&mut self, id: u32) -> Result<Arc<Person>, SimulationError> {
+        match self.positions.get(&id) {
+            Some(&index) => Ok(self.take_by_index(index)),
+            None => Err(SimulationError::UnknownPerson(id))
+        }
+    }
+
+    pub fn get_by_id_mut(&mut self, id: u32) -> Option<&mut Person> {
+        match self.positions.get(&id) {
+            Some(&index) => Some(Arc::make_mut(&mut self.persons_mut()[index])),
+            None => None
         }
-    }                
+    }
 
     fn pop_by_index(&mut self, index: usize) -> Person {
+        Arc::unwrap_or_clone(self.take_by_index(index))
+    }
+
+    fn take_by_index(&mut self, index: usize) -> Arc<Person> {
         debug_assert!(index < self.population.len());
-        self.population.swap_remove(index)
+        let person = self.persons_mut().swap_remove(index);
+        self.positions.remove(&person.get_id());
+        // the last person takes the place of the removed one
+        if let Some(moved) = self.population.get(index) {
+            self.positions.insert(moved.get_id(), index);
+        }
+        person
     }
 
-    pub fn update<R: Rng + ?Sized>(&mut self, today: Date, person_generator: &mut PersonGenerator, rng: &mut R) {
-        self.handle_births(today, person_generator, rng);
-        self.handle_deaths(today, rng);
-        self.handle_growth(today, rng);
-        self.handle_fertility(today);
-        self.handle_marriages(rng);
+    /// Forgets the deaths and events of the previous update.
+    pub fn begin_update(&mut self) {
+        self.recent_deaths.clear();
+        self.recent_events.clear();
     }
 
-    fn handle_growth<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) {
+    pub fn handle_economy<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) {
+        for person in self.persons_mut().iter_mut() {
+            if person.get_age(&today) < 14 {
+                continue;
+            }
+            let person = Arc::make_mut(person);
+            let occupation = match person.get_occupation() {
+                Some(occupation) => occupation,
+                None => {
+                    let occupation = Occupation::choose(person.get_wealth(), rng);
+                    trace!("Person {} is now a {}", person.get_id(), occupation);
+                    person.set_occupation(occupation);
+                    occupation
+                }
+            };
+            let income = occupation.get_yearly_income(person.get_wealth());
+            let wealth = person.get_wealth_mut();
+            wealth.earn(income);
+            wealth.spend(YEARLY_LIVING_COST);
+            wealth.invest();
+
+            let unmarried = !person.was_married() && !person.get_attr().is_married();
+            let mobility = person.get_social_class().get_mobility(Some(occupation), person.get_wealth(), unmarried, rng);
+            if let Some(new_class) = mobility {
+                info!("Person {} changes class from {} to {}", person.get_id(), person.get_social_class(), new_class);
+                person.set_social_class(new_class);
+            }
+        }
+    }
+
+    pub fn handle_culture<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) {
+        let majority_culture = self.get_majority_culture();
+        let majority_religion = self.get_majority_religion();
+        let rules = self.culture_rules;
+        for person in self.persons_mut().iter_mut() {
+            if person.get_age(&today) < 14 {
+                continue;
+            }
+            let conversion = match majority_religion {
+                Some(religion) if person.get_religion() != religion && rng.gen_bool(rules.conversion_chance) => Some(religion),
+                _ => None
+            };
+            let assimilation = match majority_culture {
+                Some(culture) if person.get_culture() != culture && rng.gen_bool(rules.assimilation_chance) => Some(culture),
+                _ => None
+            };
+            if conversion.is_none() && assimilation.is_none() {
+                continue;
+            }
+            let person = Arc::make_mut(person);
+            if let Some(religion) = conversion {
+                info!("Person {} converts to the religion of the majority", person.get_id());
+                person.set_religion(religion);
+            }
+            if let Some(culture) = assimilation {
+                trace!("Person {} adopts the culture of the majority", person.get_id());
+                person.set_culture(culture);
+            }
+        }
+    }
+
+    /// Starts a pregnancy for every whole unit of growth, with the same chances as repeated
+    /// `random_pregnancy`. Mothers and fathers are weighed once for all pregnancies of the year.
+    pub fn handle_growth<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) {
         self.apply_growth();
+        if !self.can_grow() {
+            return;
+        }
+        let unwanted_mother = AttributeList::builder().set_pregnant().set_postpartum().build();
+        let wanted_mother = AttributeList::builder().set_female().set_fertile().build();
+        let wanted_father = AttributeList::builder().set_male().set_fertile().build();
+
+        // weighted sampling without replacement, sorting by the key ln(u) / weight
+        let mut mothers: Vec<(f32, usize)> = Vec::new();
+        let mut fathers = WeightedIndices::default();
+        let mut unmarried_fathers = WeightedIndices::default();
+        for (index, p) in self.population.iter().enumerate() {
+            if p.satisfies(&wanted_mother, &unwanted_mother) {
+                let weight = self.get_reproduction_weight(p, today) * match p.get_spouse().and_then(|id| self.get_by_id(id)) {
+                    Some(spouse) => self.get_reproduction_weight(spouse, today),
+                    None => 1.
+                };
+                if weight > 0. {
+                    mothers.push((rng.gen::<f32>().ln() / weight, index));
+                }
+            } else if p.satisfies(&wanted_father, &AttributeList::default()) {
+                let weight = self.get_reproduction_weight(p, today);
+                fathers.push(index, weight);
+                if !p.get_attr().is_married() {
+                    unmarried_fathers.push(index, weight);
+                }
+            }
+        }
+        mothers.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        let married_mothers: Vec<usize> = mothers.iter()
+            .map(|&(_, index)| index)
+            .filter(|&index| self.population[index].get_attr().is_married())
+            .collect();
+        let mut all_mothers = mothers.into_iter().map(|(_, index)| index);
+        let mut married_mothers = married_mothers.into_iter();
+        let mut taken = HashSet::new();
+
         while self.can_grow() {
-            let preg_start = today + rng.gen_range(0, DAYS_PER_YEAR);
-            if !self.random_pregnancy(preg_start, rng) {
-                break;
+            let preg_start = self.seasonality.conception.random_date(today, rng);
+            let next_mother = match rng.gen_bool(self.config.mother_married_chance) {
+                true => married_mothers.find(|index| !taken.contains(index)),
+                false => all_mothers.find(|index| !taken.contains(index))
+            };
+            let mother_index = match next_mother {
+                Some(index) => index,
+                None => {
+                    trace!("Wanted to spawn pregnancy, but no mother found");
+                    break;
+                }
+            };
+            let fathers = match rng.gen_bool(self.config.father_extra_marriage_chance) {
+                true => &fathers,
+                false => &unmarried_fathers
+            };
+            let mother = &self.population[mother_index];
+            let father_id = match mother.get_spouse() {
+                Some(spouse_id) => self.get_by_id(spouse_id).map(|father| father.get_id()),
+                // related fathers are rejected and drawn again, which keeps the chances of the others
+                None => (0..MAX_PARTNER_DRAWS)
+                    .filter_map(|_| fathers.choose(rng))
+                    .map(|index| self.population[index].get_id())
+                    .find(|&id| !self.are_related(mother.get_id(), id))
+            };
+            let father_id = match father_id {
+                Some(id) => id,
+                None => {
+                    trace!("Wanted to spawn pregnancy, but no father found");
+                    break;
+                }
+            };
+            taken.insert(mother_index);
+            let model = &self.pregnancy_model;
+            let outcome = model.roll_outcome(mother.get_age(&preg_start), rng);
+            let birthday = model.roll_end(preg_start, outcome, rng);
+            let count = model.roll_count(rng);
+            let mother = Arc::make_mut(&mut Arc::make_mut(&mut self.population)[mother_index]);
+            mother.get_attr_mut().set_pregnant(father_id, birthday, count, outcome);
+            self.pregnancies.insert((birthday, mother.get_id()));
+            info!("New pregnancy: mother: {}, father: {}, birthday: {}, count: {}", mother.get_id(), father_id, birthday, count);
+            let mother = self.population[mother_index].clone();
+            self.add_event(Event::Pregnancy { mother, father_id, due: birthday });
+            self.growth_accumulator -= 1.;
+        }
+    }
+
+    pub fn handle_marriages<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) -> Result<(), SimulationError> {
+        self.marry_couples(self.get_wanted_marriages(), today, rng);
+        self.handle_remarriages(today, rng)
+    }
+
+    fn handle_remarriages<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) -> Result<(), SimulationError> {
+        let wanted = AttributeList::default();
+        let unwanted = AttributeList::builder().set_married().set_mourning().build();
+        let rules = self.marriage_rules;
+
+        let candidates: Vec<u32> = self.population.iter()
+            .filter(|p| p.was_married() &&
+                p.satisfies(&wanted, &unwanted) &&
+                p.get_age(&today) <= rules.remarriage_max_age)
+            .map(|p| p.get_id())
+            .collect();
+        // unmarried men and women who may be chosen as partners, by age
+        let mut men = AgeBuckets::default();
+        let mut women = AgeBuckets::default();
+        for p in self.iter() {
+            let age = p.get_age(&today);
+            if age >= MIN_MARRIAGE_AGE && p.satisfies(&wanted, &unwanted) && p.get_social_class().can_marry() {
+                match p.get_attr().is_male() {
+                    true => men.push(age, p.get_id()),
+                    false => women.push(age, p.get_id())
+                }
             }
         }
+        for id in candidates {
+            if !rng.gen_bool(rules.remarriage_chance) {
+                continue;
+            }
+            let (is_male, age) = match self.get_by_id(id) {
+                Some(person) if person.satisfies(&wanted, &unwanted) && person.get_social_class().can_marry() =>
+                    (person.get_attr().is_male(), person.get_age(&today)),
+                _ => continue
+            };
+            let partners = match is_male {
+                true => &mut women,
+                false => &mut men
+            };
+            let min_age = age.saturating_sub(rules.remarriage_max_age_gap);
+            let max_age = age.saturating_add(rules.remarriage_max_age_gap);
+            let mut draws = 0;
+            let mut partner_id = None;
+            while draws < MAX_PARTNER_DRAWS {
+                let (partner_age, position) = match partners.choose(min_age, max_age, rng) {
+                    Some(choice) => choice,
+                    None => break
+                };
+                let partner = partners.get(partner_age, position);
+                // partners who married this year are dropped, related ones are drawn again
+                match self.get_by_id(partner) {
+                    Some(p) if p.satisfies(&wanted, &unwanted) => {
+                        if !self.are_related(id, partner) {
+                            partners.remove(partner_age, position);
+                            partner_id = Some(partner);
+                            break;
+                        }
+                        draws += 1;
+                    },
+                    _ => partners.remove(partner_age, position)
+                }
+            }
+            if let Some(partner_id) = partner_id {
+                let partner = self.pop_by_id(partner_id)?;
+                let person = match self.pop_by_id(id) {
+                    Ok(person) => person,
+                    Err(err) => {
+                        self.push(partner);
+                        return Err(err);
+                    }
+                };
+                let (groom, bride) = match is_male {
+                    true => (person, partner),
+                    false => (partner, person)
+                };
+                let wedding = self.random_wedding_date(&groom, &bride, today, rng);
+                self.marry(groom, bride, wedding);
+            }
+        }
+        Ok(())
+    }
+
+    // during the past year, but not before either of them may marry
+    fn random_wedding_date<R: Rng + ?Sized>(&self, groom: &Person, bride: &Person, today: Date, rng: &mut R) -> Date {
+        let mourning_period = self.marriage_rules.mourning_period;
+        self.seasonality.marriage.random_past_date(today, rng)
+            .max(groom.get_earliest_wedding(mourning_period))
+            .max(bride.get_earliest_wedding(mourning_period))
+    }
+
+    fn marry(&mut self, mut groom: Person, mut bride: Person, wedding: Date) {
+        info!("Marrying: persons {} and {} on {}", groom.get_id(), bride.get_id(), wedding);
+        groom.begin_marriage(bride.get_id(), wedding);
+        bride.begin_marriage(groom.get_id(), wedding);
+        bride.set_last_name(groom.get_last_name());
+        bride.set_social_class(groom.get_social_class());
+        if bride.get_religion() != groom.get_religion() {
+            info!("Person {} converts to the religion of person {}", bride.get_id(), groom.get_id());
+            bride.set_religion(groom.get_religion());
+        }
+        let (groom, bride) = (Arc::new(groom), Arc::new(bride));
+        self.add_shared(groom.clone());
+        self.add_shared(bride.clone());
+        self.add_event(Event::Marriage { groom, bride });
     }
 
-    fn handle_marriages<R: Rng + ?Sized>(&mut self, rng: &mut R) {
-        let mut fails = 0;
-        while self.get_marriage_ratio() < 0.66 && fails < 3 {
-            if !self.random_marriage(rng) {
-                fails +=1;
+    pub fn handle_divorces<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) -> Result<(), SimulationError> {
+        let wanted = AttributeList::builder().set_male().set_married().build();
+        let unwanted = AttributeList::default();
+        let divorce_chance = self.marriage_rules.divorce_chance;
+
+        let husbands: Vec<u32> = self.population.iter()
+            .filter(|p| p.satisfies(&wanted, &unwanted))
+            .map(|p| p.get_id())
+            .collect();
+        for id in husbands {
+            if rng.gen_bool(divorce_chance) {
+                self.divorce(id, today)?;
             }
         }
+        Ok(())
+    }
+
+    fn divorce(&mut self, id: u32, today: Date) -> Result<(), SimulationError> {
+        self.check_spouse(id)?;
+        let mut person = self.pop_by_id(id)?;
+        if let Some(spouse_id) = person.end_marriage(today, MarriageEnd::Divorce) {
+            let mut spouse = self.pop_by_id(spouse_id)?;
+            spouse.end_marriage(today, MarriageEnd::Divorce);
+            info!("Persons {} and {} are now divorced", person.get_id(), spouse.get_id());
+            self.add(spouse);
+        }
+        self.add(person);
+        Ok(())
+    }
+
+    // checked before anyone is taken out of the population, so an error leaves it untouched
+    fn check_spouse(&self, id: u32) -> Result<(), SimulationError> {
+        let person = self.get_by_id(id).ok_or(SimulationError::UnknownPerson(id))?;
+        match person.get_spouse() {
+            Some(spouse) if self.get_by_id(spouse).is_none() => Err(SimulationError::MissingSpouse { person: id, spouse }),
+            _ => Ok(())
+        }
     }
 
-    pub fn random_marriage<R: Rng + ?Sized>(&mut self, rng: &mut R) -> bool {
+    pub fn handle_expired_attributes(&mut self, today: Date) {
+        for person in self.persons_mut().iter_mut().filter(|p| p.get_attr().has_expired(today)) {
+            let person = Arc::make_mut(person);
+            person.get_attr_mut().clear_expired(today);
+            trace!("Person {} has expired attributes removed", person.get_id());
+        }
+    }
+
+    pub fn random_marriage<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) -> bool {
         let wanted_male = AttributeList::builder().set_male().set_fertile().build();
         let wanted_female  = AttributeList::builder().set_female().set_fertile().build();
-        let unwanted = AttributeList::builder().set_married().build();
-
-        match self.pop_random_by_attribute_list(&wanted_male, &unwanted, rng) {
-            Some(mut groom) => {
-                match self.pop_random_by_attribute_list(&wanted_female, &unwanted, rng) {
-                    Some(mut bride) => {
-                        info!("Marrying: {} and {}", groom.get_full_name(), bride.get_full_name());
-                        groom.get_attr_mut().set_married(bride.get_id());
-                        bride.get_attr_mut().set_married(groom.get_id());
-                        bride.set_last_name(groom.get_last_name());
-                        self.add(groom);
-                        self.add(bride);
+        let unwanted = AttributeList::builder().set_married().set_mourning().build();
+
+        let opt_groom_index = self.get_random_index_by_weight(
+            |p| match p.satisfies(&wanted_male, &unwanted) && p.get_social_class().can_marry() {
+                true => self.demography.get_marriage_age_weight(p, p.get_age(&today)),
+                false => 0.
+            },
+            rng);
+        match opt_groom_index.map(|index| self.pop_by_index(index)) {
+            Some(groom) => {
+                let groom_id = groom.get_id();
+                let groom_age = groom.get_age(&today);
+                let groom_class = groom.get_social_class();
+                let groom_identity = (groom.get_culture(), groom.get_religion());
+                let opt_bride_index = self.get_random_index_by_weight(
+                    |p| match p.satisfies(&wanted_female, &unwanted) && p.get_social_class().can_marry() && !self.are_related(groom_id, p.get_id()) {
+                        true => {
+                            let bride_age = p.get_age(&today);
+                            self.demography.get_marriage_age_weight(p, bride_age) *
+                            self.demography.get_age_gap_weight(groom_age, bride_age) *
+                            groom_class.get_marriage_weight(p.get_social_class()) *
+                            self.culture_rules.get_marriage_weight(groom_identity, (p.get_culture(), p.get_religion()))
+                        },
+                        false => 0.
+                    },
+                    rng);
+                match opt_bride_index.map(|index| self.pop_by_index(index)) {
+                    Some(bride) => {
+                        let wedding = self.random_wedding_date(&groom, &bride, today, rng);
+                        self.marry(groom, bride, wedding);
                         true
                     },
                     None => {
@@ -193,46 +815,156 @@ impl Population {
         }
     }
 
+    // marriages missing to reach the target marriage ratio
+    fn get_wanted_marriages(&self) -> usize {
+        let married = self.population.iter().filter(|p| p.get_attr().is_married()).count();
+        let wanted = (self.config.target_marriage_ratio * self.population.len() as f32).ceil() as usize;
+        wanted.saturating_sub(married).div_ceil(2)
+    }
+
+    /// Marries up to `count` couples with the same chances as repeated `random_marriage`, returns
+    /// the number of weddings. Brides are grouped by everything their weight depends on, so every
+    /// groom only weighs the groups instead of the whole population.
+    pub fn marry_couples<R: Rng + ?Sized>(&mut self, count: usize, today: Date, rng: &mut R) -> usize {
+        if count == 0 {
+            return 0;
+        }
+        let wanted_male = AttributeList::builder().set_male().set_fertile().build();
+        let wanted_female  = AttributeList::builder().set_female().set_fertile().build();
+        let unwanted = AttributeList::builder().set_married().set_mourning().build();
+
+        // weighted sampling without replacement, sorting by the key ln(u) / weight
+        let mut grooms: Vec<(f32, usize)> = Vec::new();
+        let mut groups: Vec<BrideGroup> = Vec::new();
+        let mut group_indices = HashMap::new();
+        for (index, p) in self.population.iter().enumerate() {
+            if !p.get_social_class().can_marry() {
+                continue;
+            }
+            let age = p.get_age(&today);
+            if p.satisfies(&wanted_male, &unwanted) {
+                let weight = self.demography.get_marriage_age_weight(p, age);
+                if weight > 0. {
+                    grooms.push((rng.gen::<f32>().ln() / weight, index));
+                }
+            } else if p.satisfies(&wanted_female, &unwanted) {
+                let key = (age, p.get_social_class(), p.get_culture(), p.get_religion());
+                let group = *group_indices.entry(key).or_insert_with(|| {
+                    groups.push(BrideGroup {
+                        age,
+                        social_class: key.1,
+                        identity: (key.2, key.3),
+                        weight: self.demography.get_marriage_age_weight(p, age),
+                        brides: Vec::new()
+                    });
+                    groups.len() - 1
+                });
+                groups[group].brides.push(index);
+            }
+        }
+        grooms.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut couples = Vec::new();
+        let mut weights = vec![0.; groups.len()];
+        for &(_, groom_index) in grooms.iter() {
+            if couples.len() == count {
+                break;
+            }
+            let groom = &self.population[groom_index];
+            let groom_age = groom.get_age(&today);
+            let groom_identity = (groom.get_culture(), groom.get_religion());
+            let mut total = 0.;
+            for (weight, group) in weights.iter_mut().zip(groups.iter()) {
+                *weight = group.brides.len() as f32 * group.weight *
+                    self.demography.get_age_gap_weight(groom_age, group.age) *
+                    groom.get_social_class().get_marriage_weight(group.social_class) *
+                    self.culture_rules.get_marriage_weight(groom_identity, group.identity);
+                total += *weight;
+            }
+            // related brides are rejected and drawn again, which keeps the chances of the others
+            for _ in 0..MAX_PARTNER_DRAWS {
+                if total <= 0. {
+                    break;
+                }
+                let group = choose_weighted(&weights, total, rng);
+                let member = rng.gen_range(0, groups[group].brides.len());
+                let bride_index = groups[group].brides[member];
+                if !self.are_related(groom.get_id(), self.population[bride_index].get_id()) {
+                    groups[group].brides.swap_remove(member);
+                    couples.push((groom_index, bride_index));
+                    break;
+                }
+            }
+        }
+
+        // taken out from the back, so the indices of the others stay valid
+        let mut indices: Vec<usize> = couples.iter().flat_map(|&(groom, bride)| vec![groom, bride]).collect();
+        indices.sort_unstable_by_key(|&index| Reverse(index));
+        let mut persons: HashMap<usize, Person> = indices.into_iter()
+            .map(|index| (index, self.pop_by_index(index)))
+            .collect();
+        for &(groom, bride) in couples.iter() {
+            if let (Some(groom), Some(bride)) = (persons.remove(&groom), persons.remove(&bride)) {
+                let wedding = self.random_wedding_date(&groom, &bride, today, rng);
+                self.marry(groom, bride, wedding);
+            }
+        }
+        couples.len()
+    }
+
     pub fn random_pregnancy<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) -> bool {
-        const MOTHER_MANDATORY_MARRIED_CHANCE: f64 = 0.6;
-        const FATHER_EXTRA_MARRIAGE_CHANCE: f64 = 0.2;
-        const PREGNANCY_LENGTH: u32 = DAYS_PER_MONTH * 9; 
-        let wanted_mother = match rng.gen_bool(MOTHER_MANDATORY_MARRIED_CHANCE) {
-            true => AttributeList::builder().set_female().set_married().build(),
-            false => AttributeList::builder().set_female().build()
+        let wanted_mother = match rng.gen_bool(self.config.mother_married_chance) {
+            true => AttributeList::builder().set_female().set_fertile().set_married().build(),
+            false => AttributeList::builder().set_female().set_fertile().build()
         };
-        let unwanted_mother = AttributeList::builder().set_pregnant().build();
-        let wanted_father = AttributeList::builder().set_male().build();
-        let unwanted_father = match rng.gen_bool(FATHER_EXTRA_MARRIAGE_CHANCE) {
+        let unwanted_mother = AttributeList::builder().set_pregnant().set_postpartum().build();
+        let wanted_father = AttributeList::builder().set_male().set_fertile().build();
+        let unwanted_father = match rng.gen_bool(self.config.father_extra_marriage_chance) {
             true => AttributeList::default(),
             false => AttributeList::builder().set_married().build()
         };
 
-        let opt_mother = self.pop_random_by_attribute_list(&wanted_mother, &unwanted_mother, rng);
-        match opt_mother {
+        let opt_mother_index = self.get_random_index_by_weight(
+            |p| match p.satisfies(&wanted_mother, &unwanted_mother) {
+                true => self.get_reproduction_weight(p, today) * match p.get_spouse().and_then(|id| self.get_by_id(id)) {
+                    Some(spouse) => self.get_reproduction_weight(spouse, today),
+                    None => 1.
+                },
+                false => 0.
+            },
+            rng);
+        match opt_mother_index.map(|index| self.pop_by_index(index)) {
             Some(mut mother) => {
+                let mother_id = mother.get_id();
                 let opt_father = match mother.get_spouse() {
                     Some(spouse_id) => self.get_by_id(spouse_id),
-                    None => self.get_random_by_attribute_list(&wanted_father, &unwanted_father, rng)
+                    None => self.get_random_index_by_weight(
+                        |p| match p.satisfies(&wanted_father, &unwanted_father) && !self.are_related(mother_id, p.get_id()) {
+                            true => self.get_reproduction_weight(p, today),
+                            false => 0.
+                        },
+                        rng)
+                        .map(|index| self.population[index].as_ref())
                 };
                 match opt_father {
                     Some(father) => {
-                        let birthday = today + rng.gen_range(PREGNANCY_LENGTH - 10, PREGNANCY_LENGTH + 10);
-                        let count = if rng.gen_bool(0.05) {
-                            rng.gen_range(2, 5)
-                        } else {
-                            1
-                        };
-                        mother.get_attr_mut().set_pregnant(father.get_id(), birthday, count);
-                        info!("New pregnancy: mother: {}, father: {}, birthday: {}, count: {}", mother.get_full_name(), father.get_full_name(), birthday, count);
-                        self.add(mother);
+                        let model = &self.pregnancy_model;
+                        let outcome = model.roll_outcome(mother.get_age(&today), rng);
+                        let birthday = model.roll_end(today, outcome, rng);
+                        let count = model.roll_count(rng);
+                        let father_id = father.get_id();
+                        mother.get_attr_mut().set_pregnant(father_id, birthday, count, outcome);
+                        info!("New pregnancy: mother: {}, father: {}, birthday: {}, count: {}", mother.get_id(), father_id, birthday, count);
+                        let mother = Arc::new(mother);
+                        self.add_shared(mother.clone());
+                        self.add_event(Event::Pregnancy { mother, father_id, due: birthday });
                         self.growth_accumulator -= 1.;
                         true
                     },
                     None => {
                         self.add(mother);
                         trace!("Wanted to spawn pregnancy, but no father found");
-                        true
+                        false
                     }
                 }
             },
@@ -243,93 +975,206 @@ impl Population {
         }
     }
 
-    pub fn handle_births<R: Rng + ?Sized>(&mut self, today: Date, person_generator: &mut PersonGenerator, rng: &mut R) {
-        let wanted = AttributeList::builder().set_pregnant().build();
-        let unwanted = AttributeList::default();
+    pub fn handle_births<R: Rng + ?Sized>(&mut self, today: Date, person_generator: &mut PersonGenerator, rng: &mut R) -> Result<(), SimulationError> {
+        let majority_culture = self.get_majority_culture();
 
-        for mut mother in self.pop_by_attribute_list(&wanted, &unwanted) {
+        // pregnancies due by today, mothers who died or are no longer pregnant are skipped
+        let later = self.pregnancies.split_off(&(today + 1, 0));
+        let due = std::mem::replace(&mut self.pregnancies, later);
+        let mut mothers = Vec::new();
+        for (birth, id) in due {
+            if self.get_by_id(id).and_then(|p| p.get_attr().get_due_date()) == Some(birth) {
+                mothers.push(self.pop_by_id(id)?);
+            }
+        }
+        let mut mothers = mothers.into_iter();
+        let mut error = None;
+        for mut mother in mothers.by_ref() {
             match mother.get_attr_mut().pop_pregnancy() {
-                Some(Attribute::Pregnant { father_id, birth, count }) if today >= birth => {
-                    for _ in 0..count {
-                        let mut child = person_generator.generate_random_person(rng);
-                        child.set_last_name(mother.get_last_name());
+                Some(Attribute::Pregnant { birth, outcome, .. }) if today >= birth && outcome != PregnancyOutcome::LiveBirth => {
+                    match outcome {
+                        PregnancyOutcome::Miscarriage => info!("Person {} had a miscarriage", mother.get_id()),
+                        _ => {
+                            info!("Person {} had a stillbirth", mother.get_id());
+                            mother.add_delivery(birth);
+                        }
+                    }
+                    let recovery_end = self.pregnancy_model.get_recovery_end(birth, outcome);
+                    mother.get_attr_mut().set_postpartum(recovery_end);
+                    self.push(mother);
+                },
+                Some(Attribute::Pregnant { father_id, birth, count, outcome }) if today >= birth => {
+                    // children of an earlier marriage keep the name and class of their father
+                    let (last_name, social_class, father_culture, father_religion) = match self.get_by_id(father_id) {
+                        Some(father) => (father.get_last_name(), father.get_social_class(), father.get_culture(), father.get_religion()),
+                        None => (mother.get_last_name(), mother.get_social_class(), mother.get_culture(), mother.get_religion())
+                    };
+                    // children of mixed couples grow up in the culture of the town if one parent shares it
+                    let culture = match majority_culture {
+                        Some(majority) if father_culture != mother.get_culture() && mother.get_culture() == majority => majority,
+                        _ => father_culture
+                    };
+                    let religion = match father_religion == mother.get_religion() {
+                        true => father_religion,
+                        false => mother.get_religion()
+                    };
+                    let social_class = match social_class {
+                        SocialClass::Clergy => SocialClass::Peasant,
+                        class => class
+                    };
+                    let children: Result<Vec<Person>, SimulationError> = (0..count)
+                        .map(|_| person_generator.generate_random_person(culture, rng))
+                        .collect();
+                    let children = match children {
+                        Ok(children) => children,
+                        Err(err) => {
+                            mother.get_attr_mut().add(Attribute::Pregnant { father_id, birth, count, outcome });
+                            self.push(mother);
+                            error = Some(err);
+                            break;
+                        }
+                    };
+                    for mut child in children {
+                        child.set_religion(religion);
+                        child.set_last_name(last_name);
+                        child.set_social_class(social_class);
                         child.set_father(father_id);
                         child.set_mother(mother.get_id());
                         child.set_birthday(birth);
-                        info!("New child: {}, mother: {}", child.get_full_name(), mother.get_full_name());
-                        self.population.push(child);
+                        info!("New child: {}, mother: {}", child.get_id(), mother.get_id());
+                        let child = Arc::new(child);
+                        self.add_shared(child.clone());
+                        self.add_event(Event::Birth { child });
                     }
-                    self.population.push(mother);
+                    mother.add_delivery(birth);
+                    let recovery_end = self.pregnancy_model.get_recovery_end(birth, outcome);
+                    mother.get_attr_mut().set_postpartum(recovery_end);
+                    self.push(mother);
                 },
                 Some(attr @ Attribute::Pregnant { .. } ) => {
                     mother.get_attr_mut().add(attr);
-                    self.population.push(mother);
+                    self.push(mother);
+                },
+                Some(attr) => {
+                    let id = mother.get_id();
+                    mother.get_attr_mut().add(attr);
+                    self.push(mother);
+                    error = Some(SimulationError::UnexpectedAttribute { person: id, expected: "pregnant" });
+                    break;
                 },
-                Some(_unexpected_attr) => unreachable!("Attribute should have been Attribute::Pregnant"),
-                None => unreachable!("Target should have been pregnant")
+                None => {
+                    let id = mother.get_id();
+                    self.push(mother);
+                    error = Some(SimulationError::UnexpectedAttribute { person: id, expected: "pregnant" });
+                    break;
+                }
             }
         }
+        // mothers left after an error go back unchanged
+        for mother in mothers {
+            self.push(mother);
+        }
+        match error {
+            Some(error) => Err(error),
+            None => Ok(())
+        }
     }
 
-    pub fn handle_deaths<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) {
-        self.handle_death_by_age(today, rng);
+    pub fn handle_deaths<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) -> Result<(), SimulationError> {
+        self.handle_death_by_age(today, rng)
     }
 
-    fn handle_death_by_age<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) {
+    fn handle_death_by_age<R: Rng + ?Sized>(&mut self, today: Date, rng: &mut R) -> Result<(), SimulationError> {
         let mut death_list = Vec::new();
-        for person in &self.population {
+        for person in self.population.iter() {
             let age = person.get_age(&today);
-            let die = rng.gen_bool(death_by_age_probability(age) as f64);
+            let probability = death_by_age_probability(age) * person.get_social_class().get_mortality_modifier();
+            let die = rng.gen_bool(probability.min(1.) as f64);
             if die {
-                info!("{} ({}) dies of age", person.get_full_name(), age);
-                death_list.push(person.get_id());
+                // not before anything else that happened to the person
+                let date = self.seasonality.mortality.random_past_date(today, rng).max(person.get_last_event());
+                info!("Person {} ({}) dies of age on {}", person.get_id(), age, date);
+                death_list.push((person.get_id(), date));
             }
         }
-        death_list.into_iter().for_each(|id| self.kill_person(id));
+        for (id, date) in death_list {
+            self.kill_person(id, date)?;
+        }
+        Ok(())
     }
 
-    fn kill_person(&mut self, id: u32) {
-        let person = self.pop_by_id(id);
-        match person.get_spouse() {
-            Some(spouse_id) => {
-                let mut spouse = self.pop_by_id(spouse_id);
-                spouse.get_attr_mut().pop_marriage();
-                info!("{} is now a widow/er", spouse.get_full_name());
-                self.add(spouse);
-            },
-            None => {}
+    /// Removes a person who died on the given date. The spouse is widowed and the estate goes to the heirs.
+    pub fn kill_person(&mut self, id: u32, date: Date) -> Result<(), SimulationError> {
+        self.check_spouse(id)?;
+        let person = self.take_by_id(id)?;
+        self.recent_deaths.push(id);
+        if let Some(spouse_id) = person.get_spouse() {
+            let mut spouse = self.pop_by_id(spouse_id)?;
+            spouse.end_marriage(date, MarriageEnd::Widowed);
+            spouse.get_attr_mut().set_mourning(date + self.marriage_rules.mourning_period);
+            info!("Person {} is now a widow/er", spouse.get_id());
+            self.add(spouse);
         }
+        self.bequeath(&person);
+        self.add_event(Event::Death { person, date });
+        Ok(())
     }
 
-    fn handle_fertility(&mut self, today: Date) {
+    fn bequeath(&mut self, person: &Person) {
+        let estate = *person.get_wealth();
+        if estate.is_empty() {
+            return;
+        }
+        let id = person.get_id();
+        let mut children: Vec<&Person> = self.lineage.get_children(id).iter()
+            .filter_map(|&child| self.get_by_id(child))
+            .collect();
+        children.sort_by_key(|p| p.get_birthday());
+        let heirs = Heirs {
+            spouse: person.get_spouse(),
+            children: children.iter().map(|p| p.get_id()).collect(),
+            eldest_son: children.iter().find(|p| p.get_attr().is_male()).map(|p| p.get_id())
+        };
+        let shares = self.inheritance_rule.distribute(estate, &heirs);
+        if shares.is_empty() {
+            info!("Person {} died without heirs, {} is lost", person.get_id(), estate);
+        }
+        for (heir_id, share) in shares {
+            if let Some(heir) = self.get_by_id_mut(heir_id) {
+                trace!("Person {} inherits {}", heir.get_id(), share);
+                *heir.get_wealth_mut() += share;
+            }
+        }
+    }
+
+    pub fn handle_fertility(&mut self, today: Date) {
         self.handle_fertility_gain(today);
         self.handle_fertility_lose(today);
     }
 
     fn handle_fertility_gain(&mut self, today: Date) {
-        let wanted = AttributeList::default();
-        let unwanted = AttributeList::builder().set_fertile().build();
-
-        for mut person in self.pop_by_attribute_list(&wanted, &unwanted).into_iter() {
-            let age = person.get_age(&today);
-            if age >= 14 && age < 40 {
-                trace!("{} is now fertile", person.get_full_name());
-                person.get_attr_mut().set_fertile();
-            }
-            self.add(person);
+        let gaining: Vec<usize> = self.population.iter()
+            .enumerate()
+            .filter(|(_, p)| !p.get_attr().is_fertile() && self.get_fertility(p, today) > 0.)
+            .map(|(i, _)| i)
+            .collect();
+        for index in gaining {
+            let person = Arc::make_mut(&mut self.persons_mut()[index]);
+            trace!("Person {} is now fertile", person.get_id());
+            person.get_attr_mut().set_fertile();
         }
     }
-    
-    fn handle_fertility_lose(&mut self, today: Date) {
-        let wanted = AttributeList::builder().set_fertile().build();
-        let unwanted = AttributeList::default();
 
-        for mut person in self.pop_by_attribute_list(&wanted, &unwanted).into_iter() {
-            if person.get_age(&today) >= 40 {
-                trace!("{} lost fertility", person.get_full_name());
-                person.get_attr_mut().clear_fertile();
-            }
-            self.add(person);
+    fn handle_fertility_lose(&mut self, today: Date) {
+        let losing: Vec<usize> = self.population.iter()
+            .enumerate()
+            .filter(|(_, p)| p.get_attr().is_fertile() && self.get_fertility(p, today) <= 0.)
+            .map(|(i, _)| i)
+            .collect();
+        for index in losing {
+            let person = Arc::make_mut(&mut self.persons_mut()[index]);
+            trace!("Person {} lost fertility", person.get_id());
+            person.get_attr_mut().clear_fertile();
         }
     }
     
@@ -338,10 +1183,15 @@ impl Population {
         wanted: &AttributeList,
         unwanted: &AttributeList,
         rng: &mut R) -> Option<usize> {
+        self.get_random_index_by_predicate(|p| p.satisfies(wanted, unwanted), rng)
+    }
+
+    fn get_random_index_by_predicate<F, R>(&self, predicate: F, rng: &mut R) -> Option<usize>
+    where F: Fn(&Person) -> bool, R: Rng + ?Sized {
         self.population.iter()
             .enumerate()
             .filter_map(|(i, p)|
-                if p.satisfies(wanted, unwanted) {
+                if predicate(p) {
                     Some(i)
                 } else {
                     None
@@ -349,6 +1199,35 @@ impl Population {
             .choose(rng)
     }
 
+    fn get_random_index_by_weight<F, R>(&self, weight: F, rng: &mut R) -> Option<usize>
+    where F: Fn(&Person) -> f32, R: Rng + ?Sized {
+        let weighted: Vec<(usize, f32)> = self.population.iter()
+            .enumerate()
+            .map(|(i, p)| (i, weight(p)))
+            .filter(|&(_, w)| w > 0.)
+            .collect();
+        let total = weighted.iter().fold(0., |acc, &(_, w)| acc + w);
+        if total <= 0. {
+            return None;
+        }
+        let mut target = rng.gen_range(0., total);
+        for &(i, w) in weighted.iter() {
+            if target < w {
+                return Some(i);
+            }
+            target -= w;
+        }
+        weighted.last().map(|&(i, _)| i)
+    }
+
+    fn get_fertility(&self, person: &Person, today: Date) -> f32 {
+        self.demography.get_fertility(person, person.get_age(&today))
+    }
+
+    fn get_reproduction_weight(&self, person: &Person, today: Date) -> f32 {
+        self.get_fertility(person, today) * person.get_social_class().get_fertility_modifier()
+    }
+
     fn get_first_index_by_attribute_list(&self, wanted: &AttributeList, unwanted: &AttributeList) -> Option<usize> {
         self.population.iter()
             .enumerate()
@@ -364,21 +1243,37 @@ impl Population {
 impl Default for Population {
     fn default() -> Population {
         Self {
-            population: Vec::new(),
+            population: Arc::new(Vec::new()),
+            positions: HashMap::new(),
+            pregnancies: BTreeSet::new(),
+            lineage: Lineage::default(),
+            recent_deaths: Vec::new(),
+            recent_events: Vec::new(),
+            listeners: Vec::new(),
             capacity: 50,
-            growth_accumulator: 0.
+            growth_accumulator: 0.,
+            config: PopulationConfig::default(),
+            marriage_rules: MarriageRules::default(),
+            demography: Demography::default(),
+            pregnancy_model: PregnancyModel::default(),
+            inheritance_rule: InheritanceRule::default(),
+            culture_rules: CultureRules::default(),
+            seasonality: Seasonality::default()
         }
     }
 }
 
 impl fmt::Display for Population  {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "size = {}, capacity = {}, female ratio = {:.2}, fertility ratio = {:.2}, marriage ratio = {:.2}, growth = {}, growth acc = {}",
+        write!(f, "size = {}, capacity = {}, female ratio = {:.2}, fertility ratio = {:.2}, marriage ratio = {:.2}, mean inbreeding = {:.4}, completed parity = {:.2}, wealth gini = {:.2}, growth = {}, growth acc = {}",
             self.population.len(),
             self.capacity,
             self.get_female_ratio(),
             self.get_fertility_ratio(),
             self.get_marriage_ratio(),
+            self.get_mean_inbreeding_coefficient(),
+            self.get_mean_completed_parity(),
+            self.get_wealth_gini(),
             self.calculate_growth(),
             self.growth_accumulator)
     }
@@ -391,3 +1286,69 @@ fn logistic(curr: f32, cap: f32, coefficent: f32) -> f32 {
 fn death_by_age_probability(age: u32) -> f32 {
     1. - 1. / f32::exp((age as f32 - 26.).powf(2.) * 1e-4)
 }
+
+impl WeightedIndices {
+    fn push(&mut self, index: usize, weight: f32) {
+        if weight > 0. {
+            let total = self.cumulative.last().cloned().unwrap_or(0.);
+            self.indices.push(index);
+            self.cumulative.push(total + weight as f64);
+        }
+    }
+
+    fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<usize> {
+        let total = *self.cumulative.last()?;
+        let target = rng.gen_range(0., total);
+        let position = self.cumulative.partition_point(|&sum| sum <= target);
+        self.indices.get(position.min(self.indices.len() - 1)).cloned()
+    }
+}
+
+impl AgeBuckets {
+    fn push(&mut self, age: u32, id: u32) {
+        let age = age as usize;
+        if self.buckets.len() <= age {
+            self.buckets.resize_with(age + 1, Vec::new);
+        }
+        self.buckets[age].push(id);
+    }
+
+    fn get(&self, age: u32, position: usize) -> u32 {
+        self.buckets[age as usize][position]
+    }
+
+    fn remove(&mut self, age: u32, position: usize) {
+        self.buckets[age as usize].swap_remove(position);
+    }
+
+    // age and position of a person between the given ages, every person is equally likely
+    fn choose<R: Rng + ?Sized>(&self, min_age: u32, max_age: u32, rng: &mut R) -> Option<(u32, usize)> {
+        let ages = min_age as usize..(max_age as usize + 1).min(self.buckets.len());
+        let total: usize = self.buckets.get(ages.clone())?.iter().map(Vec::len).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut target = rng.gen_range(0, total);
+        for age in ages {
+            let count = self.buckets[age].len();
+            if target < count {
+                return Some((age as u32, target));
+            }
+            target -= count;
+        }
+        None
+    }
+}
+
+// index of a weight chosen with a chance proportional to it
+fn choose_weighted<R: Rng + ?Sized>(weights: &[f32], total: f32, rng: &mut R) -> usize {
+    let mut target = rng.gen_range(0., total);
+    for (index, &weight) in weights.iter().enumerate() {
+        if target < weight {
+            return index;
+        }
+        target -= weight;
+    }
+    // rounding may leave the target just above the last weight
+    weights.iter().rposition(|&weight| weight > 0.).unwrap_or(0)
+}
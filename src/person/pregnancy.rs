@@ -0,0 +1,88 @@
+use rand::Rng;
+use serde::{ Serialize, Deserialize };
+
+use crate::utility::{ Date, DAYS_PER_MONTH };
+use super::AgeCurve;
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum PregnancyOutcome {
+    LiveBirth,
+    Stillbirth,
+    Miscarriage
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PregnancyModel {
+    pub length: u32,
+    pub length_deviation: u32,
+    pub postpartum_period: u32,
+    pub miscarriage_recovery: u32,
+    pub twin_chance: f64,
+    pub triplet_chance: f64,
+    // chances by age of the mother
+    pub miscarriage_chance: AgeCurve,
+    pub stillbirth_chance: AgeCurve
+}
+
+impl PregnancyModel {
+
+    pub fn roll_outcome<R: Rng + ?Sized>(&self, mother_age: u32, rng: &mut R) -> PregnancyOutcome {
+        let miscarriage = self.miscarriage_chance.get_rate(mother_age) as f64;
+        let stillbirth = self.stillbirth_chance.get_rate(mother_age) as f64;
+        let roll = rng.gen::<f64>();
+        if roll < miscarriage {
+            PregnancyOutcome::Miscarriage
+        } else if roll < miscarriage + stillbirth {
+            PregnancyOutcome::Stillbirth
+        } else {
+            PregnancyOutcome::LiveBirth
+        }
+    }
+
+    pub fn roll_count<R: Rng + ?Sized>(&self, rng: &mut R) -> u32 {
+        let roll = rng.gen::<f64>();
+        if roll < self.triplet_chance {
+            3
+        } else if roll < self.triplet_chance + self.twin_chance {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Date on which the pregnancy ends, miscarriages end within the first months.
+    pub fn roll_end<R: Rng + ?Sized>(&self, conception: Date, outcome: PregnancyOutcome, rng: &mut R) -> Date {
+        match outcome {
+            PregnancyOutcome::Miscarriage => conception + rng.gen_range(DAYS_PER_MONTH, self.length / 2),
+            _ => conception + rng.gen_range(self.length - self.length_deviation, self.length + self.length_deviation)
+        }
+    }
+
+    /// Date until which the mother can't conceive again.
+    pub fn get_recovery_end(&self, end: Date, outcome: PregnancyOutcome) -> Date {
+        match outcome {
+            PregnancyOutcome::Miscarriage => end + self.miscarriage_recovery,
+            _ => end + self.postpartum_period
+        }
+    }
+}
+
+impl Default for PregnancyModel {
+    fn default() -> Self {
+        Self {
+            length: DAYS_PER_MONTH * 9,
+            length_deviation: 10,
+            postpartum_period: DAYS_PER_MONTH * 12,
+            miscarriage_recovery: DAYS_PER_MONTH * 3,
+            twin_chance: 0.012,
+            triplet_chance: 0.0002,
+            miscarriage_chance: AgeCurve::new(vec![
+                (15, 0.15), (25, 0.1), (30, 0.12), (35, 0.2), (40, 0.35), (45, 0.55)
+            ]),
+            stillbirth_chance: AgeCurve::new(vec![
+                (15, 0.04), (25, 0.025), (35, 0.035), (45, 0.06)
+            ])
+        }
+    }
+}
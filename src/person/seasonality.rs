@@ -0,0 +1,76 @@
+use rand::Rng;
+use serde::{ Serialize, Deserialize };
+
+use crate::utility::{ Date, Season };
+
+/// Relative frequency of an event in each season.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SeasonWeights {
+    pub winter: f32,
+    pub spring: f32,
+    pub summer: f32,
+    pub autumn: f32
+}
+
+/// When in the year weddings, conceptions and deaths happen.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Seasonality {
+    pub marriage: SeasonWeights,
+    pub conception: SeasonWeights,
+    pub mortality: SeasonWeights
+}
+
+impl SeasonWeights {
+
+    pub fn get(&self, season: Season) -> f32 {
+        match season {
+            Season::Winter => self.winter,
+            Season::Spring => self.spring,
+            Season::Summer => self.summer,
+            Season::Autumn => self.autumn
+        }
+    }
+
+    /// Random day of the year beginning at `start`, days in heavier seasons are more likely.
+    pub fn random_date<R: Rng + ?Sized>(&self, start: Date, rng: &mut R) -> Date {
+        let length = start.get_days_in_year();
+        // the months within the year, as (offset from start, days, weight)
+        let mut months = Vec::new();
+        let mut offset = 0;
+        while offset < length {
+            let month_start = start + offset;
+            let days = (month_start.get_days_in_month() - month_start.get_day()).min(length - offset);
+            months.push((offset, days, days as f32 * self.get(month_start.get_season())));
+            offset += days;
+        }
+        let total = months.iter().fold(0., |acc, &(_, _, weight)| acc + weight);
+        if total <= 0. {
+            return start + rng.gen_range(0, length);
+        }
+        let mut target = rng.gen_range(0., total);
+        for &(offset, days, weight) in months.iter() {
+            if target < weight {
+                return start + offset + rng.gen_range(0, days);
+            }
+            target -= weight;
+        }
+        start + rng.gen_range(0, length)
+    }
+
+    /// Random day of the year ending before `end`.
+    pub fn random_past_date<R: Rng + ?Sized>(&self, end: Date, rng: &mut R) -> Date {
+        self.random_date(end - end.get_days_in_year(), rng)
+    }
+}
+
+impl Default for Seasonality {
+    fn default() -> Self {
+        Self {
+            // weddings follow the harvest
+            marriage: SeasonWeights { winter: 0.6, spring: 0.8, summer: 0.9, autumn: 1.7 },
+            conception: SeasonWeights { winter: 0.9, spring: 1.2, summer: 1.0, autumn: 0.9 },
+            mortality: SeasonWeights { winter: 1.5, spring: 1.0, summer: 0.7, autumn: 0.8 }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+use rand::Rng;
+
+use super::{ Wealth, Occupation };
+
+const BURGHER_MIN_WEALTH: u64 = 200;
+const BURGHER_KEEP_WEALTH: u64 = 20;
+const NOBILITY_MIN_LAND: u32 = 30;
+const ENNOBLEMENT_CHANCE: f64 = 0.05;
+const CLERGY_VOCATION_CHANCE: f64 = 0.005;
+// relative preference for a partner of another class
+pub const CROSS_CLASS_MARRIAGE_WEIGHT: f32 = 0.1;
+
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash, Debug)]
+pub enum SocialClass {
+    Nobility,
+    Clergy,
+    Burgher,
+    #[default]
+    Peasant
+}
+
+impl SocialClass {
+
+    pub const ALL: [SocialClass; 4] = [
+        SocialClass::Nobility,
+        SocialClass::Clergy,
+        SocialClass::Burgher,
+        SocialClass::Peasant
+    ];
+
+    pub fn random_founder<R: Rng + ?Sized>(rng: &mut R) -> SocialClass {
+        match rng.gen_range(0, 100) {
+            0 ..= 2 => SocialClass::Nobility,
+            3 ..= 7 => SocialClass::Clergy,
+            8 ..= 27 => SocialClass::Burgher,
+            _ => SocialClass::Peasant
+        }
+    }
+
+    pub fn get_founding_wealth_factor(&self) -> u32 {
+        match self {
+            SocialClass::Nobility => 10,
+            SocialClass::Clergy => 2,
+            SocialClass::Burgher => 3,
+            SocialClass::Peasant => 1
+        }
+    }
+
+    pub fn get_mortality_modifier(&self) -> f32 {
+        match self {
+            SocialClass::Nobility => 0.8,
+            SocialClass::Clergy => 0.9,
+            SocialClass::Burgher => 0.95,
+            SocialClass::Peasant => 1.1
+        }
+    }
+
+    pub fn get_fertility_modifier(&self) -> f32 {
+        match self {
+            SocialClass::Nobility => 1.1,
+            SocialClass::Clergy => 0.,
+            SocialClass::Burgher => 1.,
+            SocialClass::Peasant => 1.
+        }
+    }
+
+    pub fn can_marry(&self) -> bool {
+        *self != SocialClass::Clergy
+    }
+
+    pub fn get_marriage_weight(&self, other: SocialClass) -> f32 {
+        if *self == other {
+            1.
+        } else {
+            CROSS_CLASS_MARRIAGE_WEIGHT
+        }
+    }
+
+    /// Class after a year of work, None if the class doesn't change.
+    pub fn get_mobility<R: Rng + ?Sized>(&self, occupation: Option<Occupation>, wealth: &Wealth, unmarried: bool, rng: &mut R) -> Option<SocialClass> {
+        if *self != SocialClass::Clergy && unmarried && rng.gen_bool(CLERGY_VOCATION_CHANCE) {
+            return Some(SocialClass::Clergy);
+        }
+        match (self, occupation) {
+            (SocialClass::Peasant, Some(Occupation::Craftsman)) |
+            (SocialClass::Peasant, Some(Occupation::Merchant)) if wealth.get_value() >= BURGHER_MIN_WEALTH =>
+                Some(SocialClass::Burgher),
+            (SocialClass::Burgher, _) if wealth.get_land() >= NOBILITY_MIN_LAND && rng.gen_bool(ENNOBLEMENT_CHANCE) =>
+                Some(SocialClass::Nobility),
+            (SocialClass::Burgher, _) if wealth.get_value() < BURGHER_KEEP_WEALTH =>
+                Some(SocialClass::Peasant),
+            (_, _) => None
+        }
+    }
+}
+
+impl fmt::Display for SocialClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SocialClass::Nobility => write!(f, "nobility"),
+            SocialClass::Clergy => write!(f, "clergy"),
+            SocialClass::Burgher => write!(f, "burgher"),
+            SocialClass::Peasant => write!(f, "peasant")
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt;
+use std::ops::AddAssign;
+use rand::Rng;
+use serde::{ Serialize, Deserialize };
+
+pub const LAND_PRICE: u32 = 50;
+pub const HOUSE_PRICE: u32 = 100;
+pub const YEARLY_LIVING_COST: u32 = 3;
+// nobody buys land beyond this
+pub const MAX_LAND: u32 = 500;
+
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub struct Wealth {
+    coin: u32,
+    land: u32,
+    houses: u32
+}
+
+#[derive(Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InheritanceRule {
+    // land and houses go to the eldest son, coin is split among all children
+    Primogeniture,
+    #[default]
+    EqualSplit,
+    SpouseFirst
+}
+
+/// Living heirs of a deceased person, children ordered from eldest to youngest.
+pub struct Heirs {
+    pub spouse: Option<u32>,
+    pub children: Vec<u32>,
+    pub eldest_son: Option<u32>
+}
+
+impl Wealth {
+    pub fn new(coin: u32, land: u32, houses: u32) -> Self {
+        Self {
+            coin,
+            land,
+            houses
+        }
+    }
+
+    pub fn random_founder<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let land = if rng.gen_bool(0.1) {
+            rng.gen_range(5, 20)
+        } else {
+            rng.gen_range(0, 3)
+        };
+        let houses = if rng.gen_bool(0.5) { 1 } else { 0 };
+        Self::new(rng.gen_range(0, 10) * rng.gen_range(0, 10), land, houses)
+    }
+
+    pub fn get_coin(&self) -> u32 {
+        self.coin
+    }
+    pub fn get_land(&self) -> u32 {
+        self.land
+    }
+    pub fn get_houses(&self) -> u32 {
+        self.houses
+    }
+
+    pub fn get_value(&self) -> u64 {
+        self.coin as u64 + self.land as u64 * LAND_PRICE as u64 + self.houses as u64 * HOUSE_PRICE as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coin == 0 && self.land == 0 && self.houses == 0
+    }
+
+    pub fn scale(&mut self, factor: u32) {
+        self.coin = self.coin.saturating_mul(factor);
+        self.land = self.land.saturating_mul(factor);
+    }
+
+    pub fn earn(&mut self, amount: u32) {
+        self.coin = self.coin.saturating_add(amount);
+    }
+
+    pub fn spend(&mut self, amount: u32) {
+        self.coin = self.coin.saturating_sub(amount);
+    }
+
+    /// Turns surplus coin into land or a first house, up to `MAX_LAND` plots.
+    pub fn invest(&mut self) {
+        if self.houses == 0 && self.coin >= HOUSE_PRICE {
+            self.coin -= HOUSE_PRICE;
+            self.houses += 1;
+        }
+        while self.coin >= 2 * LAND_PRICE && self.land < MAX_LAND {
+            self.coin -= LAND_PRICE;
+            self.land += 1;
+        }
+    }
+
+    /// Splits into parts as equal as possible, the remainder goes to the first parts.
+    pub fn split(&self, parts: u32) -> Vec<Wealth> {
+        debug_assert!(parts > 0);
+        (0..parts)
+            .map(|i| Wealth::new(
+                share(self.coin, parts, i),
+                share(self.land, parts, i),
+                share(self.houses, parts, i)))
+            .collect()
+    }
+}
+
+impl AddAssign for Wealth {
+    fn add_assign(&mut self, other: Wealth) {
+        self.coin = self.coin.saturating_add(other.coin);
+        self.land = self.land.saturating_add(other.land);
+        self.houses = self.houses.saturating_add(other.houses);
+    }
+}
+
+impl InheritanceRule {
+
+    pub fn distribute(&self, estate: Wealth, heirs: &Heirs) -> Vec<(u32, Wealth)> {
+        match (self, heirs.spouse, heirs.children.is_empty()) {
+            (_, None, true) => Vec::new(),
+            (InheritanceRule::SpouseFirst, Some(spouse), _) |
+            (_, Some(spouse), true) => vec![(spouse, estate)],
+            (InheritanceRule::Primogeniture, _, false) => {
+                let heir = heirs.eldest_son.unwrap_or(heirs.children[0]);
+                let coin = Wealth::new(estate.coin, 0, 0);
+                let mut shares = split_among(coin, &heirs.children);
+                shares.push((heir, Wealth::new(0, estate.land, estate.houses)));
+                shares
+            },
+            (_, _, false) => split_among(estate, &heirs.children)
+        }
+    }
+}
+
+fn split_among(estate: Wealth, heirs: &[u32]) -> Vec<(u32, Wealth)> {
+    heirs.iter()
+        .cloned()
+        .zip(estate.split(heirs.len() as u32))
+        .collect()
+}
+
+fn share(amount: u32, parts: u32, index: u32) -> u32 {
+    amount / parts + if index < amount % parts { 1 } else { 0 }
+}
+
+/// Gini coefficient of the given values, 0 is perfect equality.
+pub fn gini(values: &mut [u64]) -> f32 {
+    let total: u64 = values.iter().fold(0u64, |acc, &v| acc.saturating_add(v));
+    if values.is_empty() || total == 0 {
+        return 0.;
+    }
+    values.sort();
+    let n = values.len() as f64;
+    let weighted: f64 = values.iter()
+        .enumerate()
+        .map(|(i, &v)| (i as f64 + 1.) * v as f64)
+        .sum();
+    ((2. * weighted) / (n * total as f64) - (n + 1.) / n) as f32
+}
+
+impl fmt::Display for Wealth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "coin = {}, land = {}, houses = {}", self.coin, self.land, self.houses)
+    }
+}
+
+impl fmt::Display for InheritanceRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InheritanceRule::Primogeniture => write!(f, "primogeniture"),
+            InheritanceRule::EqualSplit => write!(f, "equal split"),
+            InheritanceRule::SpouseFirst => write!(f, "spouse first")
+        }
+    }
+}
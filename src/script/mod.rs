@@ -0,0 +1,5 @@
+pub mod script_engine;
+pub mod script_town;
+
+pub use self::script_engine::ScriptEngine;
+pub use self::script_town::ScriptTown;
@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use rand::{ Rng, SeedableRng };
+use rand::rngs::SmallRng;
+use rhai::{ Engine, AST, Scope, Dynamic };
+
+use crate::utility::{ Date, ResourceLocator, SymbolTable };
+use crate::utility::application_error::ApplicationError;
+use crate::utility::file_error::FileError;
+use crate::utility::script_error::ScriptError;
+use crate::utility::read_file::read_file;
+use crate::person::Population;
+use crate::town::Chronicle;
+use crate::simulation::{ SimulationPhase, PhaseContext };
+use crate::simulation::pipeline::SCRIPT_PHASE;
+use super::script_town::{ ScriptTown, ScriptState };
+
+const RULES_DIR: &str = "rules";
+const SCRIPT_EXTENSION: &str = "rhai";
+const YEARLY_PHASE: &str = "on_year";
+// keeps endless loops in a script from stalling the simulation
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Rule scripts from the `rules` resource directory. Every script defining
+/// `fn on_year(town)` adds a yearly phase, run in order of the file names.
+pub struct ScriptEngine {
+    engine: Engine,
+    scripts: Vec<(String, AST)>
+}
+
+impl ScriptEngine {
+
+    pub fn new() -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.on_print(|text| info!("Script: {}", text));
+        engine.on_debug(|text, _, _| debug!("Script: {}", text));
+        ScriptTown::register(&mut engine);
+        Self {
+            engine,
+            scripts: Vec::new()
+        }
+    }
+
+    pub fn with_resources(resources: &ResourceLocator) -> Result<Self, ApplicationError> {
+        let mut engine = ScriptEngine::new();
+        if let Some(dir) = resources.get_dir() {
+            let rules_dir = dir.join(RULES_DIR);
+            if rules_dir.is_dir() {
+                engine.load_dir(&rules_dir)?;
+            }
+        }
+        Ok(engine)
+    }
+
+    /// Loads all scripts of the directory, sorted by file name.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<(), ApplicationError> {
+        let mut files: Vec<_> = fs::read_dir(dir)
+            .map_err(|err| FileError::Read(dir.to_string_lossy().into_owned(), err))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == SCRIPT_EXTENSION))
+            .collect();
+        files.sort();
+        for file in files {
+            self.load_file(&file)?;
+        }
+        Ok(())
+    }
+
+    pub fn load_file(&mut self, path: &Path) -> Result<(), ApplicationError> {
+        let path_str = path.to_string_lossy().into_owned();
+        let source = read_file(&path_str)?;
+        let name = path.file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path_str.clone());
+        self.add_script(&name, &source)
+            .map_err(|err| match err {
+                ScriptError::Parse(_, msg) => ScriptError::Parse(path_str, msg),
+                err => err
+            })?;
+        Ok(())
+    }
+
+    pub fn add_script(&mut self, name: &str, source: &str) -> Result<(), ScriptError> {
+        let ast = self.engine.compile(source)
+            .map_err(|err| ScriptError::Parse(name.to_owned(), err.to_string()))?;
+        info!("Loaded rule script '{}'", name);
+        self.scripts.push((name.to_owned(), ast));
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Runs the yearly phases of all scripts. The random numbers of the scripts are
+    /// drawn from the given generator, so runs are reproducible with the same seed.
+    pub fn run_year<R: Rng + ?Sized>(&self, name: &str, date: Date, population: &mut Population, symbols: Arc<SymbolTable>, chronicle: &mut Chronicle, rng: &mut R) -> Result<(), ScriptError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let script_rng = SmallRng::from_rng(rng)
+            .map_err(|err| ScriptError::Runtime(name.to_owned(), err.to_string()))?;
+        let town = ScriptTown::new(ScriptState {
+            name: name.to_owned(),
+            date,
+            population: std::mem::take(population),
+            symbols,
+            rng: script_rng,
+            chronicle: Vec::new()
+        });
+
+        let mut result = Ok(());
+        for (script, ast) in self.scripts.iter().filter(|(_, ast)| has_function(ast, YEARLY_PHASE)) {
+            let call = self.engine.call_fn::<Dynamic>(&mut Scope::new(), ast, YEARLY_PHASE, (town.clone(),));
+            if let Err(err) = call {
+                result = Err(ScriptError::Runtime(script.clone(), err.to_string()));
+                break;
+            }
+        }
+
+        let state = town.into_state();
+        *population = state.population;
+        for text in state.chronicle {
+            chronicle.add_entry(date, &text);
+        }
+        result
+    }
+}
+
+impl SimulationPhase for ScriptEngine {
+
+    fn get_name(&self) -> &str {
+        SCRIPT_PHASE
+    }
+
+    fn run(&self, context: &mut PhaseContext) -> Result<(), ApplicationError> {
+        self.run_year(context.town_name, context.date, context.population, context.person_generator.share_symbols(), context.chronicle, context.rng)?;
+        Ok(())
+    }
+}
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        ScriptEngine::new()
+    }
+}
+
+fn has_function(ast: &AST, name: &str) -> bool {
+    ast.iter_functions().any(|f| f.name == name && f.params.len() == 1)
+}
@@ -0,0 +1,140 @@
+use std::sync::{ Arc, Mutex, MutexGuard };
+use rand::Rng;
+use rand::rngs::SmallRng;
+use rhai::{ Engine, EvalAltResult, Array, Dynamic };
+
+use crate::utility::{ Date, SymbolTable };
+use crate::person::{ Person, Population, Event };
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// State of a town while the rule scripts run, taken out of the town and put back afterwards.
+pub struct ScriptState {
+    pub name: String,
+    pub date: Date,
+    pub population: Population,
+    // names of the persons, see `PersonGenerator`
+    pub symbols: Arc<SymbolTable>,
+    pub rng: SmallRng,
+    pub chronicle: Vec<String>
+}
+
+/// Handle passed to the scripts as `town`. Persons are only accessible by id,
+/// so scripts can never hold on to a person outside of the population.
+#[derive(Clone)]
+pub struct ScriptTown(Arc<Mutex<ScriptState>>);
+
+impl ScriptTown {
+
+    pub fn new(state: ScriptState) -> Self {
+        ScriptTown(Arc::new(Mutex::new(state)))
+    }
+
+    pub fn into_state(self) -> ScriptState {
+        match Arc::try_unwrap(self.0) {
+            Ok(mutex) => mutex.into_inner().unwrap_or_else(|err| err.into_inner()),
+            Err(_) => unreachable!("Script town handles must not outlive the script run")
+        }
+    }
+
+    pub fn register(engine: &mut Engine) {
+        engine.register_type_with_name::<ScriptTown>("Town")
+            .register_get("name", |t: &mut ScriptTown| t.lock().name.clone())
+            .register_get("year", |t: &mut ScriptTown| t.lock().date.get_year() as i64)
+            .register_get("size", |t: &mut ScriptTown| t.lock().population.size() as i64)
+            .register_fn("persons", ScriptTown::persons)
+            .register_fn("first_name", |t: &mut ScriptTown, id: i64| t.with_name(id, |p, symbols| symbols.resolve(p.get_first_name()).to_owned()))
+            .register_fn("last_name", |t: &mut ScriptTown, id: i64| t.with_name(id, |p, symbols| symbols.resolve(p.get_last_name()).to_owned()))
+            .register_fn("full_name", |t: &mut ScriptTown, id: i64| t.with_name(id, |p, symbols| p.get_full_name(symbols)))
+            .register_fn("is_male", |t: &mut ScriptTown, id: i64| t.with_person(id, |p| p.get_attr().is_male()))
+            .register_fn("is_married", |t: &mut ScriptTown, id: i64| t.with_person(id, |p| p.get_attr().is_married()))
+            .register_fn("social_class", |t: &mut ScriptTown, id: i64| t.with_person(id, |p| p.get_social_class().to_string()))
+            .register_fn("coin", |t: &mut ScriptTown, id: i64| t.with_person(id, |p| p.get_wealth().get_coin() as i64))
+            .register_fn("age", ScriptTown::age)
+            .register_fn("get", |t: &mut ScriptTown, id: i64, key: &str| t.with_person(id, |p| p.get_custom(key).unwrap_or(0)))
+            .register_fn("set", |t: &mut ScriptTown, id: i64, key: &str, value: i64| t.with_person_mut(id, |p| p.set_custom(key, value)))
+            .register_fn("give_coin", |t: &mut ScriptTown, id: i64, amount: i64| t.with_person_mut(id, |p| p.get_wealth_mut().earn(clamp_amount(amount))))
+            .register_fn("take_coin", |t: &mut ScriptTown, id: i64, amount: i64| t.with_person_mut(id, |p| p.get_wealth_mut().spend(clamp_amount(amount))))
+            .register_fn("chronicle", |t: &mut ScriptTown, text: &str| t.lock().chronicle.push(text.to_owned()))
+            .register_fn("emit", |t: &mut ScriptTown, name: &str| t.lock().population.add_event(Event::Custom { name: name.to_owned(), person: None }))
+            .register_fn("emit", ScriptTown::emit_for_person)
+            .register_fn("random", |t: &mut ScriptTown| t.lock().rng.gen::<f64>())
+            .register_fn("chance", ScriptTown::chance)
+            .register_fn("random_int", ScriptTown::random_int);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ScriptState> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // ascending ids, so the order doesn't depend on the population layout
+    fn persons(&mut self) -> Array {
+        let mut ids: Vec<u32> = self.lock().population.iter()
+            .map(|p| p.get_id())
+            .collect();
+        ids.sort();
+        ids.into_iter().map(|id| Dynamic::from(id as i64)).collect()
+    }
+
+    fn age(&mut self, id: i64) -> ScriptResult<i64> {
+        let today = self.lock().date;
+        self.with_person(id, |p| p.get_age(&today) as i64)
+    }
+
+    fn emit_for_person(&mut self, name: &str, id: i64) -> ScriptResult<()> {
+        let mut state = self.lock();
+        match state.population.get_shared_by_id(id as u32) {
+            Some(person) if id >= 0 => {
+                state.population.add_event(Event::Custom { name: name.to_owned(), person: Some(person) });
+                Ok(())
+            },
+            _ => Err(unknown_person(id))
+        }
+    }
+
+    fn chance(&mut self, probability: f64) -> ScriptResult<bool> {
+        match (0. ..=1.).contains(&probability) {
+            true => Ok(self.lock().rng.gen_bool(probability)),
+            false => Err(format!("invalid probability {}", probability).into())
+        }
+    }
+
+    fn random_int(&mut self, min: i64, max: i64) -> ScriptResult<i64> {
+        match min < max {
+            true => Ok(self.lock().rng.gen_range(min, max)),
+            false => Err(format!("invalid range {}..{}", min, max).into())
+        }
+    }
+
+    fn with_person<T, F: FnOnce(&Person) -> T>(&self, id: i64, f: F) -> ScriptResult<T> {
+        let state = self.lock();
+        match state.population.get_by_id(id as u32) {
+            Some(person) if id >= 0 => Ok(f(person)),
+            _ => Err(unknown_person(id))
+        }
+    }
+
+    fn with_name<F: FnOnce(&Person, &SymbolTable) -> String>(&self, id: i64, f: F) -> ScriptResult<String> {
+        let state = self.lock();
+        match state.population.get_by_id(id as u32) {
+            Some(person) if id >= 0 => Ok(f(person, &state.symbols)),
+            _ => Err(unknown_person(id))
+        }
+    }
+
+    fn with_person_mut<T, F: FnOnce(&mut Person) -> T>(&self, id: i64, f: F) -> ScriptResult<T> {
+        let mut state = self.lock();
+        match state.population.get_by_id_mut(id as u32) {
+            Some(person) if id >= 0 => Ok(f(person)),
+            _ => Err(unknown_person(id))
+        }
+    }
+}
+
+fn unknown_person(id: i64) -> Box<EvalAltResult> {
+    format!("unknown person {}", id).into()
+}
+
+fn clamp_amount(amount: i64) -> u32 {
+    amount.max(0).min(u32::MAX as i64) as u32
+}
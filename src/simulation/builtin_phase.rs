@@ -0,0 +1,81 @@
+use std::fmt;
+
+use crate::utility::application_error::ApplicationError;
+use super::{ SimulationPhase, PhaseContext };
+
+/// Phases of the population update provided by the crate.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum BuiltinPhase {
+    Births,
+    Deaths,
+    Divorces,
+    Economy,
+    Culture,
+    Growth,
+    Fertility,
+    ExpiredAttributes,
+    Marriages
+}
+
+impl BuiltinPhase {
+
+    // default order of the pipeline
+    pub const ALL: [BuiltinPhase; 9] = [
+        BuiltinPhase::Births,
+        BuiltinPhase::Deaths,
+        BuiltinPhase::Divorces,
+        BuiltinPhase::Economy,
+        BuiltinPhase::Culture,
+        BuiltinPhase::Growth,
+        BuiltinPhase::Fertility,
+        BuiltinPhase::ExpiredAttributes,
+        BuiltinPhase::Marriages
+    ];
+
+    pub fn from_name(name: &str) -> Option<BuiltinPhase> {
+        BuiltinPhase::ALL.iter()
+            .find(|phase| phase.get_name() == name)
+            .cloned()
+    }
+}
+
+impl SimulationPhase for BuiltinPhase {
+
+    fn get_name(&self) -> &str {
+        match self {
+            BuiltinPhase::Births => "births",
+            BuiltinPhase::Deaths => "deaths",
+            BuiltinPhase::Divorces => "divorces",
+            BuiltinPhase::Economy => "economy",
+            BuiltinPhase::Culture => "culture",
+            BuiltinPhase::Growth => "growth",
+            BuiltinPhase::Fertility => "fertility",
+            BuiltinPhase::ExpiredAttributes => "expired_attributes",
+            BuiltinPhase::Marriages => "marriages"
+        }
+    }
+
+    fn run(&self, context: &mut PhaseContext) -> Result<(), ApplicationError> {
+        let today = context.date;
+        let population = &mut *context.population;
+        let rng = &mut *context.rng;
+        match self {
+            BuiltinPhase::Births => population.handle_births(today, context.person_generator, rng)?,
+            BuiltinPhase::Deaths => population.handle_deaths(today, rng)?,
+            BuiltinPhase::Divorces => population.handle_divorces(today, rng)?,
+            BuiltinPhase::Economy => population.handle_economy(today, rng),
+            BuiltinPhase::Culture => population.handle_culture(today, rng),
+            BuiltinPhase::Growth => population.handle_growth(today, rng),
+            BuiltinPhase::Fertility => population.handle_fertility(today),
+            BuiltinPhase::ExpiredAttributes => population.handle_expired_attributes(today),
+            BuiltinPhase::Marriages => population.handle_marriages(today, rng)?
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for BuiltinPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_name())
+    }
+}
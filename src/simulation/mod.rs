@@ -0,0 +1,7 @@
+pub mod phase;
+pub mod builtin_phase;
+pub mod pipeline;
+
+pub use self::phase::{ SimulationPhase, PhaseContext };
+pub use self::builtin_phase::BuiltinPhase;
+pub use self::pipeline::Pipeline;
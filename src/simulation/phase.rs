@@ -0,0 +1,23 @@
+use rand::RngCore;
+
+use crate::utility::Date;
+use crate::utility::application_error::ApplicationError;
+use crate::person::{ Population, PersonGenerator };
+use crate::town::Chronicle;
+
+/// Everything a phase may read or change while a town is updated.
+pub struct PhaseContext<'a> {
+    pub town_name: &'a str,
+    pub date: Date,
+    pub population: &'a mut Population,
+    pub chronicle: &'a mut Chronicle,
+    pub person_generator: &'a mut PersonGenerator,
+    pub rng: &'a mut dyn RngCore
+}
+
+/// One step of the yearly town update. Phases are shared between towns,
+/// so all state has to live in the context.
+pub trait SimulationPhase: Send + Sync {
+    fn get_name(&self) -> &str;
+    fn run(&self, context: &mut PhaseContext) -> Result<(), ApplicationError>;
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use crate::utility::application_error::ApplicationError;
+use crate::utility::config_error::ConfigError;
+use crate::script::ScriptEngine;
+use super::{ SimulationPhase, PhaseContext, BuiltinPhase };
+
+pub const SCRIPT_PHASE: &str = "scripts";
+
+/// Ordered phases of the yearly town update.
+#[derive(Clone)]
+pub struct Pipeline {
+    phases: Vec<Arc<dyn SimulationPhase>>
+}
+
+impl Pipeline {
+
+    pub fn empty() -> Self {
+        Self {
+            phases: Vec::new()
+        }
+    }
+
+    /// The built-in phases followed by the rule scripts.
+    pub fn with_scripts(scripts: Arc<ScriptEngine>) -> Self {
+        let mut pipeline = Pipeline::default();
+        pipeline.add(scripts);
+        pipeline
+    }
+
+    /// Builds a pipeline from phase names, `scripts` stands for the rule scripts.
+    pub fn from_names(names: &[String], scripts: Arc<ScriptEngine>) -> Result<Self, ConfigError> {
+        let mut pipeline = Pipeline::empty();
+        for name in names {
+            match BuiltinPhase::from_name(name) {
+                Some(phase) => pipeline.add(Arc::new(phase)),
+                None if name == SCRIPT_PHASE => pipeline.add(scripts.clone()),
+                None => return Err(ConfigError::Invalid(format!("unknown phase '{}'", name)))
+            }
+        }
+        Ok(pipeline)
+    }
+
+    pub fn is_known_phase(name: &str) -> bool {
+        name == SCRIPT_PHASE || BuiltinPhase::from_name(name).is_some()
+    }
+
+    pub fn add(&mut self, phase: Arc<dyn SimulationPhase>) {
+        self.phases.push(phase);
+    }
+
+    /// Inserts the phase before the first phase with the given name, returns false if there is none.
+    pub fn insert_before(&mut self, name: &str, phase: Arc<dyn SimulationPhase>) -> bool {
+        match self.find(name) {
+            Some(index) => {
+                self.phases.insert(index, phase);
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Inserts the phase after the first phase with the given name, returns false if there is none.
+    pub fn insert_after(&mut self, name: &str, phase: Arc<dyn SimulationPhase>) -> bool {
+        match self.find(name) {
+            Some(index) => {
+                self.phases.insert(index + 1, phase);
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Removes all phases with the given name, returns false if there were none.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.phases.len();
+        self.phases.retain(|phase| phase.get_name() != name);
+        self.phases.len() != len
+    }
+
+    pub fn get_names(&self) -> Vec<&str> {
+        self.phases.iter()
+            .map(|phase| phase.get_name())
+            .collect()
+    }
+
+    pub fn run(&self, context: &mut PhaseContext) -> Result<(), ApplicationError> {
+        context.population.begin_update();
+        for phase in &self.phases {
+            trace!("Running phase '{}'", phase.get_name());
+            phase.run(context)?;
+        }
+        Ok(())
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.phases.iter().position(|phase| phase.get_name() == name)
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self {
+            phases: BuiltinPhase::ALL.iter()
+                .map(|&phase| Arc::new(phase) as Arc<dyn SimulationPhase>)
+                .collect()
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+
+use crate::utility::Date;
+
+#[derive(Clone)]
+pub struct Entry {
+    date: Date,
+    text: String
+}
+
+#[derive(Clone)]
+pub struct Reign {
+    ruler_id: u32,
+    name: String,
+    title: String,
+    start: Date,
+    end: Option<Date>
+}
+
+#[derive(Clone, Default)]
+pub struct Chronicle {
+    entries: Vec<Entry>,
+    rulers: Vec<Reign>
+}
+
+/// Length of a chronicle to go back to, see `Chronicle::mark`.
+#[derive(Clone, Copy)]
+pub struct Mark {
+    entries: usize,
+    rulers: usize,
+    // end of the last reign, which may be set later on
+    end: Option<Date>
+}
+
+impl Chronicle {
+
+    pub fn add_entry(&mut self, date: Date, text: &str) {
+        info!("Chronicle {}: {}", date, text);
+        self.entries.push(Entry {
+            date,
+            text: text.to_owned()
+        });
+    }
+
+    pub fn begin_reign(&mut self, ruler_id: u32, name: &str, title: &str, start: Date) {
+        self.add_entry(start, &format!("{} becomes {}", name, title));
+        self.rulers.push(Reign {
+            ruler_id,
+            name: name.to_owned(),
+            title: title.to_owned(),
+            start,
+            end: None
+        });
+    }
+
+    pub fn end_reign(&mut self, end: Date, reason: &str) {
+        let text = match self.rulers.last_mut() {
+            Some(reign) if reign.end.is_none() => {
+                reign.end = Some(end);
+                format!("{} {} {}", reign.title, reign.name, reason)
+            },
+            _ => return
+        };
+        self.add_entry(end, &text);
+    }
+
+    pub fn get_entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn get_rulers(&self) -> &[Reign] {
+        &self.rulers
+    }
+
+    pub fn mark(&self) -> Mark {
+        Mark {
+            entries: self.entries.len(),
+            rulers: self.rulers.len(),
+            end: self.rulers.last().and_then(|reign| reign.end)
+        }
+    }
+
+    /// Removes everything written since the mark was taken.
+    pub fn truncate(&mut self, mark: Mark) {
+        self.entries.truncate(mark.entries);
+        self.rulers.truncate(mark.rulers);
+        if let Some(reign) = self.rulers.last_mut() {
+            reign.end = mark.end;
+        }
+    }
+}
+
+impl Entry {
+    pub fn get_date(&self) -> Date {
+        self.date
+    }
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Reign {
+    pub fn get_ruler_id(&self) -> u32 {
+        self.ruler_id
+    }
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn get_title(&self) -> &str {
+        &self.title
+    }
+    pub fn get_start(&self) -> Date {
+        self.start
+    }
+    pub fn get_end(&self) -> Option<Date> {
+        self.end
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.date, self.text)
+    }
+}
+
+impl fmt::Display for Reign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.end {
+            Some(end) => write!(f, "{} {}, {} - {}", self.title, self.name, self.start, end),
+            None => write!(f, "{} {}, since {}", self.title, self.name, self.start)
+        }
+    }
+}
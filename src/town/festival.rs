@@ -0,0 +1,31 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::utility::{ Date, Calendar };
+
+/// Named yearly celebration, month and day are counted from 1.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Festival {
+    pub name: String,
+    pub month: u32,
+    pub day: u32
+}
+
+impl Festival {
+
+    /// Date of the festival in the given year of the calendar. Days missing in a shorter month,
+    /// like a leap day, fall back to the last day of the month.
+    pub fn get_date(&self, calendar: &'static dyn Calendar, year: i32) -> Date {
+        let mut date = Date::with_calendar(calendar, year, self.month - 1, 0);
+        date.set_day((self.day - 1).min(date.get_days_in_month() - 1));
+        date
+    }
+
+    /// The last celebration on or before the given date.
+    pub fn get_last_date(&self, today: Date) -> Date {
+        let calendar = today.get_calendar();
+        match self.get_date(calendar, today.get_year()) {
+            date if date <= today => date,
+            _ => self.get_date(calendar, today.get_year() - 1)
+        }
+    }
+}
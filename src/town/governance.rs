@@ -0,0 +1,185 @@
+use std::fmt;
+use rand::Rng;
+
+use crate::utility::{ Date, SymbolTable };
+use crate::person::{ Person, Population, SocialClass };
+use super::Chronicle;
+
+const MIN_RULER_AGE: u32 = 16;
+const MIN_COUNCIL_AGE: u32 = 25;
+// relatives further away can't inherit, cousins are 4
+const MAX_HEIR_DEGREE: u32 = 6;
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum SuccessionRule {
+    // nearest living relative, descendants and sons first, then the eldest
+    Hereditary,
+    Election { term_years: u32 }
+}
+
+#[derive(Clone)]
+pub struct Governance {
+    rule: SuccessionRule,
+    leader: Option<u32>,
+    term_end: Option<Date>,
+    council: Vec<u32>,
+    council_size: usize
+}
+
+impl Governance {
+
+    pub fn new(rule: SuccessionRule, council_size: usize) -> Self {
+        Self {
+            rule,
+            leader: None,
+            term_end: None,
+            council: Vec::new(),
+            council_size
+        }
+    }
+
+    pub fn random<R: Rng + ?Sized>(population: &Population, rng: &mut R) -> Self {
+        let has_nobility = population.iter()
+            .any(|p| p.get_social_class() == SocialClass::Nobility);
+        let rule = if has_nobility && rng.gen_bool(0.7) {
+            SuccessionRule::Hereditary
+        } else {
+            SuccessionRule::Election { term_years: rng.gen_range(3, 8) }
+        };
+        Self::new(rule, rng.gen_range(3, 8))
+    }
+
+    pub fn get_rule(&self) -> SuccessionRule {
+        self.rule
+    }
+
+    pub fn get_leader(&self) -> Option<u32> {
+        self.leader
+    }
+
+    pub fn get_council(&self) -> &[u32] {
+        &self.council
+    }
+
+    pub fn get_title(&self) -> &'static str {
+        match self.rule {
+            SuccessionRule::Hereditary => "Lord",
+            SuccessionRule::Election { .. } => "Mayor"
+        }
+    }
+
+    /// Replaces a dead leader or one whose term ended, and fills the council.
+    /// The names of new rulers in the chronicle are looked up in the given table.
+    pub fn update<R: Rng + ?Sized>(&mut self, today: Date, population: &Population, symbols: &SymbolTable, chronicle: &mut Chronicle, rng: &mut R) {
+        let deaths = population.get_recent_deaths();
+        let mut predecessor = None;
+        if let Some(leader) = self.leader {
+            if deaths.contains(&leader) {
+                chronicle.end_reign(today, "died");
+                self.leader = None;
+                predecessor = Some(leader);
+            } else if self.term_end.is_some_and(|end| today >= end) {
+                chronicle.end_reign(today, "ends the term");
+                self.leader = None;
+            }
+        }
+        self.council.retain(|id| !deaths.contains(id));
+
+        if self.leader.is_none() {
+            let successor = match (self.rule, predecessor) {
+                (SuccessionRule::Hereditary, Some(predecessor)) => match find_heir(predecessor, today, population) {
+                    Some(heir) => Some(heir),
+                    None => {
+                        // the town elects its rulers from now on
+                        self.rule = SuccessionRule::Election { term_years: rng.gen_range(3, 8) };
+                        chronicle.add_entry(today, &format!("The ruling line has died out, the town is governed by {} from now on", self.rule));
+                        self.hold_election(today, population, rng)
+                    }
+                },
+                _ => self.hold_election(today, population, rng)
+            };
+            if let Some(person) = successor {
+                self.install(person, symbols, today, chronicle);
+            }
+        }
+        self.fill_council(today, population);
+    }
+
+    fn install(&mut self, person: &Person, symbols: &SymbolTable, today: Date, chronicle: &mut Chronicle) {
+        self.leader = Some(person.get_id());
+        self.council.retain(|&id| id != person.get_id());
+        self.term_end = match self.rule {
+            SuccessionRule::Hereditary => None,
+            SuccessionRule::Election { term_years } => {
+                let mut end = today;
+                end.advance_years(term_years);
+                Some(end)
+            }
+        };
+        chronicle.begin_reign(person.get_id(), &person.get_full_name(symbols), self.get_title(), today);
+    }
+
+    // candidates win with a chance proportional to their wealth
+    fn hold_election<'a, R: Rng + ?Sized>(&self, today: Date, population: &'a Population, rng: &mut R) -> Option<&'a Person> {
+        let candidates: Vec<&Person> = population.iter()
+            .filter(|p| is_eligible(p, today, MIN_COUNCIL_AGE))
+            .collect();
+        let total = candidates.iter().fold(0u64, |acc, p| acc.saturating_add(p.get_wealth().get_value().saturating_add(1)));
+        if total == 0 {
+            return None;
+        }
+        let mut target = rng.gen_range(0, total);
+        for candidate in candidates {
+            let votes = candidate.get_wealth().get_value().saturating_add(1);
+            if target < votes {
+                return Some(candidate);
+            }
+            target -= votes;
+        }
+        None
+    }
+
+    // vacant seats go to the wealthiest eligible adults
+    fn fill_council(&mut self, today: Date, population: &Population) {
+        if self.council.len() >= self.council_size {
+            return;
+        }
+        let mut candidates: Vec<&Person> = population.iter()
+            .filter(|p| is_eligible(p, today, MIN_COUNCIL_AGE) &&
+                Some(p.get_id()) != self.leader &&
+                !self.council.contains(&p.get_id()))
+            .collect();
+        candidates.sort_by_key(|p| std::cmp::Reverse(p.get_wealth().get_value()));
+        for candidate in candidates.into_iter().take(self.council_size - self.council.len()) {
+            trace!("Person {} joins the council", candidate.get_id());
+            self.council.push(candidate.get_id());
+        }
+    }
+}
+
+fn find_heir(predecessor: u32, today: Date, population: &Population) -> Option<&Person> {
+    let lineage = population.get_lineage();
+    let candidates = lineage.get_relatives(predecessor, MAX_HEIR_DEGREE).into_iter()
+        .filter_map(|(id, degree)| population.get_by_id(id).map(|p| (p, degree)))
+        .filter(|&(p, _)| is_eligible(p, today, MIN_RULER_AGE));
+    candidates.min_by_key(|&(p, degree)| (
+        degree,
+        !lineage.is_ancestor(predecessor, p.get_id(), degree),
+        !p.get_attr().is_male(),
+        p.get_birthday()
+    ))
+    .map(|(p, _)| p)
+}
+
+fn is_eligible(person: &Person, today: Date, min_age: u32) -> bool {
+    person.get_age(&today) >= min_age && person.get_social_class() != SocialClass::Clergy
+}
+
+impl fmt::Display for SuccessionRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SuccessionRule::Hereditary => write!(f, "hereditary"),
+            SuccessionRule::Election { term_years } => write!(f, "election every {} years", term_years)
+        }
+    }
+}
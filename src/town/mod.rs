@@ -1,4 +1,20 @@
 
+// part of the public path, as in town::town::Town
+#[allow(clippy::module_inception)]
 pub mod town;
+pub mod chronicle;
+pub mod governance;
+pub mod temple;
+pub mod observer;
+pub mod snapshot;
+pub mod festival;
+pub mod place_names;
 
-pub use self::town::Town;
\ No newline at end of file
+pub use self::town::Town;
+pub use self::chronicle::Chronicle;
+pub use self::governance::{ Governance, SuccessionRule };
+pub use self::temple::Temple;
+pub use self::observer::Observer;
+pub use self::snapshot::Snapshot;
+pub use self::festival::Festival;
+pub use self::place_names::{ PlaceNamer, PlaceNames, PlaceKind };
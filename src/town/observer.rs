@@ -0,0 +1,60 @@
+use std::sync::{ Arc, Mutex, PoisonError };
+
+use crate::utility::Date;
+use crate::person::{ Person, Event, EventListener };
+use super::Town;
+
+/// Callbacks for host applications, all of them do nothing by default. Events are reported
+/// with the name of their town while the town is updated, right after the change. If the year
+/// fails afterwards, it is undone and `on_year_undone` follows instead of `on_year_end`.
+pub trait Observer {
+    fn on_birth(&mut self, _town: &str, _child: &Person) {}
+    fn on_death(&mut self, _town: &str, _person: &Person, _date: Date) {}
+    fn on_marriage(&mut self, _town: &str, _groom: &Person, _bride: &Person) {}
+    fn on_pregnancy(&mut self, _town: &str, _mother: &Person, _father_id: u32, _due: Date) {}
+    fn on_custom_event(&mut self, _town: &str, _name: &str, _person: Option<&Person>) {}
+    fn on_year_end(&mut self, _town: &Town) {}
+    fn on_year_undone(&mut self, _town: &Town) {}
+}
+
+// an observer registered on the population of a town, shared with the town and its clones
+pub(crate) struct TownObserver {
+    town: String,
+    observer: Arc<Mutex<Box<dyn Observer + Send>>>
+}
+
+impl TownObserver {
+    pub(crate) fn new(town: &str, observer: Arc<Mutex<Box<dyn Observer + Send>>>) -> TownObserver {
+        TownObserver {
+            town: town.to_owned(),
+            observer
+        }
+    }
+}
+
+impl EventListener for TownObserver {
+    fn on_event(&self, event: &Event) {
+        // an observer which panicked before is still notified
+        let mut observer = self.observer.lock().unwrap_or_else(PoisonError::into_inner);
+        report(&self.town, event, observer.as_mut());
+    }
+}
+
+/// Calls the callback of the event.
+pub fn report(town: &str, event: &Event, observer: &mut dyn Observer) {
+    match event {
+        Event::Birth { child } => observer.on_birth(town, child),
+        Event::Death { person, date } => observer.on_death(town, person, *date),
+        Event::Marriage { groom, bride } => observer.on_marriage(town, groom, bride),
+        Event::Pregnancy { mother, father_id, due } => observer.on_pregnancy(town, mother, *father_id, *due),
+        Event::Custom { name, person } => observer.on_custom_event(town, name, person.as_deref())
+    }
+}
+
+/// Reports the events of the last update of the town again, followed by the end of the year.
+pub fn notify(town: &Town, observer: &mut dyn Observer) {
+    for event in town.get_population().get_recent_events() {
+        report(town.get_name(), event, observer);
+    }
+    observer.on_year_end(town);
+}
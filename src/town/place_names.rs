@@ -0,0 +1,188 @@
+use std::fmt;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::person::{ PersonGenerator, Culture };
+use crate::map::{ Site, Street, Quarter };
+
+// attempts to find a name not yet used in the town
+const MAX_ATTEMPTS: u32 = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlaceKind {
+    Street,
+    Square,
+    Inn,
+    Church,
+    District
+}
+
+/// Names places of a town after its culture, its leading families and its surroundings.
+/// Names only depend on the given random generator, so they follow the seed of the town.
+pub struct PlaceNamer<'a> {
+    person_generator: &'a PersonGenerator,
+    culture: Culture,
+    site: Site,
+    // surnames of notable families, the founders first
+    families: &'a [String]
+}
+
+/// Names given to the places of a town so far.
+#[derive(Clone, Default)]
+pub struct PlaceNames {
+    square: Option<String>,
+    streets: Vec<(Street, String)>,
+    districts: Vec<(Quarter, String)>,
+    inns: Vec<String>
+}
+
+impl PlaceKind {
+    /// Key of the templates in the name packs.
+    pub fn get_key(&self) -> &'static str {
+        match *self {
+            PlaceKind::Street => "street",
+            PlaceKind::Square => "square",
+            PlaceKind::Inn => "inn",
+            PlaceKind::Church => "church",
+            PlaceKind::District => "district"
+        }
+    }
+}
+
+impl<'a> PlaceNamer<'a> {
+    pub fn new(person_generator: &'a PersonGenerator, culture: Culture, site: Site, families: &'a [String]) -> Self {
+        Self {
+            person_generator,
+            culture,
+            site,
+            families
+        }
+    }
+
+    /// A name from the templates of the kind which is not among the taken names, if one is found.
+    pub fn generate<R: Rng + ?Sized>(&self, kind: PlaceKind, taken: &[&str], rng: &mut R) -> String {
+        let mut name = self.generate_any(kind, rng);
+        for _ in 1..MAX_ATTEMPTS {
+            if !taken.contains(&name.as_str()) {
+                break;
+            }
+            name = self.generate_any(kind, rng);
+        }
+        name
+    }
+
+    fn generate_any<R: Rng + ?Sized>(&self, kind: PlaceKind, rng: &mut R) -> String {
+        let namesake = self.get_namesake(kind, rng);
+        match self.choose_word(kind.get_key(), rng) {
+            Some(template) => template.replace("{}", &namesake),
+            None => namesake
+        }
+    }
+
+    // streets are mostly named after families, squares and districts after the surroundings
+    fn get_namesake<R: Rng + ?Sized>(&self, kind: PlaceKind, rng: &mut R) -> String {
+        let family = self.families.choose(rng).cloned();
+        let feature = self.choose_feature(rng);
+        let namesake = match kind {
+            PlaceKind::Street => match rng.gen_range(0, 4) {
+                0 | 1 => family.clone(),
+                2 => feature.clone(),
+                _ => self.choose_word("trades", rng)
+            },
+            PlaceKind::Square | PlaceKind::District => match rng.gen_bool(0.5) {
+                true => feature.clone(),
+                false => family.clone()
+            },
+            PlaceKind::Inn => self.choose_word("emblems", rng),
+            // churches are dedicated to a saint, the templates are written for male ones
+            PlaceKind::Church => {
+                let saint = self.person_generator.random_male_first_name(self.culture, rng);
+                Some(self.person_generator.get_name(saint).to_owned())
+            }
+        };
+        namesake.or(feature)
+            .or(family)
+            .unwrap_or_else(|| String::from("Old"))
+    }
+
+    // the land around the town, its river or the sea
+    fn choose_feature<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<String> {
+        let mut kinds = vec![self.site.terrain.to_string()];
+        if self.site.river {
+            kinds.push(String::from("river"));
+        }
+        if self.site.coast {
+            kinds.push(String::from("sea"));
+        }
+        kinds.choose(rng)
+            .and_then(|kind| self.choose_word(kind, rng))
+    }
+
+    fn choose_word<R: Rng + ?Sized>(&self, kind: &str, rng: &mut R) -> Option<String> {
+        self.person_generator.get_place_words(self.culture, kind)
+            .choose(rng)
+            .cloned()
+    }
+}
+
+impl PlaceNames {
+
+    pub fn get_square(&self) -> Option<&str> {
+        self.square.as_deref()
+    }
+
+    pub fn get_street(&self, street: Street) -> Option<&str> {
+        self.streets.iter()
+            .find(|(s, _)| *s == street)
+            .map(|(_, name)| name.as_str())
+    }
+
+    pub fn get_streets(&self) -> &[(Street, String)] {
+        &self.streets
+    }
+
+    pub fn get_district(&self, quarter: Quarter) -> Option<&str> {
+        self.districts.iter()
+            .find(|(q, _)| *q == quarter)
+            .map(|(_, name)| name.as_str())
+    }
+
+    pub fn get_districts(&self) -> &[(Quarter, String)] {
+        &self.districts
+    }
+
+    pub fn get_inns(&self) -> &[String] {
+        &self.inns
+    }
+
+    /// All names given so far.
+    pub fn get_names(&self) -> Vec<&str> {
+        self.square.iter().map(|name| name.as_str())
+            .chain(self.streets.iter().map(|(_, name)| name.as_str()))
+            .chain(self.districts.iter().map(|(_, name)| name.as_str()))
+            .chain(self.inns.iter().map(|name| name.as_str()))
+            .collect()
+    }
+
+    pub fn set_square(&mut self, name: String) {
+        self.square = Some(name);
+    }
+
+    pub fn add_street(&mut self, street: Street, name: String) {
+        self.streets.push((street, name));
+    }
+
+    pub fn add_district(&mut self, quarter: Quarter, name: String) {
+        self.districts.push((quarter, name));
+    }
+
+    pub fn add_inn(&mut self, name: String) {
+        self.inns.push(name);
+    }
+}
+
+impl fmt::Display for PlaceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_key())
+    }
+}
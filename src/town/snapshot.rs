@@ -0,0 +1,58 @@
+use std::sync::{ Arc, OnceLock };
+use std::collections::HashMap;
+
+use crate::utility::Date;
+use crate::person::Person;
+
+/// State of a town at the end of a year. The list of persons is shared with the town
+/// until the town changes it, and every person until it changes.
+#[derive(Clone)]
+pub struct Snapshot {
+    date: Date,
+    population: Arc<Vec<Arc<Person>>>,
+    // position of every person by id, built on the first lookup
+    positions: OnceLock<HashMap<u32, usize>>,
+    leader: Option<u32>,
+    temple_count: u32
+}
+
+impl Snapshot {
+
+    pub fn new(date: Date, population: Arc<Vec<Arc<Person>>>, leader: Option<u32>, temple_count: u32) -> Self {
+        Self {
+            date,
+            population,
+            positions: OnceLock::new(),
+            leader,
+            temple_count
+        }
+    }
+
+    pub fn get_date(&self) -> Date {
+        self.date
+    }
+
+    pub fn get_population(&self) -> &[Arc<Person>] {
+        &self.population
+    }
+
+    pub fn get_by_id(&self, id: u32) -> Option<&Person> {
+        let positions = self.positions.get_or_init(|| self.population.iter()
+            .enumerate()
+            .map(|(index, person)| (person.get_id(), index))
+            .collect());
+        positions.get(&id).map(|&index| self.population[index].as_ref())
+    }
+
+    pub fn size(&self) -> u32 {
+        self.population.len() as u32
+    }
+
+    pub fn get_leader(&self) -> Option<u32> {
+        self.leader
+    }
+
+    pub fn get_temple_count(&self) -> u32 {
+        self.temple_count
+    }
+}
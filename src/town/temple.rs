@@ -0,0 +1,42 @@
+use crate::utility::Date;
+use crate::person::Religion;
+
+const MIN_ADHERENTS: u32 = 15;
+const ADHERENTS_PER_TEMPLE: u32 = 80;
+
+#[derive(Clone)]
+pub struct Temple {
+    name: String,
+    religion: Religion,
+    founded: Date
+}
+
+impl Temple {
+    pub fn new(name: String, religion: Religion, founded: Date) -> Self {
+        Self {
+            name,
+            religion,
+            founded
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_religion(&self) -> Religion {
+        self.religion
+    }
+    pub fn get_founded(&self) -> Date {
+        self.founded
+    }
+}
+
+/// Number of temples a community of the given size maintains.
+pub fn get_wanted_temples(adherents: u32) -> u32 {
+    if adherents < MIN_ADHERENTS {
+        0
+    } else {
+        1 + adherents / ADHERENTS_PER_TEMPLE
+    }
+}
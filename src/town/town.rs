@@ -1,73 +1,417 @@
 use std::fmt;
+use std::collections::VecDeque;
+use std::sync::{ Arc, Mutex, PoisonError };
 use rand::rngs::SmallRng;
 use rand::{ Rng, SeedableRng };
 
-use crate::utility::date::{ Date, DAYS_PER_YEAR };
-use crate::person::{ Population, PersonGenerator };
+use crate::utility::date::Date;
+use crate::person::{ Population, PersonGenerator, Culture, Religion };
+use crate::person::population::Checkpoint;
+use crate::config::SimulationConfig;
+use crate::utility::simulation_error::SimulationError;
+use crate::utility::application_error::ApplicationError;
+use crate::simulation::{ Pipeline, PhaseContext };
+use crate::map::{ TownLayout, Site };
+use super::{ Chronicle, Governance, Temple, Festival, Observer, Snapshot, PlaceNamer, PlaceNames, PlaceKind };
+use super::observer::{ self, TownObserver };
+use super::chronicle::Mark;
+use super::temple::get_wanted_temples;
+
+// families the places of the town are named after besides the founders
+const NOTABLE_FAMILIES: usize = 3;
+const FOUNDING_FAMILIES: usize = 5;
+const PERSONS_PER_INN: u32 = 60;
 
 #[derive(Clone)]
 pub struct Town {
     rng: SmallRng,
     name: String,
+    site: Site,
+    // culture of the first settlers
+    culture: Culture,
+    // surnames of the richest founding families
+    founders: Vec<String>,
     date: Date,
     population: Population,
+    governance: Governance,
+    chronicle: Chronicle,
+    temples: Vec<Temple>,
+    festivals: Vec<Festival>,
+    layout: TownLayout,
+    place_names: PlaceNames,
+    pipeline: Pipeline,
+    person_generator: PersonGenerator,
+    // snapshots of the previous years, oldest first
+    history: VecDeque<Snapshot>,
+    history_length: usize,
+    // shared with clones of the town
+    observers: Vec<Arc<Mutex<Box<dyn Observer + Send>>>>
+}
+
+// what a town needs to undo a year or to finish it
+pub(crate) struct YearCheckpoint {
+    // the town at the end of the previous year
+    snapshot: Snapshot,
+    population: Checkpoint,
+    chronicle: Mark,
+    person_generator: PersonGenerator,
+    rng: SmallRng
 }
 
 impl Town {
-    pub fn found<R: Rng + ?Sized>(name: &str, date: Date, person_generator: &mut PersonGenerator, rng: &mut R) -> Town {
-        let mut local_rng = SmallRng::from_rng(rng).unwrap();
-
-        let initial_pop_size = local_rng.gen_range(10, 40);
-        let capacity = local_rng.gen_range(initial_pop_size * 2, initial_pop_size * 5);
-        let population = Population::new(initial_pop_size, capacity, date, person_generator, &mut local_rng);
-        
-        Town {
+    pub fn found<R: Rng + ?Sized>(name: &str, date: Date, site: Site, settlers: &[(Culture, Religion)], config: &SimulationConfig, mut person_generator: PersonGenerator, rng: &mut R) -> Result<Town, SimulationError> {
+        let mut local_rng = SmallRng::from_rng(rng)?;
+
+        let initial_pop_size = local_rng.gen_range(config.town.initial_population_min, config.town.initial_population_max);
+        let factor_range = (config.town.capacity_factor_max - config.town.capacity_factor_min) as f32;
+        let capacity_factor = config.town.capacity_factor_min as f32 + factor_range * site.fertility;
+        let capacity = (initial_pop_size as f32 * capacity_factor).round() as u32;
+        let population = Population::new(initial_pop_size, capacity, date, settlers, config, &mut person_generator, &mut local_rng)?;
+        let layout = TownLayout::generate(capacity, site.river, &mut local_rng);
+        let mut governance = Governance::random(&population, &mut local_rng);
+        let mut chronicle = Chronicle::default();
+        chronicle.add_entry(date, &format!("{} is founded {}, governed by {}", name, site, governance.get_rule()));
+        governance.update(date, &population, person_generator.get_symbols(), &mut chronicle, &mut local_rng);
+        let founders = population.get_family_wealth().into_iter()
+            .take(FOUNDING_FAMILIES)
+            .map(|(family, _)| person_generator.get_name(family).to_owned())
+            .collect();
+
+        let mut town = Town {
             rng: local_rng,
             name: name.to_owned(),
-            date: date,
-            population: population,
-        }
+            site,
+            culture: settlers.first().map(|&(culture, _)| culture).unwrap_or_default(),
+            founders,
+            date,
+            population,
+            governance,
+            chronicle,
+            temples: Vec::new(),
+            festivals: config.festivals.clone(),
+            layout,
+            place_names: PlaceNames::default(),
+            pipeline: Pipeline::default(),
+            person_generator,
+            history: VecDeque::new(),
+            history_length: config.town.history_length,
+            observers: Vec::new()
+        };
+        town.update_temples();
+        town.update_layout();
+        town.update_place_names();
+        Ok(town)
     }
 
-    pub fn progress_year(&self, person_generator: &mut PersonGenerator) -> Town {
+    /// Progresses the town by one year. On error the town keeps its previous state.
+    pub fn progress_year(&mut self) -> Result<(), ApplicationError> {
+        let checkpoint = self.begin_year()?;
+        self.finish_year(checkpoint);
+        self.notify_observers();
+        Ok(())
+    }
+
+    // runs the pipeline on the population, the only part of a year which can fail.
+    // On error the town is restored, otherwise the returned checkpoint can undo the year.
+    pub(crate) fn begin_year(&mut self) -> Result<YearCheckpoint, ApplicationError> {
         info!("########################");
         info!("Progressing '{}'", self.name);
-        let mut next_town = self.clone();
-        next_town.forward_date_one_year();
-        next_town.update_population(person_generator);
+        let checkpoint = YearCheckpoint {
+            snapshot: self.snapshot(),
+            population: self.population.checkpoint(),
+            chronicle: self.chronicle.mark(),
+            person_generator: self.person_generator.clone(),
+            rng: self.rng.clone()
+        };
+        self.forward_date_one_year();
+        match self.update_population() {
+            Ok(()) => Ok(checkpoint),
+            Err(err) => {
+                self.undo_year(checkpoint);
+                Err(err)
+            }
+        }
+    }
+
+    // returns to the state before `begin_year`
+    pub(crate) fn undo_year(&mut self, checkpoint: YearCheckpoint) {
+        self.date = checkpoint.snapshot.get_date();
+        self.population.restore(checkpoint.population);
+        self.chronicle.truncate(checkpoint.chronicle);
+        self.person_generator = checkpoint.person_generator;
+        self.rng = checkpoint.rng;
+        for observer in self.observers.iter() {
+            let mut observer = observer.lock().unwrap_or_else(PoisonError::into_inner);
+            observer.on_year_undone(self);
+        }
+    }
+
+    // the rest of the year, which only follows the population
+    pub(crate) fn finish_year(&mut self, checkpoint: YearCheckpoint) {
+        self.record_history(checkpoint.snapshot);
+        self.update_governance();
+        self.update_temples();
+        self.update_layout();
+        self.update_place_names();
+        self.update_festivals();
         info!("Date: {}, population: {}",
-            next_town.get_date(),
-            next_town.get_population());
+            self.get_date(),
+            self.get_population());
+        info!("Classes: {}", self.get_population().get_class_counts().iter()
+            .map(|(class, count)| format!("{} = {}", class, count))
+            .collect::<Vec<String>>()
+            .join(", "));
+        self.print_faith();
         info!("########################");
-        next_town
     }
 
-    fn update_population(&mut self, person_generator: &mut PersonGenerator) {
-        self.population.update(self.date, person_generator, &mut self.rng);
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(self.date, self.population.share_population(), self.governance.get_leader(), self.temples.len() as u32)
+    }
+
+    /// Snapshots of up to `history_length` previous years, oldest first.
+    pub fn get_history(&self) -> impl Iterator<Item = &Snapshot> {
+        self.history.iter()
+    }
+
+    pub fn set_history_length(&mut self, length: usize) {
+        self.history_length = length;
+        while self.history.len() > length {
+            self.history.pop_front();
+        }
+    }
+
+    fn record_history(&mut self, snapshot: Snapshot) {
+        if self.history_length == 0 {
+            return;
+        }
+        if self.history.len() == self.history_length {
+            self.history.pop_front();
+        }
+        self.history.push_back(snapshot);
+    }
+
+    fn update_population(&mut self) -> Result<(), ApplicationError> {
+        let mut context = PhaseContext {
+            town_name: &self.name,
+            date: self.date,
+            population: &mut self.population,
+            chronicle: &mut self.chronicle,
+            person_generator: &mut self.person_generator,
+            rng: &mut self.rng
+        };
+        self.pipeline.run(&mut context)
+    }
+
+    fn update_governance(&mut self) {
+        self.governance.update(self.date, &self.population, self.person_generator.get_symbols(), &mut self.chronicle, &mut self.rng);
+    }
+
+    // festivals of the past year
+    fn update_festivals(&mut self) {
+        if self.population.size() == 0 {
+            return;
+        }
+        let mut celebrations: Vec<(Date, &Festival)> = self.festivals.iter()
+            .map(|festival| (festival.get_last_date(self.date), festival))
+            .collect();
+        celebrations.sort_by_key(|&(date, _)| date);
+        for (date, festival) in celebrations {
+            let text = format!("{} townsfolk celebrate the {}", self.population.size(), festival.name);
+            self.chronicle.add_entry(date, &text);
+        }
+    }
+
+    fn update_temples(&mut self) {
+        let mut families = None;
+        for (religion, adherents) in self.population.get_religion_counts() {
+            let existing = self.temples.iter().filter(|t| t.get_religion() == religion).count() as u32;
+            for _ in existing..get_wanted_temples(adherents) {
+                let families = families.get_or_insert_with(|| self.get_notable_families());
+                let name = self.generate_place_name(PlaceKind::Church, families);
+                let text = format!("{}, a temple of the {}, is built", name, self.person_generator.get_religion_name(religion));
+                self.chronicle.add_entry(self.date, &text);
+                self.temples.push(Temple::new(name, religion, self.date));
+            }
+        }
+    }
+
+    // dwellings for the households and the temples of this year
+    fn update_layout(&mut self) {
+        let households = self.population.get_households(self.date);
+        self.layout.update(&households, self.temples.len(), &mut self.rng);
+    }
+
+    // names the square, new streets and districts, and opens inns as the town grows
+    fn update_place_names(&mut self) {
+        let families = self.get_notable_families();
+        if self.place_names.get_square().is_none() {
+            let name = self.generate_place_name(PlaceKind::Square, &families);
+            self.place_names.set_square(name);
+        }
+        for street in self.layout.get_streets() {
+            if self.place_names.get_street(street).is_none() {
+                let name = self.generate_place_name(PlaceKind::Street, &families);
+                self.place_names.add_street(street, name);
+            }
+        }
+        for quarter in self.layout.get_settled_quarters() {
+            if self.place_names.get_district(quarter).is_none() {
+                let name = self.generate_place_name(PlaceKind::District, &families);
+                self.place_names.add_district(quarter, name);
+            }
+        }
+        if self.population.size() == 0 {
+            return;
+        }
+        let wanted_inns = self.population.size().div_ceil(PERSONS_PER_INN) as usize;
+        while self.place_names.get_inns().len() < wanted_inns {
+            let name = self.generate_place_name(PlaceKind::Inn, &families);
+            self.chronicle.add_entry(self.date, &format!("{} opens its doors", name));
+            self.place_names.add_inn(name);
+        }
+    }
+
+    // surnames places are named after, the founders first and then the richest families
+    fn get_notable_families(&self) -> Vec<String> {
+        let mut families = self.founders.clone();
+        for (family, _) in self.population.get_family_wealth().into_iter().take(NOTABLE_FAMILIES) {
+            let family = self.person_generator.get_name(family).to_owned();
+            if !families.contains(&family) {
+                families.push(family);
+            }
+        }
+        families
+    }
+
+    // a name not yet given to another place of the town, if one is found
+    fn generate_place_name(&mut self, kind: PlaceKind, families: &[String]) -> String {
+        let culture = self.population.get_majority_culture().unwrap_or(self.culture);
+        let namer = PlaceNamer::new(&self.person_generator, culture, self.site, families);
+        let mut taken = self.place_names.get_names();
+        taken.extend(self.temples.iter().map(Temple::get_name));
+        namer.generate(kind, &taken, &mut self.rng)
+    }
+
+    fn print_faith(&self) {
+        info!("Cultures: {}, assimilation = {:.2}",
+            self.population.get_culture_counts().iter()
+                .map(|&(culture, count)| format!("{} = {}", self.person_generator.get_culture_name(culture), count))
+                .collect::<Vec<String>>()
+                .join(", "),
+            self.population.get_assimilation_ratio());
+        info!("Religions: {}, temples = {}, clergy = {}",
+            self.population.get_religion_counts().iter()
+                .map(|&(religion, count)| format!("{} = {}", self.person_generator.get_religion_name(religion), count))
+                .collect::<Vec<String>>()
+                .join(", "),
+            self.temples.len(),
+            self.population.get_clergy_counts().iter().fold(0, |acc, &(_, count)| acc + count));
     }
 
     fn forward_date_one_year(&mut self) {
-        self.date += DAYS_PER_YEAR;
+        self.date.advance_years(1);
     }
 
     pub fn get_name(&self) -> &str {
         &self.name
     }
 
+    pub fn get_site(&self) -> &Site {
+        &self.site
+    }
+
     pub fn get_date(&self) -> &Date {
         &self.date
     }
 
+    pub fn get_governance(&self) -> &Governance {
+        &self.governance
+    }
+
+    pub fn get_chronicle(&self) -> &Chronicle {
+        &self.chronicle
+    }
+
+    /// Reports the events of the last year to the observer again.
+    pub fn notify(&self, observer: &mut dyn Observer) {
+        observer::notify(self, observer);
+    }
+
+    /// Registers an observer which is notified of every following event and year of this town.
+    pub fn add_observer(&mut self, observer: Box<dyn Observer + Send>) {
+        self.add_shared_observer(Arc::new(Mutex::new(observer)));
+    }
+
+    // the observer may also be registered on other towns
+    pub(crate) fn add_shared_observer(&mut self, observer: Arc<Mutex<Box<dyn Observer + Send>>>) {
+        self.population.add_listener(Arc::new(TownObserver::new(&self.name, observer.clone())));
+        self.observers.push(observer);
+    }
+
+    // the events were reported during the year
+    pub(crate) fn notify_observers(&self) {
+        for observer in self.observers.iter() {
+            // an observer which panicked before is still notified
+            let mut observer = observer.lock().unwrap_or_else(PoisonError::into_inner);
+            observer.on_year_end(self);
+        }
+    }
+
+    pub fn get_person_generator(&self) -> &PersonGenerator {
+        &self.person_generator
+    }
+
+    pub fn get_pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+
+    /// Phases run on every yearly update, in order.
+    pub fn get_pipeline_mut(&mut self) -> &mut Pipeline {
+        &mut self.pipeline
+    }
+
+    pub fn set_pipeline(&mut self, pipeline: Pipeline) {
+        self.pipeline = pipeline;
+    }
+
+    pub fn get_temples(&self) -> &[Temple] {
+        &self.temples
+    }
+
+    pub fn get_festivals(&self) -> &[Festival] {
+        &self.festivals
+    }
+
+    pub fn get_layout(&self) -> &TownLayout {
+        &self.layout
+    }
+
+    pub fn get_place_names(&self) -> &PlaceNames {
+        &self.place_names
+    }
+
+    /// Surnames of the richest families at the founding of the town.
+    pub fn get_founders(&self) -> &[String] {
+        &self.founders
+    }
+
+    pub fn print_rulers(&self) {
+        info!("Rulers of {}:", self.name);
+        self.chronicle.get_rulers().iter()
+            .for_each(|reign| info!("{}", reign));
+    }
+
     pub fn print_full(&self) {
         info!("################");
         info!("{}", self);
 
-        self.population.get_population().iter()
-            .for_each(|p| info!("{} ({})", p.get_full_name(), p.get_age(&self.date)));
+        self.population.iter()
+            .for_each(|p| info!("{} ({})", p.get_full_name(self.person_generator.get_symbols()), p.get_age(&self.date)));
         info!("################");
     }
 
-    fn get_population(&self) -> &Population {
+    pub fn get_population(&self) -> &Population {
         &self.population
     }
 }
@@ -2,10 +2,18 @@ use std::fmt;
 use std::error::Error;
 
 use super::file_error::FileError;
+use super::config_error::ConfigError;
+use super::simulation_error::SimulationError;
+use super::script_error::ScriptError;
+use super::date_error::DateError;
 
 #[derive(Debug)]
 pub enum ApplicationError {
-    File(FileError)
+    File(FileError),
+    Config(ConfigError),
+    Simulation(SimulationError),
+    Script(ScriptError),
+    Date(DateError)
 }
 
 impl From<FileError> for ApplicationError {
@@ -14,17 +22,49 @@ impl From<FileError> for ApplicationError {
     }
 }
 
+impl From<ConfigError> for ApplicationError {
+    fn from(err: ConfigError) -> Self {
+        ApplicationError::Config(err)
+    }
+}
+
+impl From<SimulationError> for ApplicationError {
+    fn from(err: SimulationError) -> Self {
+        ApplicationError::Simulation(err)
+    }
+}
+
+impl From<ScriptError> for ApplicationError {
+    fn from(err: ScriptError) -> Self {
+        ApplicationError::Script(err)
+    }
+}
+
+impl From<DateError> for ApplicationError {
+    fn from(err: DateError) -> Self {
+        ApplicationError::Date(err)
+    }
+}
+
 impl Error for ApplicationError {
 
     fn description(&self) -> &str {
         match *self {
-            ApplicationError::File(_) => "file"
+            ApplicationError::File(_) => "file",
+            ApplicationError::Config(_) => "config",
+            ApplicationError::Simulation(_) => "simulation",
+            ApplicationError::Script(_) => "script",
+            ApplicationError::Date(_) => "date"
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn cause(&self) -> Option<&dyn Error> {
         match *self {
-            ApplicationError::File(ref err) => Some(err)
+            ApplicationError::File(ref err) => Some(err),
+            ApplicationError::Config(ref err) => Some(err),
+            ApplicationError::Simulation(ref err) => Some(err),
+            ApplicationError::Script(ref err) => Some(err),
+            ApplicationError::Date(ref err) => Some(err)
         }
     }
 }
@@ -32,7 +72,11 @@ impl Error for ApplicationError {
 impl fmt::Display for ApplicationError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            ApplicationError::File(ref err) => write!(f, "{}/{}", self.description(), err)
+            ApplicationError::File(ref err) => write!(f, "file/{}", err),
+            ApplicationError::Config(ref err) => write!(f, "config/{}", err),
+            ApplicationError::Simulation(ref err) => write!(f, "simulation/{}", err),
+            ApplicationError::Script(ref err) => write!(f, "script/{}", err),
+            ApplicationError::Date(ref err) => write!(f, "date/{}", err)
         }
     }
 }
@@ -0,0 +1,339 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+use serde::{ Serialize, Deserialize };
+
+use super::Date;
+use super::date_error::DateError;
+use super::Season;
+use super::date::{ DAYS_PER_MONTH, MONTHS_PER_YEAR, DAYS_PER_YEAR };
+
+pub(crate) static SIMPLE_CALENDAR: SimpleCalendar = SimpleCalendar;
+static GREGORIAN_CALENDAR: GregorianCalendar = GregorianCalendar;
+// each distinct custom calendar is kept once for the lifetime of the program,
+// the lock is only taken when a calendar is looked up for a new world
+static CUSTOM_CALENDARS: Mutex<Vec<&'static CustomCalendar>> = Mutex::new(Vec::new());
+
+/// Divides days into months and years. Months and days are counted from 0,
+/// day 0 is the first day of year 0 and earlier days are negative.
+pub trait Calendar: Send + Sync {
+    fn get_name(&self) -> &str;
+    fn get_months_per_year(&self) -> u32;
+    fn get_days_in_month(&self, month: u32, year: i32) -> u32;
+    /// Days from the start of year 0 to the start of the given year, negative before year 0.
+    fn get_days_before_year(&self, year: i32) -> i64;
+
+    fn get_days_in_year(&self, year: i32) -> u32 {
+        (0..self.get_months_per_year())
+            .map(|month| self.get_days_in_month(month, year))
+            .sum()
+    }
+
+    fn get_month_name(&self, _month: u32) -> Option<&str> {
+        None
+    }
+
+    fn get_season(&self, month: u32) -> Season {
+        Season::of_month(month, self.get_months_per_year())
+    }
+
+    fn get_days_per_week(&self) -> u32 {
+        7
+    }
+
+    /// Day of the week counted from 0, weeks run on across years.
+    fn get_weekday(&self, date: &Date) -> u32 {
+        self.get_day_number(date).rem_euclid(self.get_days_per_week() as i64) as u32
+    }
+
+    fn get_weekday_name(&self, _weekday: u32) -> Option<&str> {
+        None
+    }
+
+    /// Year containing the given day number.
+    fn get_year_of_day(&self, day_number: i64) -> i32 {
+        // estimate by the length of year 0 and correct for leap days
+        let mut year = day_number.div_euclid(self.get_days_in_year(0).max(1) as i64) as i32;
+        while self.get_days_before_year(year) > day_number {
+            year -= 1;
+        }
+        while self.get_days_before_year(year + 1) <= day_number {
+            year += 1;
+        }
+        year
+    }
+
+    /// Days from the start of year 0 to the given date.
+    fn get_day_number(&self, date: &Date) -> i64 {
+        let days_before_month: i64 = (0..date.get_month())
+            .map(|month| self.get_days_in_month(month, date.get_year()) as i64)
+            .sum();
+        self.get_days_before_year(date.get_year()) + days_before_month + date.get_day() as i64
+    }
+
+    fn get_date_of_day(&self, day_number: i64) -> Date {
+        let year = self.get_year_of_day(day_number);
+        let mut day = (day_number - self.get_days_before_year(year)) as u32;
+        let mut month = 0;
+        while day >= self.get_days_in_month(month, year) {
+            day -= self.get_days_in_month(month, year);
+            month += 1;
+        }
+        Date::from_parts(year, month, day)
+    }
+
+    /// Years before year 1 are negative, the year before 1 is 0.
+    fn format_date(&self, date: &Date, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}-{}", date.get_year(), date.get_month() + 1, date.get_day() + 1)
+    }
+
+    /// Reads a date in the format written by `format_date`.
+    fn parse_date(&self, text: &str) -> Result<Date, DateError> {
+        parse_numeric_date(self, text)
+    }
+}
+
+/// Calendar selected by the simulation config.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CalendarKind {
+    #[default]
+    Simple,
+    Gregorian,
+    Custom(CustomCalendar)
+}
+
+// 12 months with 30 days each
+#[derive(Clone, Copy, Default)]
+pub struct SimpleCalendar;
+
+// months of varying length and a leap day in february
+#[derive(Clone, Copy, Default)]
+pub struct GregorianCalendar;
+
+/// Named months of fixed length and optional named weekdays.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomCalendar {
+    pub name: String,
+    #[serde(default)]
+    pub weekdays: Vec<String>,
+    pub months: Vec<Month>
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Month {
+    pub name: String,
+    pub days: u32,
+    // derived from the position in the year if missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub season: Option<Season>
+}
+
+const GREGORIAN_MONTH_DAYS: [u32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+const GREGORIAN_MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December"
+];
+const GREGORIAN_WEEKDAY_NAMES: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+// the first day of year 0 is a saturday
+const GREGORIAN_FIRST_WEEKDAY: i64 = 5;
+
+impl CalendarKind {
+    pub fn create(&self) -> Box<dyn Calendar> {
+        match *self {
+            CalendarKind::Simple => Box::new(SimpleCalendar),
+            CalendarKind::Gregorian => Box::new(GregorianCalendar),
+            CalendarKind::Custom(ref calendar) => Box::new(calendar.clone())
+        }
+    }
+
+    /// Shared instance of the calendar, which dates refer to.
+    pub fn get_calendar(&self) -> &'static dyn Calendar {
+        match *self {
+            CalendarKind::Simple => &SIMPLE_CALENDAR,
+            CalendarKind::Gregorian => &GREGORIAN_CALENDAR,
+            CalendarKind::Custom(ref calendar) => {
+                let mut calendars = CUSTOM_CALENDARS.lock().unwrap();
+                match calendars.iter().find(|known| **known == calendar) {
+                    Some(known) => *known,
+                    None => {
+                        let known: &'static CustomCalendar = Box::leak(Box::new(calendar.clone()));
+                        calendars.push(known);
+                        known
+                    }
+                }
+            }
+        }
+    }
+}
+
+// year-month-day, counted from 1
+fn parse_numeric_date<C: Calendar + ?Sized>(calendar: &C, text: &str) -> Result<Date, DateError> {
+    let trimmed = text.trim();
+    // a leading minus belongs to the year
+    let (sign, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, trimmed)
+    };
+    let parts: Vec<&str> = unsigned.split('-').collect();
+    if parts.len() != 3 {
+        return Err(DateError::Parse(text.to_owned()));
+    }
+    let year: i32 = parse_number(parts[0], text)?;
+    create_date(calendar, sign * year, parse_number(parts[1], text)?, parse_number(parts[2], text)?)
+}
+
+fn parse_number<T: FromStr>(number: &str, text: &str) -> Result<T, DateError> {
+    number.trim().parse().map_err(|_| DateError::Parse(text.to_owned()))
+}
+
+// month and day are counted from 1
+fn create_date<C: Calendar + ?Sized>(calendar: &C, year: i32, month: u32, day: u32) -> Result<Date, DateError> {
+    if month == 0 || month > calendar.get_months_per_year() {
+        return Err(DateError::Invalid(format!("month {} of year {}", month, year)));
+    }
+    if day == 0 || day > calendar.get_days_in_month(month - 1, year) {
+        return Err(DateError::Invalid(format!("day {} of month {} in year {}", day, month, year)));
+    }
+    Ok(Date::from_parts(year, month - 1, day - 1))
+}
+
+impl Calendar for SimpleCalendar {
+    fn get_name(&self) -> &str {
+        "simple"
+    }
+
+    fn get_months_per_year(&self) -> u32 {
+        MONTHS_PER_YEAR
+    }
+
+    fn get_days_in_month(&self, _month: u32, _year: i32) -> u32 {
+        DAYS_PER_MONTH
+    }
+
+    fn get_days_in_year(&self, _year: i32) -> u32 {
+        DAYS_PER_YEAR
+    }
+
+    fn get_days_before_year(&self, year: i32) -> i64 {
+        year as i64 * DAYS_PER_YEAR as i64
+    }
+}
+
+impl GregorianCalendar {
+    pub fn is_leap_year(year: i32) -> bool {
+        year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0)
+    }
+}
+
+impl Calendar for GregorianCalendar {
+    fn get_name(&self) -> &str {
+        "gregorian"
+    }
+
+    fn get_months_per_year(&self) -> u32 {
+        12
+    }
+
+    fn get_days_in_month(&self, month: u32, year: i32) -> u32 {
+        match month {
+            1 if GregorianCalendar::is_leap_year(year) => 29,
+            _ => GREGORIAN_MONTH_DAYS[month as usize]
+        }
+    }
+
+    fn get_days_in_year(&self, year: i32) -> u32 {
+        match GregorianCalendar::is_leap_year(year) {
+            true => 366,
+            false => 365
+        }
+    }
+
+    fn get_days_before_year(&self, year: i32) -> i64 {
+        // leap years in [0, year), or the negative count in [year, 0)
+        let year = year as i64;
+        let leap_years = (year + 3).div_euclid(4) - (year + 99).div_euclid(100) + (year + 399).div_euclid(400);
+        year * 365 + leap_years
+    }
+
+    fn get_month_name(&self, month: u32) -> Option<&str> {
+        GREGORIAN_MONTH_NAMES.get(month as usize).cloned()
+    }
+
+    fn get_weekday(&self, date: &Date) -> u32 {
+        (self.get_day_number(date) + GREGORIAN_FIRST_WEEKDAY).rem_euclid(7) as u32
+    }
+
+    fn get_weekday_name(&self, weekday: u32) -> Option<&str> {
+        GREGORIAN_WEEKDAY_NAMES.get(weekday as usize).cloned()
+    }
+}
+
+impl Calendar for CustomCalendar {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_months_per_year(&self) -> u32 {
+        self.months.len() as u32
+    }
+
+    fn get_days_in_month(&self, month: u32, _year: i32) -> u32 {
+        self.months[month as usize].days
+    }
+
+    fn get_days_before_year(&self, year: i32) -> i64 {
+        year as i64 * self.get_days_in_year(0) as i64
+    }
+
+    fn get_month_name(&self, month: u32) -> Option<&str> {
+        self.months.get(month as usize).map(|month| month.name.as_str())
+    }
+
+    fn get_season(&self, month: u32) -> Season {
+        match self.months.get(month as usize).and_then(|month| month.season) {
+            Some(season) => season,
+            None => Season::of_month(month, self.get_months_per_year())
+        }
+    }
+
+    // weeks have seven days if none are named
+    fn get_days_per_week(&self) -> u32 {
+        match self.weekdays.len() {
+            0 => 7,
+            count => count as u32
+        }
+    }
+
+    fn get_weekday_name(&self, weekday: u32) -> Option<&str> {
+        self.weekdays.get(weekday as usize).map(|name| name.as_str())
+    }
+
+    fn format_date(&self, date: &Date, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(weekday) = self.get_weekday_name(self.get_weekday(date)) {
+            write!(f, "{}, ", weekday)?;
+        }
+        match self.get_month_name(date.get_month()) {
+            Some(month) => write!(f, "{} {} {}", date.get_day() + 1, month, date.get_year()),
+            None => write!(f, "{}-{}-{}", date.get_year(), date.get_month() + 1, date.get_day() + 1)
+        }
+    }
+
+    fn parse_date(&self, text: &str) -> Result<Date, DateError> {
+        // the weekday follows from the date
+        let named = match text.find(", ") {
+            Some(index) => &text[index + 2..],
+            None => text
+        };
+        let words: Vec<&str> = named.split_whitespace().collect();
+        if words.len() < 3 {
+            return parse_numeric_date(self, text);
+        }
+        let month_name = words[1..words.len() - 1].join(" ");
+        let month = match self.months.iter().position(|month| month.name.eq_ignore_ascii_case(&month_name)) {
+            Some(index) => index as u32 + 1,
+            None => return Err(DateError::Invalid(format!("unknown month '{}'", month_name)))
+        };
+        create_date(self, parse_number(words[words.len() - 1], text)?, month, parse_number(words[0], text)?)
+    }
+}
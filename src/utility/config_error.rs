@@ -0,0 +1,55 @@
+use std::fmt;
+use std::error::Error;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Parse(String),
+    Serialize(String),
+    UnknownPreset(String),
+    Invalid(String),
+    File(String, Box<ConfigError>)
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(err: toml::de::Error) -> ConfigError {
+        ConfigError::Parse(err.to_string())
+    }
+}
+
+impl From<toml::ser::Error> for ConfigError {
+    fn from(err: toml::ser::Error) -> ConfigError {
+        ConfigError::Serialize(err.to_string())
+    }
+}
+
+impl Error for ConfigError {
+
+    fn description(&self) -> &str {
+        match *self {
+            ConfigError::Parse(_) => "parse",
+            ConfigError::Serialize(_) => "serialize",
+            ConfigError::UnknownPreset(_) => "unknown preset",
+            ConfigError::Invalid(_) => "invalid",
+            ConfigError::File(_, _) => "file"
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            ConfigError::File(_, ref err) => Some(err.as_ref()),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::Parse(ref msg) => write!(f, "parse:{}", msg),
+            ConfigError::Serialize(ref msg) => write!(f, "serialize:{}", msg),
+            ConfigError::UnknownPreset(ref name) => write!(f, "unknown preset:'{}'", name),
+            ConfigError::Invalid(ref msg) => write!(f, "invalid:{}", msg),
+            ConfigError::File(ref path, ref err) => write!(f, "'{}':{}", path, err)
+        }
+    }
+}
@@ -1,62 +1,213 @@
 use std::cmp::Ordering;
 use std::fmt;
 use std::ops::{ Add, AddAssign, Sub, SubAssign };
+use std::str::FromStr;
 use rand::Rng;
 
-// 1 year has 12 months with 30 days
+use super::calendar::{ Calendar, SIMPLE_CALENDAR };
+use super::date_error::DateError;
+use super::Season;
+
+// months and years of the simple calendar, also used as nominal lengths of time spans
 pub const DAYS_PER_MONTH: u32 = 30;
 pub const MONTHS_PER_YEAR: u32 = 12;
 pub const DAYS_PER_YEAR: u32 = DAYS_PER_MONTH * MONTHS_PER_YEAR;
 
-#[derive(Eq, Clone, Copy)]
+/// Day of a calendar, which is kept by the date and by the dates calculated from it.
+/// Years are signed, year 0 is the year before year 1.
+#[derive(Clone, Copy)]
 pub struct Date {
     day: u32,
     month: u32,
-    year: u32
+    year: i32,
+    calendar: &'static dyn Calendar
+}
+
+/// Unit by which a `DateRange` advances.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DateStep {
+    Day,
+    Month,
+    Year
+}
+
+/// Dates from a start up to, but excluding, an end. Months and years
+/// are counted from the start, so short months don't shift later dates.
+pub struct DateRange {
+    start: Date,
+    end: Date,
+    step: DateStep,
+    index: u32
 }
 
 impl Date {
 
+    /// Date of the simple calendar.
+    pub fn new(year: i32, month: u32, day: u32) -> Date {
+        Self::with_calendar(&SIMPLE_CALENDAR, year, month, day)
+    }
+
+    pub fn with_calendar(calendar: &'static dyn Calendar, year: i32, month: u32, day: u32) -> Date {
+        debug_assert!(month < calendar.get_months_per_year());
+        debug_assert!(day < calendar.get_days_in_month(month, year));
+        Self {
+            day,
+            month,
+            year,
+            calendar
+        }
+    }
+
+    // calendars create their dates in the simple calendar, dates move them into their own
+    pub(crate) fn from_parts(year: i32, month: u32, day: u32) -> Date {
+        Self {
+            day,
+            month,
+            year,
+            calendar: &SIMPLE_CALENDAR
+        }
+    }
+
+    /// Date of the simple calendar, the given number of days since the start of year 0.
+    pub fn from_ordinal(ordinal: i64) -> Date {
+        Self::from_ordinal_in(&SIMPLE_CALENDAR, ordinal)
+    }
+
+    pub fn from_ordinal_in(calendar: &'static dyn Calendar, ordinal: i64) -> Date {
+        Self {
+            calendar,
+            ..calendar.get_date_of_day(ordinal)
+        }
+    }
+
+    /// Reads a date in the `Display` format of the calendar.
+    pub fn parse_in(calendar: &'static dyn Calendar, text: &str) -> Result<Date, DateError> {
+        calendar.parse_date(text).map(|date| Self { calendar, ..date })
+    }
+
+    pub fn get_calendar(&self) -> &'static dyn Calendar {
+        self.calendar
+    }
+
     pub fn get_day(&self) -> u32 {
         self.day
     }
     pub fn get_month(&self) -> u32 {
         self.month
     }
-    pub fn get_year(&self) -> u32 {
+    pub fn get_year(&self) -> i32 {
         self.year
     }
 
-    pub fn random<R: Rng + ?Sized>(min_year: u32, max_year: u32, rng: &mut R) -> Date {
+    /// Days since the start of year 0, negative for earlier dates.
+    pub fn to_ordinal(&self) -> i64 {
+        self.calendar.get_day_number(self)
+    }
+
+    pub fn get_days_in_year(&self) -> u32 {
+        self.calendar.get_days_in_year(self.year)
+    }
+
+    pub fn get_days_in_month(&self) -> u32 {
+        self.calendar.get_days_in_month(self.month, self.year)
+    }
+
+    pub fn get_month_name(&self) -> Option<&'static str> {
+        self.calendar.get_month_name(self.month)
+    }
+
+    pub fn get_season(&self) -> Season {
+        self.calendar.get_season(self.month)
+    }
+
+    /// Day of the week counted from 0.
+    pub fn get_weekday(&self) -> u32 {
+        self.calendar.get_weekday(self)
+    }
+
+    pub fn get_weekday_name(&self) -> Option<&'static str> {
+        self.calendar.get_weekday_name(self.calendar.get_weekday(self))
+    }
+
+    pub fn random<R: Rng + ?Sized>(calendar: &'static dyn Calendar, min_year: i32, max_year: i32, rng: &mut R) -> Date {
+        let year = rng.gen_range(min_year, max_year);
+        let month = rng.gen_range(0, calendar.get_months_per_year());
         Self {
-            day: rng.gen_range(0, DAYS_PER_MONTH),
-            month: rng.gen_range(0, MONTHS_PER_YEAR),
-            year: rng.gen_range(min_year, max_year)
+            day: rng.gen_range(0, calendar.get_days_in_month(month, year)),
+            month,
+            year,
+            calendar
         }
     }
 
     pub fn random_future_years_range<R: Rng + ?Sized>(&self, range: (u32, u32), rng: &mut R) -> Date {
-        *self + rng.gen_range(range.0 * DAYS_PER_YEAR, range.1 * DAYS_PER_YEAR)
+        let days_per_year = self.get_days_in_year();
+        *self + rng.gen_range(range.0 * days_per_year, range.1 * days_per_year)
     }
 
     pub fn random_past_years_range<R: Rng + ?Sized>(&self, range: (u32, u32), rng: &mut R) -> Date {
-        *self - rng.gen_range(range.0 * DAYS_PER_YEAR, range.1 * DAYS_PER_YEAR)
+        let days_per_year = self.get_days_in_year();
+        *self - rng.gen_range(range.0 * days_per_year, range.1 * days_per_year)
     }
     
     pub fn set_day(&mut self, day: u32) {
-        debug_assert!(day < DAYS_PER_MONTH);
+        debug_assert!(day < self.calendar.get_days_in_month(self.month, self.year));
         self.day = day;
     }
     pub fn set_month(&mut self, month: u32) {
-        debug_assert!(month < MONTHS_PER_YEAR);
+        debug_assert!(month < self.calendar.get_months_per_year());
         self.month = month;
     }
-    pub fn set_year(&mut self, year: u32) {
+    pub fn set_year(&mut self, year: i32) {
         self.year = year;
     }
 
+    /// Same day and month some years later, days missing in the target month
+    /// fall back to its last day.
     pub fn advance_years(&mut self, years: u32) {
-        self.year += years;
+        self.year += years as i32;
+        self.clamp_day();
+    }
+
+    /// Same day some months later, days missing in the target month
+    /// fall back to its last day.
+    pub fn advance_months(&mut self, months: u32) {
+        let months_per_year = self.calendar.get_months_per_year();
+        let month = self.month + months;
+        self.year += (month / months_per_year) as i32;
+        self.month = month % months_per_year;
+        self.clamp_day();
+    }
+
+    pub fn range(&self, end: Date, step: DateStep) -> DateRange {
+        DateRange {
+            start: *self,
+            end,
+            step,
+            index: 0
+        }
+    }
+
+    fn clamp_day(&mut self) {
+        self.day = self.day.min(self.calendar.get_days_in_month(self.month, self.year) - 1);
+    }
+}
+
+impl Iterator for DateRange {
+    type Item = Date;
+
+    fn next(&mut self) -> Option<Date> {
+        let mut date = self.start;
+        match self.step {
+            DateStep::Day => date += self.index,
+            DateStep::Month => date.advance_months(self.index),
+            DateStep::Year => date.advance_years(self.index)
+        }
+        if date >= self.end {
+            return None;
+        }
+        self.index += 1;
+        Some(date)
     }
 }
 
@@ -65,7 +216,8 @@ impl Default for Date {
         Self {
             day: 0,
             month: 0,
-            year: 1000
+            year: 1000,
+            calendar: &SIMPLE_CALENDAR
         }
     }
 }
@@ -73,71 +225,25 @@ impl Default for Date {
 impl Add<u32> for Date {
     type Output = Date;
 
-    fn add(mut self, day_amount: u32) -> Self {
-        if day_amount >= DAYS_PER_YEAR {
-            self.year += day_amount / DAYS_PER_YEAR;
-        }
-        let days_left = day_amount % DAYS_PER_YEAR;
-        let additional_months = days_left / DAYS_PER_MONTH;
-        let additional_days = days_left % DAYS_PER_MONTH;
-        if additional_months > 0 {
-            if self.month + additional_months < MONTHS_PER_YEAR {
-                self.month += additional_months;
-            } else {
-                self.month = (self.month + additional_months) % MONTHS_PER_YEAR;
-                self.year += 1;
-            }
-        }
-        if additional_days > 0 {
-            if self.day + additional_days < DAYS_PER_MONTH {
-                self.day += additional_days;
-            } else {
-                self.day = (self.day + additional_days) % DAYS_PER_MONTH;
-                self.month += 1;
-                if self.month == MONTHS_PER_YEAR {
-                    self.month = 0;
-                    self.year += 1;
-                }
-            }
-        }
-        self
+    fn add(self, day_amount: u32) -> Self {
+        Date::from_ordinal_in(self.calendar, self.to_ordinal() + day_amount as i64)
     }
 }
 
 impl Sub<u32> for Date {
     type Output = Date;
 
-    fn sub(mut self, day_amount: u32) -> Self {
-        if day_amount >= DAYS_PER_YEAR {
-            self.year -= day_amount / DAYS_PER_YEAR;
-        }
-        let days_left = day_amount % DAYS_PER_YEAR;
-        let additional_months = days_left / DAYS_PER_MONTH;
-        let additional_days = days_left % DAYS_PER_MONTH;
-        if additional_months > 0 {
-            if self.month >= additional_months {
-                self.month -= additional_months;
-            } else {
-                let diff = (self.month as i32 - additional_months as i32).abs() as u32;
-                self.month = MONTHS_PER_YEAR - diff;
-                self.year -= 1;
-            }
-        }
-        if additional_days > 0 {
-            if self.day >= additional_days {
-                self.day -= additional_days;
-            } else {
-                let diff = (self.day as i32 - additional_days as i32).abs() as u32;
-                self.day = DAYS_PER_MONTH - diff;
-                if self.month > 0 {
-                    self.month -= 1;
-                } else {
-                    self.month = MONTHS_PER_YEAR - 1;
-                    self.year -= 1;
-                }
-            }
-        }
-        self
+    fn sub(self, day_amount: u32) -> Self {
+        Date::from_ordinal_in(self.calendar, self.to_ordinal() - day_amount as i64)
+    }
+}
+
+/// Days from the other date to this one, negative if the other date is later.
+impl Sub<Date> for Date {
+    type Output = i64;
+
+    fn sub(self, other: Date) -> i64 {
+        self.to_ordinal() - other.to_ordinal()
     }
 }
 
@@ -153,6 +259,15 @@ impl SubAssign<u32> for Date {
     }
 }
 
+/// Reads dates of the simple calendar.
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(text: &str) -> Result<Date, DateError> {
+        Date::parse_in(&SIMPLE_CALENDAR, text)
+    }
+}
+
 
 impl Ord for Date {
     fn cmp(&self, other: &Self) -> Ordering {
@@ -184,6 +299,7 @@ impl PartialOrd for Date {
     }
 }
 
+// dates of different calendars are compared by their numbers
 impl PartialEq for Date {
     fn eq(&self, other: &Self) -> bool {
         self.day == other.day &&
@@ -192,8 +308,16 @@ impl PartialEq for Date {
     }
 }
 
+impl Eq for Date {}
+
 impl fmt::Display for Date {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}-{}-{}", self.year, self.month + 1, self.day + 1)
+        self.calendar.format_date(self, f)
+    }
+}
+
+impl fmt::Debug for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Date({}, {})", self, self.calendar.get_name())
     }
 }
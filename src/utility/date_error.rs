@@ -0,0 +1,27 @@
+use std::fmt;
+use std::error::Error;
+
+#[derive(Debug)]
+pub enum DateError {
+    Parse(String),
+    Invalid(String)
+}
+
+impl Error for DateError {
+
+    fn description(&self) -> &str {
+        match *self {
+            DateError::Parse(_) => "parse",
+            DateError::Invalid(_) => "invalid"
+        }
+    }
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DateError::Parse(ref text) => write!(f, "parse:'{}'", text),
+            DateError::Invalid(ref msg) => write!(f, "invalid:{}", msg)
+        }
+    }
+}
@@ -4,7 +4,12 @@ use std::io;
 
 #[derive(Debug)]
 pub enum FileError {
-    IO(io::Error)
+    IO(io::Error),
+    Read(String, io::Error),
+    Write(String, io::Error),
+    MissingResource(String),
+    Empty(String),
+    Serialize(String)
 }
 
 impl From<io::Error> for FileError {
@@ -17,13 +22,21 @@ impl Error for FileError {
 
     fn description(&self) -> &str {
         match *self {
-            FileError::IO(_) => "io"
+            FileError::IO(_) => "io",
+            FileError::Read(_, _) => "read",
+            FileError::Write(_, _) => "write",
+            FileError::MissingResource(_) => "missing resource",
+            FileError::Empty(_) => "empty",
+            FileError::Serialize(_) => "serialize"
         }
     }
 
-    fn cause(&self) -> Option<&Error> {
+    fn cause(&self) -> Option<&dyn Error> {
         match *self {
-            FileError::IO(ref err) => Some(err)
+            FileError::IO(ref err) => Some(err),
+            FileError::Read(_, ref err) => Some(err),
+            FileError::Write(_, ref err) => Some(err),
+            FileError::MissingResource(_) | FileError::Empty(_) | FileError::Serialize(_) => None
         }
     }
 }
@@ -31,7 +44,12 @@ impl Error for FileError {
 impl fmt::Display for FileError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            FileError::IO(ref err) => write!(f, "{}:{}", self.description(), err)
+            FileError::IO(ref err) => write!(f, "io:{}", err),
+            FileError::Read(ref path, ref err) => write!(f, "read:'{}':{}", path, err),
+            FileError::Write(ref path, ref err) => write!(f, "write:'{}':{}", path, err),
+            FileError::MissingResource(ref path) => write!(f, "missing resource:'{}'", path),
+            FileError::Empty(ref path) => write!(f, "empty:'{}'", path),
+            FileError::Serialize(ref msg) => write!(f, "serialize:{}", msg)
         }
     }
 }
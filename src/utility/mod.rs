@@ -1,8 +1,20 @@
 
 pub mod application_error;
 pub mod file_error;
+pub mod config_error;
+pub mod simulation_error;
+pub mod script_error;
+pub mod date_error;
 pub mod read_file;
 pub mod date;
+pub mod calendar;
+pub mod season;
+pub mod resource_locator;
+pub mod symbol;
 
-pub use self::date::Date;
+pub use self::date::{ Date, DateStep, DateRange };
+pub use self::calendar::{ Calendar, CalendarKind };
+pub use self::season::Season;
+pub use self::resource_locator::ResourceLocator;
+pub use self::symbol::{ Symbol, SymbolTable };
 pub use self::date::{ DAYS_PER_YEAR, DAYS_PER_MONTH, MONTHS_PER_YEAR };
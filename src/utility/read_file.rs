@@ -1,14 +1,33 @@
 use std::io;
 use std::fs;
-use std::io::Read;
+use std::io::{ Read, Write };
 
 use super::file_error::FileError;
 
 pub fn read_file(file_path: &str) -> Result<String, FileError>  {
     trace!("reading file '{}'", file_path);
-    let file = fs::File::open(file_path)?;
+    let with_path = |err| FileError::Read(file_path.to_owned(), err);
+    let file = fs::File::open(file_path).map_err(with_path)?;
     let mut buf_reader = io::BufReader::new(file);
     let mut content = String::new();
-    buf_reader.read_to_string(&mut content)?;
+    buf_reader.read_to_string(&mut content).map_err(with_path)?;
     Ok(content)
 }
+
+/// Like `read_file`, but files without any non-whitespace content are an error.
+pub fn read_non_empty_file(file_path: &str) -> Result<String, FileError> {
+    let content = read_file(file_path)?;
+    match content.trim().is_empty() {
+        true => Err(FileError::Empty(file_path.to_owned())),
+        false => Ok(content)
+    }
+}
+
+pub fn write_file(file_path: &str, content: &str) -> Result<(), FileError> {
+    trace!("writing file '{}'", file_path);
+    let with_path = |err| FileError::Write(file_path.to_owned(), err);
+    let file = fs::File::create(file_path).map_err(with_path)?;
+    let mut buf_writer = io::BufWriter::new(file);
+    buf_writer.write_all(content.as_bytes()).map_err(with_path)?;
+    buf_writer.flush().map_err(with_path)
+}
@@ -0,0 +1,58 @@
+use std::env;
+use std::path::{ Path, PathBuf };
+
+pub const RESOURCE_DIR_ENV: &str = "TOWNS_RESOURCE_DIR";
+const DEFAULT_RESOURCE_DIR: &str = "resources";
+
+/// Decides where resources are read from. An explicit directory wins over the
+/// environment variable, which wins over `resources` in the working directory.
+/// Without any directory the name lists compiled into the binary are used.
+#[derive(Clone)]
+pub struct ResourceLocator {
+    dir: Option<PathBuf>,
+    explicit: bool
+}
+
+impl ResourceLocator {
+
+    pub fn new(explicit_dir: Option<&Path>) -> Self {
+        if let Some(dir) = explicit_dir {
+            return Self::from_dir(dir);
+        }
+        if let Ok(dir) = env::var(RESOURCE_DIR_ENV) {
+            info!("Using resource directory '{}' from {}", dir, RESOURCE_DIR_ENV);
+            return Self::from_dir(Path::new(&dir));
+        }
+        let default_dir = Path::new(DEFAULT_RESOURCE_DIR);
+        if default_dir.is_dir() {
+            return Self {
+                dir: Some(default_dir.to_path_buf()),
+                explicit: false
+            };
+        }
+        Self::embedded()
+    }
+
+    pub fn from_dir(dir: &Path) -> Self {
+        Self {
+            dir: Some(dir.to_path_buf()),
+            explicit: true
+        }
+    }
+
+    pub fn embedded() -> Self {
+        Self {
+            dir: None,
+            explicit: false
+        }
+    }
+
+    pub fn get_dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// Explicitly configured directories must contain the resources, there is no fallback.
+    pub fn is_explicit(&self) -> bool {
+        self.explicit
+    }
+}
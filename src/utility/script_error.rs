@@ -0,0 +1,27 @@
+use std::fmt;
+use std::error::Error;
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Parse(String, String),
+    Runtime(String, String)
+}
+
+impl Error for ScriptError {
+
+    fn description(&self) -> &str {
+        match *self {
+            ScriptError::Parse(_, _) => "parse",
+            ScriptError::Runtime(_, _) => "runtime"
+        }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ScriptError::Parse(ref path, ref msg) => write!(f, "parse:'{}':{}", path, msg),
+            ScriptError::Runtime(ref script, ref msg) => write!(f, "runtime:'{}':{}", script, msg)
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+use serde::{ Serialize, Deserialize };
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Season {
+    Winter,
+    Spring,
+    Summer,
+    Autumn
+}
+
+impl Season {
+
+    pub const ALL: [Season; 4] = [Season::Winter, Season::Spring, Season::Summer, Season::Autumn];
+
+    /// Season of a month if the year starts in midwinter,
+    /// so the first months are winter and the last one begins the next winter.
+    pub fn of_month(month: u32, months_per_year: u32) -> Season {
+        Season::ALL[(((month + 1) * 4 / months_per_year.max(1)) % 4) as usize]
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Season::Winter => write!(f, "winter"),
+            Season::Spring => write!(f, "spring"),
+            Season::Summer => write!(f, "summer"),
+            Season::Autumn => write!(f, "autumn")
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt;
+use std::error::Error;
+
+/// Inconsistent simulation state, e.g. references to persons who are not part of the population.
+#[derive(Debug)]
+pub enum SimulationError {
+    UnknownPerson(u32),
+    MissingSpouse { person: u32, spouse: u32 },
+    UnexpectedAttribute { person: u32, expected: &'static str },
+    Random(String),
+    ThreadPool(String),
+    NoTownSite(u32),
+    IdsExhausted
+}
+
+impl From<rand::Error> for SimulationError {
+    fn from(err: rand::Error) -> SimulationError {
+        SimulationError::Random(err.to_string())
+    }
+}
+
+impl Error for SimulationError {
+
+    fn description(&self) -> &str {
+        match *self {
+            SimulationError::UnknownPerson(_) => "unknown person",
+            SimulationError::MissingSpouse { .. } => "missing spouse",
+            SimulationError::UnexpectedAttribute { .. } => "unexpected attribute",
+            SimulationError::Random(_) => "random",
+            SimulationError::ThreadPool(_) => "thread pool",
+            SimulationError::NoTownSite(_) => "no town site",
+            SimulationError::IdsExhausted => "ids exhausted"
+        }
+    }
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SimulationError::UnknownPerson(id) => write!(f, "unknown person:{}", id),
+            SimulationError::MissingSpouse { person, spouse } => write!(f, "missing spouse:{} of person {}", spouse, person),
+            SimulationError::UnexpectedAttribute { person, expected } => write!(f, "unexpected attribute:person {} should have been {}", person, expected),
+            SimulationError::Random(ref msg) => write!(f, "random:{}", msg),
+            SimulationError::ThreadPool(ref msg) => write!(f, "thread pool:{}", msg),
+            SimulationError::NoTownSite(count) => write!(f, "no town site:not enough habitable land for {} towns", count),
+            SimulationError::IdsExhausted => write!(f, "ids exhausted:no person ids left for new persons")
+        }
+    }
+}
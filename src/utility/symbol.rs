@@ -0,0 +1,67 @@
+use std::sync::Arc;
+use std::collections::HashMap;
+
+/// Name stored as its index in a `SymbolTable`, which turns it back into text.
+/// Symbols of different tables can only be compared if one table was copied from the other.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Symbol(u32);
+
+/// Append-only list of names, each stored once. Tables are copied along with
+/// the person generator that owns them, so symbols keep their meaning in the copies.
+#[derive(Clone)]
+pub struct SymbolTable {
+    names: Vec<Arc<str>>,
+    symbols: HashMap<Arc<str>, Symbol>
+}
+
+impl Symbol {
+    /// Name of persons who have not been named, present in every table.
+    pub const UNKNOWN: Symbol = Symbol(0);
+}
+
+impl SymbolTable {
+
+    /// The symbol of the name, which is added to the table if it is new.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol(self.names.len() as u32);
+        let name: Arc<str> = Arc::from(name);
+        self.names.push(name.clone());
+        self.symbols.insert(name, symbol);
+        symbol
+    }
+
+    /// The symbol of the name if it is in the table.
+    pub fn find(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).cloned()
+    }
+
+    /// Text of the symbol, symbols of another table may resolve to `Unknown`.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        match self.names.get(symbol.0 as usize) {
+            Some(name) => name,
+            None => &self.names[Symbol::UNKNOWN.0 as usize]
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl Default for SymbolTable {
+    fn default() -> SymbolTable {
+        let mut table = SymbolTable {
+            names: Vec::new(),
+            symbols: HashMap::new()
+        };
+        table.intern("Unknown");
+        table
+    }
+}
@@ -1 +1,3 @@
+// part of the public path, as in world::world::World
+#[allow(clippy::module_inception)]
 pub mod world;
\ No newline at end of file
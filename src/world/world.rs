@@ -1,44 +1,185 @@
-use rand::FromEntropy;
+use std::sync::{ Arc, Mutex };
+use std::path::Path;
+use rand::{ Rng, SeedableRng };
 use rand::rngs::StdRng;
+use rayon::prelude::*;
+use rayon::{ ThreadPool, ThreadPoolBuilder };
 
 use crate::utility::date::Date;
 use crate::utility::application_error::ApplicationError;
-use crate::town::town::Town;
+use crate::utility::simulation_error::SimulationError;
+use crate::utility::ResourceLocator;
+use crate::town::town::{ Town, YearCheckpoint };
+use crate::town::Observer;
 use crate::person::PersonGenerator;
+use crate::config::SimulationConfig;
+use crate::script::ScriptEngine;
+use crate::simulation::{ Pipeline, SimulationPhase };
+use crate::map::{ WorldMap, Position };
+use crate::utility::file_error::FileError;
 
 #[allow(unused)]
 pub struct World {
     rng: StdRng,
     person_generator: PersonGenerator,
-    towns: Vec<Town>
+    map: WorldMap,
+    towns: Vec<Town>,
+    // towns are progressed sequentially without a pool
+    thread_pool: Option<ThreadPool>
 }
 
 impl World {
     pub fn create(town_count: u32) -> Result<Self, ApplicationError> {
-        let mut rng = StdRng::from_entropy();
-        let start_date = Date::random(500, 4000, &mut rng);
-        let mut person_generator = PersonGenerator::new()?;
+        Self::create_with_resources(town_count, &ResourceLocator::new(None))
+    }
+
+    pub fn create_with_resources(town_count: u32, resources: &ResourceLocator) -> Result<Self, ApplicationError> {
+        Self::create_with_config(town_count, resources, &SimulationConfig::default())
+    }
+
+    pub fn create_with_config(town_count: u32, resources: &ResourceLocator, config: &SimulationConfig) -> Result<Self, ApplicationError> {
+        config.validate()?;
+        let seed = config.seed.unwrap_or_else(rand::random);
+        info!("World seed: {}", seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        // dates of all towns follow the configured calendar, as they are calculated from the start date
+        let start_date = Date::random(config.calendar.get_calendar(), 500, 4000, &mut rng);
+        let person_generator = PersonGenerator::with_resources(resources)?;
+        let scripts = Arc::new(ScriptEngine::with_resources(resources)?);
+        let pipeline = match config.phases {
+            Some(ref names) => Pipeline::from_names(names, scripts)?,
+            None => Pipeline::with_scripts(scripts)
+        };
+
+        let map = WorldMap::generate(&config.world, &mut rng);
+        let sites = map.find_sites(town_count, config.world.town_distance);
+        if (sites.len() as u32) < town_count {
+            return Err(SimulationError::NoTownSite(town_count).into());
+        }
 
         let mut towns = Vec::new();
-        for _ in 0..town_count {
-            towns.push(Town::found("Townshire", start_date, &mut person_generator, &mut rng));
+        for (index, site) in (0..town_count).zip(sites) {
+            let mut settlers = vec![(person_generator.random_culture(&mut rng), person_generator.random_religion(&mut rng))];
+            if rng.gen_bool(0.25) {
+                settlers.push((person_generator.random_culture(&mut rng), person_generator.random_religion(&mut rng)));
+            }
+            let name = person_generator.generate_town_name(settlers[0].0, &mut rng);
+            // every town creates persons on its own, with ids disjoint from the other towns
+            let town_generator = person_generator.split(index, town_count);
+            let mut town = Town::found(&name, start_date, site, &settlers, config, town_generator, &mut rng)?;
+            town.set_pipeline(pipeline.clone());
+            towns.push(town);
         }
 
         let world = Self {
-            rng: rng,
-            person_generator: person_generator,
-            towns: towns
+            rng,
+            person_generator,
+            map,
+            towns,
+            thread_pool: create_thread_pool(config.threads)?
         };
 
         Ok(world)
     }
 
-    pub fn progress(&mut self) {
+    /// Progresses all towns by one year. On error the world keeps its previous state.
+    pub fn progress(&mut self) -> Result<(), ApplicationError> {
         info!("Progressing world...");
-        let mut next_gen = Vec::new();
+        // towns only depend on their own state, so the result is the same for any thread count.
+        // A year is finished only once it has begun without error in every town.
+        let towns = &mut self.towns;
+        let begun: Vec<Result<YearCheckpoint, ApplicationError>> = match self.thread_pool {
+            Some(ref pool) => pool.install(|| towns.par_iter_mut()
+                .map(Town::begin_year)
+                .collect()),
+            None => towns.iter_mut()
+                .map(Town::begin_year)
+                .collect()
+        };
+        if begun.iter().any(Result::is_err) {
+            let mut error = None;
+            for (town, result) in towns.iter_mut().zip(begun) {
+                match result {
+                    Ok(checkpoint) => town.undo_year(checkpoint),
+                    Err(err) => { error.get_or_insert(err); }
+                }
+            }
+            return Err(error.unwrap());
+        }
+        let checkpoints = begun.into_iter().map(Result::unwrap);
+        match self.thread_pool {
+            Some(ref pool) => pool.install(|| towns.par_iter_mut()
+                .zip(checkpoints.collect::<Vec<YearCheckpoint>>())
+                .for_each(|(town, checkpoint)| town.finish_year(checkpoint))),
+            None => towns.iter_mut()
+                .zip(checkpoints)
+                .for_each(|(town, checkpoint)| town.finish_year(checkpoint))
+        }
         for town in &self.towns {
-            next_gen.push(town.progress_year(&mut self.person_generator));
+            town.notify_observers();
         }
-        self.towns = next_gen;
+        Ok(())
+    }
+
+    /// Registers an observer which is notified of the events and years of every town.
+    /// Towns progressed in parallel report their events in turns.
+    pub fn add_observer(&mut self, observer: Box<dyn Observer + Send>) {
+        let observer = Arc::new(Mutex::new(observer));
+        for town in self.towns.iter_mut() {
+            town.add_shared_observer(observer.clone());
+        }
+    }
+
+    /// Appends a phase to the pipeline of every town.
+    pub fn add_phase(&mut self, phase: Arc<dyn SimulationPhase>) {
+        for town in self.towns.iter_mut() {
+            town.get_pipeline_mut().add(phase.clone());
+        }
+    }
+
+    pub fn get_towns(&self) -> &[Town] {
+        &self.towns
+    }
+
+    pub fn get_map(&self) -> &WorldMap {
+        &self.map
+    }
+
+    /// Distance between the sites of two towns in map tiles.
+    pub fn get_distance(&self, from: usize, to: usize) -> f32 {
+        self.towns[from].get_site().distance(self.towns[to].get_site())
+    }
+
+    /// Indices of the other towns with their distance, nearest first.
+    pub fn get_neighbours(&self, index: usize) -> Vec<(usize, f32)> {
+        let mut neighbours: Vec<(usize, f32)> = (0..self.towns.len())
+            .filter(|&other| other != index)
+            .map(|other| (other, self.get_distance(index, other)))
+            .collect();
+        neighbours.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        neighbours
+    }
+
+    /// Renders the world map with the names of the towns.
+    pub fn save_map_svg(&self, path: &Path) -> Result<(), FileError> {
+        let towns: Vec<(&str, Position)> = self.towns.iter()
+            .map(|town| (town.get_name(), town.get_site().position))
+            .collect();
+        self.map.save_svg(path, &towns)
+    }
+}
+
+// 1 thread progresses towns sequentially, 0 uses one thread per core
+fn create_thread_pool(threads: usize) -> Result<Option<ThreadPool>, SimulationError> {
+    if threads == 1 {
+        return Ok(None);
     }
+    ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map(|pool| {
+            info!("Progressing towns with {} threads", pool.current_num_threads());
+            Some(pool)
+        })
+        .map_err(|err| SimulationError::ThreadPool(err.to_string()))
 }
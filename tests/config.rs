@@ -0,0 +1,42 @@
+extern crate procedural_towns;
+
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::person::MarriageRules;
+use procedural_towns::utility::DAYS_PER_YEAR;
+
+fn with_marriage(marriage: MarriageRules) -> SimulationConfig {
+    SimulationConfig {
+        marriage,
+        ..SimulationConfig::default()
+    }
+}
+
+#[test]
+fn presets_are_valid() {
+    for preset in procedural_towns::config::PRESETS.iter() {
+        assert!(SimulationConfig::from_preset(preset).unwrap().validate().is_ok(), "preset {}", preset);
+    }
+}
+
+#[test]
+fn remarriage_age_gap_is_bounded() {
+    let config = with_marriage(MarriageRules { remarriage_max_age_gap: u32::MAX, ..MarriageRules::default() });
+    assert!(config.validate().is_err());
+    let config = with_marriage(MarriageRules { remarriage_max_age_gap: 30, ..MarriageRules::default() });
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn mourning_period_is_bounded() {
+    let config = with_marriage(MarriageRules { mourning_period: 100 * DAYS_PER_YEAR, ..MarriageRules::default() });
+    assert!(config.validate().is_err());
+    let config = with_marriage(MarriageRules { mourning_period: 2 * DAYS_PER_YEAR, ..MarriageRules::default() });
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn toml_parses_back() {
+    let config = SimulationConfig::from_preset("harsh").unwrap();
+    let parsed = SimulationConfig::parse(&config.to_toml().unwrap(), None).unwrap();
+    assert_eq!(parsed.to_toml().unwrap(), config.to_toml().unwrap());
+}
@@ -0,0 +1,141 @@
+extern crate proptest;
+extern crate procedural_towns;
+
+use proptest::prelude::*;
+
+use procedural_towns::utility::{ Date, DateStep, Calendar, CalendarKind };
+use procedural_towns::utility::calendar::{ GregorianCalendar, CustomCalendar, Month };
+
+// dates without a calendar use the simple one
+const MAX_ORDINAL: i64 = 2_000_000;
+const MAX_DAYS: u32 = 1_000_000;
+
+fn custom_calendar() -> CustomCalendar {
+    CustomCalendar {
+        name: String::from("Test Reckoning"),
+        weekdays: vec![String::from("Moonday"), String::from("Emberday"), String::from("Restday")],
+        months: vec![
+            Month { name: String::from("Deep Frost"), days: 40, season: None },
+            Month { name: String::from("Thaw"), days: 35, season: None },
+            Month { name: String::from("Harvest"), days: 45, season: None }
+        ]
+    }
+}
+
+proptest! {
+    #[test]
+    fn add_and_sub_are_inverse(ordinal in -MAX_ORDINAL..MAX_ORDINAL, days in 0..MAX_DAYS) {
+        let date = Date::from_ordinal(ordinal);
+        prop_assert_eq!((date + days) - days, date);
+        prop_assert_eq!((date - days) + days, date);
+    }
+
+    #[test]
+    fn difference_is_added_days(ordinal in -MAX_ORDINAL..MAX_ORDINAL, days in 0..MAX_DAYS) {
+        let date = Date::from_ordinal(ordinal);
+        prop_assert_eq!((date + days) - date, days as i64);
+        prop_assert_eq!((date - days) - date, -(days as i64));
+    }
+
+    #[test]
+    fn ordinal_round_trips(ordinal in -MAX_ORDINAL..MAX_ORDINAL) {
+        prop_assert_eq!(Date::from_ordinal(ordinal).to_ordinal(), ordinal);
+    }
+
+    #[test]
+    fn display_parses_back(ordinal in -MAX_ORDINAL..MAX_ORDINAL) {
+        let date = Date::from_ordinal(ordinal);
+        prop_assert_eq!(date.to_string().parse::<Date>().unwrap(), date);
+    }
+
+    #[test]
+    fn gregorian_add_and_sub_are_inverse(ordinal in -MAX_ORDINAL..MAX_ORDINAL, days in 0..MAX_DAYS as i64) {
+        let calendar = GregorianCalendar;
+        let date = calendar.get_date_of_day(ordinal);
+        let later = calendar.get_date_of_day(calendar.get_day_number(&date) + days);
+        prop_assert_eq!(calendar.get_date_of_day(calendar.get_day_number(&later) - days), date);
+        prop_assert!(date.get_day() < calendar.get_days_in_month(date.get_month(), date.get_year()));
+    }
+
+    #[test]
+    fn gregorian_years_are_contiguous(year in -5000..5000) {
+        let calendar = GregorianCalendar;
+        let length = calendar.get_days_before_year(year + 1) - calendar.get_days_before_year(year);
+        prop_assert_eq!(length, calendar.get_days_in_year(year) as i64);
+    }
+
+    #[test]
+    fn custom_add_and_sub_are_inverse(ordinal in -MAX_ORDINAL..MAX_ORDINAL, days in 0..MAX_DAYS as i64) {
+        let calendar = custom_calendar();
+        let date = calendar.get_date_of_day(ordinal);
+        let later = calendar.get_date_of_day(calendar.get_day_number(&date) + days);
+        prop_assert_eq!(calendar.get_date_of_day(calendar.get_day_number(&later) - days), date);
+    }
+
+    #[test]
+    fn custom_named_dates_parse(ordinal in -MAX_ORDINAL..MAX_ORDINAL) {
+        let calendar = custom_calendar();
+        let date = calendar.get_date_of_day(ordinal);
+        let text = format!("{}, {} {} {}",
+            calendar.get_weekday_name(calendar.get_weekday(&date)).unwrap(),
+            date.get_day() + 1,
+            calendar.get_month_name(date.get_month()).unwrap(),
+            date.get_year());
+        prop_assert_eq!(calendar.parse_date(&text).unwrap(), date);
+    }
+}
+
+#[test]
+fn past_dates_cross_year_zero() {
+    let date = Date::new(1, 0, 0);
+    let earlier = date - 400;
+    assert_eq!(earlier.get_year(), -1);
+    assert_eq!(earlier.to_string(), "-1-11-21");
+    assert_eq!("-1-11-21".parse::<Date>().unwrap(), earlier);
+}
+
+#[test]
+fn invalid_dates_are_rejected() {
+    assert!("1000-13-1".parse::<Date>().is_err());
+    assert!("1000-2-31".parse::<Date>().is_err());
+    assert!("1000-0-1".parse::<Date>().is_err());
+    assert!("1000-2".parse::<Date>().is_err());
+    assert!("someday".parse::<Date>().is_err());
+}
+
+#[test]
+fn ranges_step_by_unit() {
+    let start = Date::new(1000, 10, 5);
+    let end = Date::new(1003, 1, 0);
+    assert_eq!(start.range(start + 10, DateStep::Day).count(), 10);
+    let months: Vec<Date> = start.range(end, DateStep::Month).collect();
+    assert_eq!(months.len(), 27);
+    assert_eq!(months[2], Date::new(1001, 0, 5));
+    let years: Vec<i32> = start.range(end, DateStep::Year).map(|date| date.get_year()).collect();
+    assert_eq!(years, vec![1000, 1001, 1002]);
+    assert_eq!(end.range(start, DateStep::Day).count(), 0);
+}
+
+#[test]
+fn gregorian_months_keep_their_day() {
+    let calendar = GregorianCalendar;
+    let leap_day = calendar.parse_date("2000-2-29").unwrap();
+    assert_eq!(calendar.get_day_number(&leap_day) + 1, calendar.get_day_number(&calendar.parse_date("2000-3-1").unwrap()));
+    assert!(calendar.parse_date("1900-2-29").is_err());
+    assert!(calendar.parse_date("-4-2-29").is_ok());
+}
+
+#[test]
+fn dates_keep_their_calendar() {
+    let gregorian = CalendarKind::Gregorian.get_calendar();
+    let custom = CalendarKind::Custom(custom_calendar()).get_calendar();
+    assert!(std::ptr::eq(custom, CalendarKind::Custom(custom_calendar()).get_calendar()));
+
+    let leap_day = Date::with_calendar(gregorian, 2000, 1, 28);
+    assert_eq!((leap_day + 1).to_string(), "2000-3-1");
+    assert_eq!((leap_day + 366).get_calendar().get_name(), "gregorian");
+    let harvest = Date::with_calendar(custom, 1000, 2, 44);
+    assert_eq!((harvest + 1).get_month_name(), Some("Deep Frost"));
+    assert_eq!(Date::parse_in(custom, &harvest.to_string()).unwrap(), harvest);
+    assert_eq!(Date::new(1000, 1, 29).get_days_in_month(), 30);
+}
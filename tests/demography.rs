@@ -0,0 +1,77 @@
+extern crate procedural_towns;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ Person, Population, Demography, AgeCurve, AgePreference };
+use procedural_towns::utility::Date;
+
+fn person(id: u32, male: bool, age: i32, today: Date) -> Person {
+    let mut person = Person::new(id);
+    match male {
+        true => person.set_male(),
+        false => person.set_female()
+    }
+    person.set_birthday(Date::new(today.get_year() - age, 0, 0));
+    person
+}
+
+#[test]
+fn curves_interpolate_between_points() {
+    let curve = AgeCurve::new(vec![(30, 1.), (20, 0.)]);
+    assert_eq!(curve.get_points(), &[(20, 0.), (30, 1.)]);
+    assert_eq!(curve.get_rate(10), 0.);
+    assert_eq!(curve.get_rate(20), 0.);
+    assert!((curve.get_rate(25) - 0.5).abs() < 1e-6);
+    assert_eq!(curve.get_rate(30), 1.);
+    assert_eq!(curve.get_rate(80), 1.);
+    assert_eq!(AgeCurve::new(Vec::new()).get_rate(30), 0.);
+}
+
+#[test]
+fn preferences_peak_at_their_mean() {
+    let preference = AgePreference::new(20., 4.);
+    assert_eq!(preference.get_weight(20.), 1.);
+    assert!(preference.get_weight(24.) < 1.);
+    assert_eq!(preference.get_weight(16.), preference.get_weight(24.));
+    assert!(preference.get_weight(30.) < preference.get_weight(24.));
+}
+
+#[test]
+fn children_have_no_marriage_weight() {
+    let today = Date::new(1000, 0, 0);
+    let demography = Demography::default();
+    let girl = person(0, false, 13, today);
+    assert_eq!(demography.get_marriage_age_weight(&girl, 13), 0.);
+    assert!(demography.get_marriage_age_weight(&girl, 14) > 0.);
+    assert_eq!(demography.get_fertility(&girl, 13), 0.);
+    assert!(demography.get_fertility(&girl, 20) > 0.);
+}
+
+#[test]
+fn mothers_are_of_fertile_age() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut population = Population::default();
+    population.set_demography(Demography {
+        fertility_female: AgeCurve::new(vec![(29, 0.), (30, 1.), (35, 1.), (36, 0.)]),
+        ..Demography::default()
+    });
+    for age in 10..60 {
+        population.add(person(age as u32, false, age, today));
+    }
+    for id in 100..110 {
+        population.add(person(id, true, 30, today));
+    }
+    population.handle_fertility(today);
+    for _ in 0..40 {
+        population.random_pregnancy(today, &mut rng);
+    }
+    // only the six women between 30 and 35 can become pregnant
+    let ages: Vec<u32> = population.iter()
+        .filter(|p| p.get_attr().get_due_date().is_some())
+        .map(|p| p.get_age(&today))
+        .collect();
+    assert!(!ages.is_empty());
+    assert!(ages.iter().all(|age| (30..=35).contains(age)), "mothers aged {:?}", ages);
+}
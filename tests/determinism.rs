@@ -0,0 +1,73 @@
+extern crate procedural_towns;
+
+use std::collections::HashSet;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::world::world::World;
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::person::{ PersonGenerator, Culture };
+use procedural_towns::utility::ResourceLocator;
+
+const SEED: u64 = 7;
+const TOWNS: u32 = 4;
+const YEARS: u32 = 40;
+
+// every person of every town with the state that results from the random choices
+fn run(threads: usize) -> Vec<String> {
+    let config = SimulationConfig {
+        seed: Some(SEED),
+        threads,
+        ..SimulationConfig::default()
+    };
+    let mut world = World::create_with_config(TOWNS, &ResourceLocator::new(None), &config).unwrap();
+    for _ in 0..YEARS {
+        world.progress().unwrap();
+    }
+    world.get_towns().iter()
+        .flat_map(|town| town.get_population().iter()
+            .map(move |p| format!("{} {} {} {} {:?} {:?} {}",
+                town.get_name(), p.get_id(), p.get_full_name(town.get_person_generator().get_symbols()), p.get_birthday(),
+                p.get_father(), p.get_spouse(), p.get_wealth())))
+        .collect()
+}
+
+#[test]
+fn thread_count_does_not_change_results() {
+    let sequential = run(1);
+    assert!(!sequential.is_empty());
+    assert_eq!(sequential, run(4));
+}
+
+#[test]
+fn split_generators_hand_out_disjoint_ids() {
+    let generator = PersonGenerator::new().unwrap();
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut generators: Vec<PersonGenerator> = (0..TOWNS).map(|i| generator.split(i, TOWNS)).collect();
+    let mut ids = HashSet::new();
+    // generators are used unevenly, as towns grow at different rates
+    for (index, generator) in generators.iter_mut().enumerate() {
+        for _ in 0..(index + 1) * 50 {
+            let id = generator.generate_random_person(Culture::default(), &mut rng).unwrap().get_id();
+            assert!(ids.insert(id), "id {} handed out twice", id);
+        }
+    }
+    // splitting a split generator keeps the ids disjoint from its siblings
+    let mut nested = generators[0].split(1, 2);
+    for _ in 0..100 {
+        let id = nested.generate_random_person(Culture::default(), &mut rng).unwrap().get_id();
+        assert!(ids.insert(id), "id {} handed out twice", id);
+    }
+}
+
+#[test]
+fn exhausted_generators_fail_instead_of_reusing_ids() {
+    let generator = PersonGenerator::new().unwrap();
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut last = generator.split(1, u32::MAX);
+    assert_eq!(last.generate_random_person(Culture::default(), &mut rng).unwrap().get_id(), 1);
+    assert!(last.generate_random_person(Culture::default(), &mut rng).is_err());
+    // splitting again does not wrap around either
+    let mut nested = last.split(2, 3);
+    assert!(nested.generate_random_person(Culture::default(), &mut rng).is_err());
+}
@@ -0,0 +1,63 @@
+extern crate procedural_towns;
+
+use std::fs;
+use std::path::Path;
+
+use procedural_towns::person::{ Person, Population };
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::utility::Date;
+use procedural_towns::utility::application_error::ApplicationError;
+use procedural_towns::utility::config_error::ConfigError;
+use procedural_towns::utility::file_error::FileError;
+use procedural_towns::utility::simulation_error::SimulationError;
+
+#[test]
+fn unknown_persons_are_errors() {
+    let today = Date::new(1000, 0, 0);
+    let mut population = Population::default();
+    population.add(Person::new(0));
+    match population.kill_person(7, today) {
+        Err(SimulationError::UnknownPerson(7)) => (),
+        other => panic!("unexpected result {:?}", other.err())
+    }
+    assert_eq!(population.size(), 1);
+    assert!(population.get_by_id(0).is_some());
+}
+
+#[test]
+fn missing_spouses_are_errors() {
+    let today = Date::new(1000, 0, 0);
+    let mut population = Population::default();
+    let mut widower = Person::new(0);
+    widower.set_male();
+    widower.begin_marriage(5, today);
+    population.add(widower);
+    match population.kill_person(0, today) {
+        Err(SimulationError::MissingSpouse { person: 0, spouse: 5 }) => (),
+        other => panic!("unexpected result {:?}", other.err())
+    }
+    assert_eq!(population.get_by_id(0).unwrap().get_spouse(), Some(5));
+}
+
+#[test]
+fn config_errors_name_their_file() {
+    let path = std::env::temp_dir().join(format!("towns-broken-config-{}.toml", std::process::id()));
+    fs::write(&path, "[marriage\nmourning_period = 3").unwrap();
+    match SimulationConfig::load(&path, None) {
+        Err(ApplicationError::Config(ConfigError::File(file, err))) => {
+            assert_eq!(Path::new(&file), path.as_path());
+            assert!(matches!(*err, ConfigError::Parse(_)));
+        },
+        other => panic!("unexpected result {:?}", other.err())
+    }
+    fs::write(&path, "preset = \"utopia\"").unwrap();
+    match SimulationConfig::load(&path, None) {
+        Err(ApplicationError::Config(ConfigError::File(_, err))) => assert!(matches!(*err, ConfigError::UnknownPreset(_))),
+        other => panic!("unexpected result {:?}", other.err())
+    }
+    fs::remove_file(&path).unwrap();
+    match SimulationConfig::load(&path, None) {
+        Err(ApplicationError::File(FileError::Read(file, _))) => assert_eq!(Path::new(&file), path.as_path()),
+        other => panic!("unexpected result {:?}", other.err())
+    }
+}
@@ -0,0 +1,77 @@
+extern crate procedural_towns;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ Person, Population, Wealth };
+use procedural_towns::town::{ Chronicle, Governance, SuccessionRule };
+use procedural_towns::utility::{ Date, SymbolTable };
+
+fn person(id: u32, birth_year: i32, father: Option<u32>) -> Person {
+    let mut person = Person::new(id);
+    person.set_male();
+    person.set_birthday(Date::new(birth_year, 0, 0));
+    if let Some(father) = father {
+        person.set_father(father);
+    }
+    person
+}
+
+// the founder 0 has the sons 1 and 2, and 2 has the son 3
+fn family() -> Population {
+    let mut population = Population::default();
+    population.add(person(0, 900, None));
+    let mut lord = person(1, 960, Some(0));
+    *lord.get_wealth_mut() = Wealth::new(u32::MAX, 0, 0);
+    population.add(lord);
+    population.add(person(2, 962, Some(0)));
+    population.add(person(3, 982, Some(2)));
+    population
+}
+
+#[test]
+fn nearest_relative_inherits_before_an_election() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut population = family();
+    population.kill_person(0, today).unwrap();
+    let symbols = SymbolTable::default();
+    let mut chronicle = Chronicle::default();
+    let mut governance = Governance::new(SuccessionRule::Hereditary, 0);
+    governance.update(today, &population, &symbols, &mut chronicle, &mut rng);
+    assert_eq!(governance.get_leader(), Some(1));
+
+    // the childless lord is followed by his brother, and the brother by his son
+    population.begin_update();
+    population.kill_person(1, today).unwrap();
+    governance.update(today, &population, &symbols, &mut chronicle, &mut rng);
+    assert_eq!(governance.get_leader(), Some(2));
+    population.begin_update();
+    population.kill_person(2, today).unwrap();
+    governance.update(today, &population, &symbols, &mut chronicle, &mut rng);
+    assert_eq!(governance.get_leader(), Some(3));
+    assert!(governance.get_rule() == SuccessionRule::Hereditary);
+}
+
+#[test]
+fn extinct_line_changes_the_rule() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut population = family();
+    let symbols = SymbolTable::default();
+    let mut chronicle = Chronicle::default();
+    let mut governance = Governance::new(SuccessionRule::Hereditary, 0);
+    governance.update(today, &population, &symbols, &mut chronicle, &mut rng);
+    assert_eq!(governance.get_leader(), Some(1));
+
+    // an unrelated townsman is the only one left
+    population.add(person(4, 950, None));
+    for id in 0..4 {
+        population.kill_person(id, today).unwrap();
+    }
+    governance.update(today, &population, &symbols, &mut chronicle, &mut rng);
+    assert_eq!(governance.get_leader(), Some(4));
+    assert!(governance.get_rule() != SuccessionRule::Hereditary);
+    assert_eq!(governance.get_title(), "Mayor");
+    assert!(chronicle.get_entries().iter().any(|entry| entry.get_text().contains("died out")));
+}
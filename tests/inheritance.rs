@@ -0,0 +1,99 @@
+extern crate procedural_towns;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ Person, Population, Wealth, InheritanceRule };
+use procedural_towns::person::wealth::{ Heirs, gini };
+use procedural_towns::utility::Date;
+
+fn person(id: u32, male: bool, age: i32, today: Date) -> Person {
+    let mut person = Person::new(id);
+    match male {
+        true => person.set_male(),
+        false => person.set_female()
+    }
+    person.set_birthday(Date::new(today.get_year() - age, 0, 0));
+    person
+}
+
+// the wealthy father 0, his wife 1, their daughter 2 and their sons 3 and 4, from eldest to youngest
+fn family(rule: InheritanceRule, today: Date) -> Population {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut population = Population::default();
+    population.set_inheritance_rule(rule);
+    let mut father = person(0, true, 50, today);
+    *father.get_wealth_mut() = Wealth::new(100, 10, 2);
+    population.add(father);
+    population.add(person(1, false, 45, today));
+    population.handle_fertility(today);
+    assert_eq!(population.marry_couples(1, today, &mut rng), 1);
+    for (id, male, age) in [(2, false, 20), (3, true, 18), (4, true, 15)].iter() {
+        let mut child = person(*id, *male, *age, today);
+        child.set_father(0);
+        child.set_mother(1);
+        population.add(child);
+    }
+    population
+}
+
+fn wealth_after_death(rule: InheritanceRule) -> Vec<Wealth> {
+    let today = Date::new(1000, 0, 0);
+    let mut population = family(rule, today);
+    population.kill_person(0, today).unwrap();
+    (1..5).map(|id| *population.get_by_id(id).unwrap().get_wealth()).collect()
+}
+
+#[test]
+fn children_split_the_estate_equally() {
+    let wealth = wealth_after_death(InheritanceRule::EqualSplit);
+    assert!(wealth[0].is_empty());
+    assert!(wealth[1] == Wealth::new(34, 4, 1));
+    assert!(wealth[2] == Wealth::new(33, 3, 1));
+    assert!(wealth[3] == Wealth::new(33, 3, 0));
+}
+
+#[test]
+fn eldest_son_inherits_land_and_houses() {
+    let wealth = wealth_after_death(InheritanceRule::Primogeniture);
+    assert!(wealth[0].is_empty());
+    assert!(wealth[1] == Wealth::new(34, 0, 0));
+    assert!(wealth[2] == Wealth::new(33, 10, 2));
+    assert!(wealth[3] == Wealth::new(33, 0, 0));
+}
+
+#[test]
+fn spouse_inherits_first() {
+    let wealth = wealth_after_death(InheritanceRule::SpouseFirst);
+    assert!(wealth[0] == Wealth::new(100, 10, 2));
+    assert!(wealth[1..].iter().all(Wealth::is_empty));
+}
+
+#[test]
+fn estates_without_children_go_to_the_spouse() {
+    let estate = Wealth::new(10, 1, 1);
+    let widow = Heirs {
+        spouse: Some(1),
+        children: Vec::new(),
+        eldest_son: None
+    };
+    for rule in [InheritanceRule::EqualSplit, InheritanceRule::Primogeniture, InheritanceRule::SpouseFirst].iter() {
+        let shares = rule.distribute(estate, &widow);
+        assert_eq!(shares.len(), 1);
+        assert!(shares[0] == (1, estate));
+    }
+    let nobody = Heirs {
+        spouse: None,
+        children: Vec::new(),
+        eldest_son: None
+    };
+    assert!(InheritanceRule::EqualSplit.distribute(estate, &nobody).is_empty());
+}
+
+#[test]
+fn gini_measures_inequality() {
+    assert_eq!(gini(&mut []), 0.);
+    assert_eq!(gini(&mut [5, 5, 5, 5]), 0.);
+    assert!((gini(&mut [0, 0, 0, 100]) - 0.75).abs() < 1e-6);
+    assert!(gini(&mut [1, 2, 3, 4]) < gini(&mut [0, 0, 1, 9]));
+}
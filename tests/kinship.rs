@@ -0,0 +1,109 @@
+extern crate procedural_towns;
+
+use procedural_towns::person::{ Lineage, Person };
+
+const EPSILON: f32 = 1e-6;
+
+fn record(lineage: &mut Lineage, id: u32, father: Option<u32>, mother: Option<u32>) {
+    let mut person = Person::new(id);
+    if let Some(father) = father {
+        person.set_father(father);
+    }
+    if let Some(mother) = mother {
+        person.set_mother(mother);
+    }
+    lineage.record(&person);
+}
+
+// grandparents 0 and 1 with the siblings 2 and 3, who marry the unrelated 4 and 5.
+// Their children 6 and 7 are first cousins and have the child 8, 9 is a half sibling of 6.
+fn pedigree() -> Lineage {
+    let mut lineage = Lineage::default();
+    record(&mut lineage, 0, None, None);
+    record(&mut lineage, 1, None, None);
+    record(&mut lineage, 2, Some(0), Some(1));
+    record(&mut lineage, 3, Some(0), Some(1));
+    record(&mut lineage, 4, None, None);
+    record(&mut lineage, 5, None, None);
+    record(&mut lineage, 6, Some(2), Some(4));
+    record(&mut lineage, 7, Some(5), Some(3));
+    record(&mut lineage, 8, Some(6), Some(7));
+    record(&mut lineage, 9, Some(2), None);
+    lineage
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < EPSILON, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn degree_of_relationship() {
+    let lineage = pedigree();
+    assert_eq!(lineage.degree_of_relationship(2, 2, 4), Some(0));
+    assert_eq!(lineage.degree_of_relationship(0, 2, 4), Some(1));
+    assert_eq!(lineage.degree_of_relationship(2, 3, 4), Some(2));
+    assert_eq!(lineage.degree_of_relationship(3, 6, 4), Some(3));
+    assert_eq!(lineage.degree_of_relationship(6, 7, 4), Some(4));
+    assert_eq!(lineage.degree_of_relationship(7, 6, 4), Some(4));
+    assert_eq!(lineage.degree_of_relationship(6, 7, 3), None);
+    assert_eq!(lineage.degree_of_relationship(4, 5, 8), None);
+    assert!(lineage.is_related(6, 9, 2));
+    assert!(!lineage.is_related(4, 3, 8));
+}
+
+#[test]
+fn relatives_by_degree() {
+    let lineage = pedigree();
+    assert_eq!(lineage.get_relatives(6, 2), vec![(2, 1), (4, 1), (8, 1), (0, 2), (1, 2), (9, 2)]);
+    assert_eq!(lineage.get_relatives(4, 1), vec![(6, 1)]);
+    assert!(lineage.is_ancestor(0, 8, 3));
+    assert!(!lineage.is_ancestor(0, 8, 2));
+    assert!(!lineage.is_ancestor(8, 0, 3));
+}
+
+#[test]
+fn truncate_forgets_the_latest_records() {
+    let mut lineage = pedigree();
+    lineage.truncate(7);
+    assert_eq!(lineage.size(), 7);
+    assert_eq!(lineage.get_children(2), &[6]);
+    assert!(lineage.get_children(6).is_empty());
+    assert!(lineage.get_children(3).is_empty());
+    assert_eq!(lineage.get_parents(8), (None, None));
+    assert_eq!(lineage.get_parents(6), (Some(2), Some(4)));
+    record(&mut lineage, 7, Some(5), Some(3));
+    assert_eq!(lineage.degree_of_relationship(6, 7, 4), Some(4));
+}
+
+#[test]
+fn coefficient_of_kinship() {
+    let lineage = pedigree();
+    assert_close(lineage.coefficient_of_kinship(4, 4), 0.5);
+    assert_close(lineage.coefficient_of_kinship(0, 2), 0.25);
+    assert_close(lineage.coefficient_of_kinship(2, 0), 0.25);
+    assert_close(lineage.coefficient_of_kinship(2, 3), 0.25);
+    assert_close(lineage.coefficient_of_kinship(6, 9), 0.125);
+    assert_close(lineage.coefficient_of_kinship(3, 6), 0.125);
+    assert_close(lineage.coefficient_of_kinship(6, 7), 1. / 16.);
+    assert_close(lineage.coefficient_of_kinship(7, 6), 1. / 16.);
+    assert_close(lineage.coefficient_of_kinship(4, 5), 0.);
+}
+
+#[test]
+fn inbreeding_coefficient() {
+    let lineage = pedigree();
+    assert_close(lineage.inbreeding_coefficient(8), 1. / 16.);
+    assert_close(lineage.inbreeding_coefficient(6), 0.);
+    assert_close(lineage.inbreeding_coefficient(9), 0.);
+    // an inbred person is more closely related to itself
+    assert_close(lineage.coefficient_of_kinship(8, 8), 0.5 * (1. + 1. / 16.));
+}
+
+// kinship follows the parents of the higher id, which relies on parents having lower ids
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "higher id")]
+fn parents_have_lower_ids() {
+    let mut lineage = Lineage::default();
+    record(&mut lineage, 3, Some(5), None);
+}
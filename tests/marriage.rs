@@ -0,0 +1,110 @@
+extern crate procedural_towns;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ Person, Population, MarriageRules, MarriageEnd };
+use procedural_towns::utility::Date;
+
+fn adult(id: u32, male: bool, age: i32, today: Date) -> Person {
+    let mut person = Person::new(id);
+    match male {
+        true => person.set_male(),
+        false => person.set_female()
+    }
+    person.set_birthday(Date::new(today.get_year() - age, 0, 0));
+    person
+}
+
+// the man 0 and the woman 1, married to each other
+fn couple(today: Date, rules: MarriageRules, rng: &mut StdRng) -> Population {
+    let mut population = Population::default();
+    population.set_marriage_rules(rules);
+    population.add(adult(0, true, 30, today));
+    population.add(adult(1, false, 27, today));
+    population.handle_fertility(today);
+    assert_eq!(population.marry_couples(1, today, rng), 1);
+    population
+}
+
+#[test]
+fn divorced_couples_are_unmarried() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(1);
+    let rules = MarriageRules {
+        divorce_chance: 1.,
+        ..MarriageRules::default()
+    };
+    let mut population = couple(today, rules, &mut rng);
+    population.handle_divorces(today, &mut rng).unwrap();
+    for id in 0..2 {
+        let person = population.get_by_id(id).unwrap();
+        assert_eq!(person.get_spouse(), None);
+        let marriage = person.get_marriage_history().last().unwrap();
+        assert_eq!(marriage.get_spouse_id(), 1 - id);
+        assert!(marriage.get_end() == Some((today, MarriageEnd::Divorce)));
+    }
+}
+
+#[test]
+fn widows_remarry_after_mourning() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(2);
+    let rules = MarriageRules {
+        remarriage_chance: 1.,
+        ..MarriageRules::default()
+    };
+    let mut population = couple(today, rules, &mut rng);
+    population.kill_person(0, today).unwrap();
+    let widow = population.get_by_id(1).unwrap();
+    assert!(widow.get_attr().is_mourning());
+    assert!(widow.get_marriage_history()[0].get_end() == Some((today, MarriageEnd::Widowed)));
+
+    // a bachelor moves to town, but has to wait for the end of the mourning
+    population.add(adult(2, true, 29, today));
+    let mourning_end = today + rules.mourning_period;
+    let during = mourning_end - 10;
+    population.handle_fertility(during);
+    population.handle_expired_attributes(during);
+    population.handle_marriages(during, &mut rng).unwrap();
+    assert_eq!(population.get_by_id(1).unwrap().get_spouse(), None);
+
+    let after = mourning_end + 200;
+    population.handle_expired_attributes(after);
+    population.handle_marriages(after, &mut rng).unwrap();
+    let widow = population.get_by_id(1).unwrap();
+    assert_eq!(widow.get_spouse(), Some(2));
+    let wedding = widow.get_marriage_history()[1].get_start();
+    assert!(wedding >= mourning_end && wedding <= after, "married on {}", wedding);
+}
+
+#[test]
+fn weddings_follow_coming_of_age_and_divorce() {
+    let today = Date::new(1000, 11, 20);
+    let rules = MarriageRules {
+        divorce_chance: 1.,
+        ..MarriageRules::default()
+    };
+    for seed in 0..20 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut population = Population::default();
+        population.set_marriage_rules(rules);
+        // she turns 14 a month before today
+        let mut bride = adult(0, false, 0, today);
+        bride.set_birthday(Date::new(986, 10, 20));
+        population.add(bride);
+        population.add(adult(1, true, 20, today));
+        population.handle_fertility(today);
+        assert_eq!(population.marry_couples(1, today, &mut rng), 1);
+        let wedding = population.get_by_id(0).unwrap().get_marriage_history()[0].get_start();
+        assert!(wedding >= Date::new(1000, 10, 20) && wedding <= today, "married on {}", wedding);
+
+        // after a divorce today, the next wedding can't be earlier
+        population.handle_divorces(today, &mut rng).unwrap();
+        population.add(adult(2, true, 22, today));
+        population.handle_fertility(today);
+        assert_eq!(population.marry_couples(1, today, &mut rng), 1);
+        let person = population.iter().find(|p| p.get_marriage_history().len() == 2).unwrap();
+        assert!(person.get_marriage_history()[1].get_start() == today);
+    }
+}
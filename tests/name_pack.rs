@@ -0,0 +1,100 @@
+extern crate procedural_towns;
+
+use std::fs;
+use std::path::PathBuf;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ NamePack, PersonGenerator };
+use procedural_towns::person::name_pack::{ WeightedNames, load_packs };
+use procedural_towns::utility::{ ResourceLocator, SymbolTable };
+
+// a directory with the given files, removed by the test
+fn pack_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("towns-pack-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for &(file, content) in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn names_are_chosen_by_frequency() {
+    let mut symbols = SymbolTable::default();
+    let names = WeightedNames::parse("Anna,3\n\n  Berta \nClara,x\n", &mut symbols);
+    assert_eq!(names.len(), 3);
+    let mut rng = StdRng::seed_from_u64(12);
+    let mut counts = [0; 3];
+    for _ in 0..5000 {
+        let name = names.choose(&mut rng).unwrap();
+        counts[names.get_names().iter().position(|&n| n == name).unwrap()] += 1;
+    }
+    // 3 : 1 : 1, the invalid frequency counts as 1
+    assert!(counts[0] > 2 * counts[1] && counts[0] > 2 * counts[2], "{:?}", counts);
+    assert_eq!(symbols.resolve(names.get_names()[1]), "Berta");
+    assert!(WeightedNames::default().choose(&mut rng).is_none());
+}
+
+#[test]
+fn packs_are_loaded_from_their_manifest() {
+    let dir = pack_dir("manifest", &[
+        ("manifest.txt", "# a test pack\nname = Marsh Folk\nfirst_names_male = men.txt\n"),
+        ("men.txt", "Ulf\n"),
+        ("first_female.txt", "Ylva\n"),
+        ("last.txt", "Reed\n"),
+        ("town_prefixes.txt", "Fen\n"),
+        ("town_suffixes.txt", "by\n")
+    ]);
+    let mut symbols = SymbolTable::default();
+    let pack = NamePack::load(&dir, &mut symbols).unwrap();
+    assert_eq!(pack.get_name(), "Marsh Folk");
+    assert_eq!(symbols.resolve(pack.get_first_names_male().get_names()[0]), "Ulf");
+    assert_eq!(symbols.resolve(pack.get_first_names_female().get_names()[0]), "Ylva");
+    let mut rng = StdRng::seed_from_u64(13);
+    assert_eq!(pack.generate_town_name(&mut rng).as_deref(), Some("Fenby"));
+    assert!(pack.get_place_words("street").is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn packs_without_names_are_errors() {
+    let dir = pack_dir("no-last-names", &[
+        ("first_male.txt", "Ulf\n"),
+        ("first_female.txt", "Ylva\n"),
+        ("last.txt", "\n")
+    ]);
+    assert!(NamePack::load(&dir, &mut SymbolTable::default()).is_err());
+    fs::remove_file(dir.join("last.txt")).unwrap();
+    assert!(NamePack::load(&dir, &mut SymbolTable::default()).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cultures_are_the_packs_of_the_resource_directory() {
+    let dir = pack_dir("cultures", &[
+        ("cultures/b/first_male.txt", "Bo\n"),
+        ("cultures/b/first_female.txt", "Bea\n"),
+        ("cultures/b/last.txt", "Berg\n"),
+        ("cultures/a/manifest.txt", "name = Alpine\n"),
+        ("cultures/a/first_male.txt", "Alois\n"),
+        ("cultures/a/first_female.txt", "Anni\n"),
+        ("cultures/a/last.txt", "Alm\n")
+    ]);
+    let packs = load_packs(&dir.join("cultures"), &mut SymbolTable::default()).unwrap();
+    assert_eq!(packs.iter().map(NamePack::get_name).collect::<Vec<&str>>(), vec!["Alpine", "b"]);
+
+    let mut generator = PersonGenerator::with_resources(&ResourceLocator::from_dir(&dir)).unwrap();
+    assert_eq!(generator.get_culture_count(), 2);
+    let culture = generator.find_culture("alpine").unwrap();
+    assert_eq!(generator.get_culture_name(culture), "Alpine");
+    let mut rng = StdRng::seed_from_u64(14);
+    for _ in 0..20 {
+        let person = generator.generate_random_person(culture, &mut rng).unwrap();
+        assert!(["Alois", "Anni"].contains(&generator.get_name(person.get_first_name())));
+        assert_eq!(generator.get_name(person.get_last_name()), "Alm");
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
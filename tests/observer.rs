@@ -0,0 +1,124 @@
+extern crate procedural_towns;
+
+use std::sync::{ Arc, Mutex };
+use std::collections::HashSet;
+
+use procedural_towns::world::world::World;
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::person::{ Person, Event };
+use procedural_towns::simulation::{ SimulationPhase, PhaseContext };
+use procedural_towns::town::{ Observer, Town };
+use procedural_towns::utility::{ Date, ResourceLocator };
+use procedural_towns::utility::application_error::ApplicationError;
+use procedural_towns::utility::simulation_error::SimulationError;
+
+// what an observer was told, shared with the test
+#[derive(Default)]
+struct Log {
+    events: Vec<(String, u32)>,
+    years: Vec<String>,
+    undone: Vec<String>
+}
+
+struct Recorder(Arc<Mutex<Log>>);
+
+impl Observer for Recorder {
+    fn on_birth(&mut self, town: &str, child: &Person) {
+        self.0.lock().unwrap().events.push((town.to_owned(), child.get_id()));
+    }
+
+    fn on_death(&mut self, town: &str, person: &Person, _date: Date) {
+        self.0.lock().unwrap().events.push((town.to_owned(), person.get_id()));
+    }
+
+    fn on_marriage(&mut self, town: &str, groom: &Person, _bride: &Person) {
+        self.0.lock().unwrap().events.push((town.to_owned(), groom.get_id()));
+    }
+
+    fn on_pregnancy(&mut self, town: &str, mother: &Person, _father_id: u32, _due: Date) {
+        self.0.lock().unwrap().events.push((town.to_owned(), mother.get_id()));
+    }
+
+    fn on_year_end(&mut self, town: &Town) {
+        self.0.lock().unwrap().years.push(town.get_name().to_owned());
+    }
+
+    fn on_year_undone(&mut self, town: &Town) {
+        self.0.lock().unwrap().undone.push(town.get_name().to_owned());
+    }
+}
+
+// compares the events the observer received with those of the running year,
+// and fails the year if asked to
+struct Probe {
+    log: Arc<Mutex<Log>>,
+    fail: bool
+}
+
+impl SimulationPhase for Probe {
+    fn get_name(&self) -> &str {
+        "probe"
+    }
+
+    fn run(&self, context: &mut PhaseContext) -> Result<(), ApplicationError> {
+        let log = self.log.lock().unwrap();
+        let reported: HashSet<u32> = log.events.iter()
+            .filter(|(town, _)| town == context.town_name)
+            .map(|&(_, id)| id)
+            .collect();
+        let events: Vec<u32> = context.population.get_recent_events().iter()
+            .filter_map(|event| match event {
+                Event::Birth { child } => Some(child.get_id()),
+                Event::Death { person, .. } => Some(person.get_id()),
+                Event::Marriage { groom, .. } => Some(groom.get_id()),
+                Event::Pregnancy { mother, .. } => Some(mother.get_id()),
+                Event::Custom { .. } => None
+            })
+            .collect();
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|id| reported.contains(id)), "events of this year were not reported yet");
+        match self.fail {
+            true => Err(SimulationError::UnknownPerson(0).into()),
+            false => Ok(())
+        }
+    }
+}
+
+fn create_world(seed: u64) -> World {
+    let config = SimulationConfig {
+        seed: Some(seed),
+        ..SimulationConfig::default()
+    };
+    World::create_with_config(2, &ResourceLocator::new(None), &config).unwrap()
+}
+
+#[test]
+fn events_are_reported_during_the_year() {
+    let mut world = create_world(21);
+    let log = Arc::new(Mutex::new(Log::default()));
+    world.add_observer(Box::new(Recorder(log.clone())));
+    world.add_phase(Arc::new(Probe { log: log.clone(), fail: false }));
+    for _ in 0..3 {
+        world.progress().unwrap();
+    }
+    let log = log.lock().unwrap();
+    let towns: HashSet<&str> = world.get_towns().iter().map(Town::get_name).collect();
+    assert_eq!(log.events.iter().map(|(town, _)| town.as_str()).collect::<HashSet<&str>>(), towns);
+    assert_eq!(log.years.len(), 6);
+    assert!(log.undone.is_empty());
+}
+
+#[test]
+fn failed_year_is_reported_as_undone() {
+    let mut world = create_world(22);
+    world.progress().unwrap();
+    let log = Arc::new(Mutex::new(Log::default()));
+    let mut town = world.get_towns()[0].clone();
+    town.add_observer(Box::new(Recorder(log.clone())));
+    town.get_pipeline_mut().add(Arc::new(Probe { log: log.clone(), fail: true }));
+    assert!(town.progress_year().is_err());
+    let log = log.lock().unwrap();
+    assert!(!log.events.is_empty());
+    assert!(log.years.is_empty());
+    assert_eq!(log.undone, vec![town.get_name().to_owned()]);
+}
@@ -0,0 +1,96 @@
+extern crate procedural_towns;
+
+use std::sync::{ Arc, Mutex };
+
+use procedural_towns::world::world::World;
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::person::Event;
+use procedural_towns::script::ScriptEngine;
+use procedural_towns::simulation::{ Pipeline, SimulationPhase, PhaseContext };
+use procedural_towns::utility::ResourceLocator;
+use procedural_towns::utility::application_error::ApplicationError;
+
+// writes its name to a shared log when run
+struct Marker {
+    name: &'static str,
+    log: Arc<Mutex<Vec<&'static str>>>
+}
+
+impl SimulationPhase for Marker {
+    fn get_name(&self) -> &str {
+        self.name
+    }
+
+    fn run(&self, _context: &mut PhaseContext) -> Result<(), ApplicationError> {
+        self.log.lock().unwrap().push(self.name);
+        Ok(())
+    }
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|&name| name.to_owned()).collect()
+}
+
+#[test]
+fn default_pipeline_ends_with_the_scripts() {
+    let pipeline = Pipeline::with_scripts(Arc::new(ScriptEngine::new()));
+    assert_eq!(pipeline.get_names(), vec![
+        "births", "deaths", "divorces", "economy", "culture", "growth", "fertility", "expired_attributes", "marriages", "scripts"
+    ]);
+}
+
+#[test]
+fn phases_are_named_in_the_config() {
+    let scripts = Arc::new(ScriptEngine::new());
+    let pipeline = Pipeline::from_names(&names(&["scripts", "deaths", "births"]), scripts.clone()).unwrap();
+    assert_eq!(pipeline.get_names(), vec!["scripts", "deaths", "births"]);
+    assert!(Pipeline::from_names(&names(&["births", "plague"]), scripts).is_err());
+    assert!(!Pipeline::is_known_phase("plague"));
+
+    let config = SimulationConfig {
+        phases: Some(names(&["births", "plague"])),
+        ..SimulationConfig::default()
+    };
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn phases_are_inserted_and_removed_by_name() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let marker = |name| Arc::new(Marker { name, log: log.clone() });
+    let mut pipeline = Pipeline::empty();
+    pipeline.add(marker("b"));
+    assert!(pipeline.insert_before("b", marker("a")));
+    assert!(pipeline.insert_after("b", marker("c")));
+    assert!(pipeline.insert_after("a", marker("x")));
+    assert!(!pipeline.insert_before("z", marker("y")));
+    assert_eq!(pipeline.get_names(), vec!["a", "x", "b", "c"]);
+    assert!(pipeline.remove("x"));
+    assert!(!pipeline.remove("x"));
+
+    let world = World::create_with_config(1, &ResourceLocator::new(None), &SimulationConfig {
+        seed: Some(23),
+        ..SimulationConfig::default()
+    }).unwrap();
+    let mut town = world.get_towns()[0].clone();
+    town.set_pipeline(pipeline);
+    town.progress_year().unwrap();
+    assert_eq!(*log.lock().unwrap(), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn left_out_phases_do_not_run() {
+    let config = SimulationConfig {
+        seed: Some(24),
+        phases: Some(names(&["deaths", "fertility", "expired_attributes", "marriages"])),
+        ..SimulationConfig::default()
+    };
+    let mut world = World::create_with_config(1, &ResourceLocator::new(None), &config).unwrap();
+    let size = world.get_towns()[0].get_population().size();
+    for _ in 0..5 {
+        world.progress().unwrap();
+        let events = world.get_towns()[0].get_population().get_recent_events();
+        assert!(!events.iter().any(|event| matches!(event, Event::Birth { .. } | Event::Pregnancy { .. })));
+    }
+    assert!(world.get_towns()[0].get_population().size() <= size);
+}
@@ -0,0 +1,99 @@
+extern crate procedural_towns;
+
+use std::fs;
+use std::path::PathBuf;
+use std::collections::HashSet;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::world::world::World;
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::map::{ Site, Position, Terrain };
+use procedural_towns::person::PersonGenerator;
+use procedural_towns::town::{ PlaceNamer, PlaceKind, Temple };
+use procedural_towns::utility::ResourceLocator;
+
+// a resource directory with one culture and the given place words
+fn resource_dir(name: &str, places: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("towns-places-{}-{}", name, std::process::id()));
+    let pack = dir.join("cultures").join("river_folk");
+    fs::create_dir_all(&pack).unwrap();
+    fs::write(pack.join("first_male.txt"), "Anselm\n").unwrap();
+    fs::write(pack.join("first_female.txt"), "Irma\n").unwrap();
+    fs::write(pack.join("last.txt"), "Fisher\n").unwrap();
+    fs::write(pack.join("places.txt"), places).unwrap();
+    dir
+}
+
+fn riverside() -> Site {
+    Site {
+        position: Position::new(3, 4),
+        terrain: Terrain::Grassland,
+        fertility: 0.8,
+        river: true,
+        coast: false
+    }
+}
+
+#[test]
+fn places_are_named_after_families_and_surroundings() {
+    let dir = resource_dir("namesakes", "street: {} Lane\nsquare: {} Square\nchurch: St. {}'s\ntrades: Miller\ngrassland: Meadow\nriver: Ford\n");
+    let generator = PersonGenerator::with_resources(&ResourceLocator::from_dir(&dir)).unwrap();
+    let culture = generator.find_culture("river_folk").unwrap();
+    let families = vec![String::from("Fisher")];
+    let namer = PlaceNamer::new(&generator, culture, riverside(), &families);
+    let mut rng = StdRng::seed_from_u64(32);
+    let expected = ["Fisher Lane", "Miller Lane", "Meadow Lane", "Ford Lane"];
+    let streets: HashSet<String> = (0..100).map(|_| namer.generate(PlaceKind::Street, &[], &mut rng)).collect();
+    assert!(streets.iter().all(|name| expected.contains(&name.as_str())), "{:?}", streets);
+    assert_eq!(streets.len(), expected.len());
+    for _ in 0..20 {
+        let square = namer.generate(PlaceKind::Square, &[], &mut rng);
+        assert!(["Fisher Square", "Meadow Square", "Ford Square"].contains(&square.as_str()));
+        assert_eq!(namer.generate(PlaceKind::Church, &[], &mut rng), "St. Anselm's");
+    }
+    // kinds the pack leaves out use the common words
+    assert!(namer.generate(PlaceKind::Inn, &[], &mut rng).starts_with("The "));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn taken_names_are_avoided() {
+    let dir = resource_dir("taken", "inn: The {} Inn\nemblems: Boar, Stag\n");
+    let generator = PersonGenerator::with_resources(&ResourceLocator::from_dir(&dir)).unwrap();
+    let culture = generator.find_culture("river_folk").unwrap();
+    let namer = PlaceNamer::new(&generator, culture, riverside(), &[]);
+    let mut rng = StdRng::seed_from_u64(33);
+    for _ in 0..20 {
+        assert_eq!(namer.generate(PlaceKind::Inn, &["The Boar Inn"], &mut rng), "The Stag Inn");
+    }
+    // with every name taken one is given twice rather than none
+    let name = namer.generate(PlaceKind::Inn, &["The Boar Inn", "The Stag Inn"], &mut rng);
+    assert!(name == "The Boar Inn" || name == "The Stag Inn");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn towns_give_distinct_names() {
+    let config = SimulationConfig {
+        seed: Some(34),
+        ..SimulationConfig::default()
+    };
+    let mut world = World::create_with_config(3, &ResourceLocator::new(None), &config).unwrap();
+    for _ in 0..10 {
+        world.progress().unwrap();
+    }
+    let mut towns = Vec::new();
+    for town in world.get_towns() {
+        let place_names = town.get_place_names();
+        assert!(place_names.get_square().is_some());
+        assert!(!place_names.get_streets().is_empty());
+        assert!(!place_names.get_inns().is_empty());
+        let mut names = place_names.get_names();
+        names.extend(town.get_temples().iter().map(Temple::get_name));
+        let distinct: HashSet<&str> = names.iter().cloned().collect();
+        assert_eq!(distinct.len(), names.len(), "{} names places twice: {:?}", town.get_name(), names);
+        towns.push(distinct);
+    }
+    assert!(towns[0] != towns[1] && towns[1] != towns[2]);
+}
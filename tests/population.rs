@@ -0,0 +1,200 @@
+extern crate procedural_towns;
+
+use std::collections::{ HashMap, HashSet };
+use std::hash::Hash;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ Person, Population, PersonGenerator, MarriageRules, SocialClass, Event, Culture, Religion };
+use procedural_towns::person::kinship::DEFAULT_FORBIDDEN_DEGREE;
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::simulation::{ Pipeline, PhaseContext };
+use procedural_towns::town::Chronicle;
+use procedural_towns::utility::Date;
+
+const SEED: u64 = 3;
+
+// a founded population with its generator and the date of founding
+fn found(size: u32, config: &SimulationConfig, rng: &mut StdRng) -> (Population, PersonGenerator, Date) {
+    let mut person_generator = PersonGenerator::new().unwrap();
+    let mut today = Date::default();
+    today.set_year(1000);
+    person_generator.set_date(today);
+    let settlers = [(Culture::default(), Religion::default())];
+    let population = Population::new(size, size * 2, today, &settlers, config, &mut person_generator, rng).unwrap();
+    (population, person_generator, today)
+}
+
+fn run_year(population: &mut Population, person_generator: &mut PersonGenerator, today: Date, rng: &mut StdRng) {
+    let mut chronicle = Chronicle::default();
+    let mut context = PhaseContext {
+        town_name: "Testham",
+        date: today,
+        population,
+        chronicle: &mut chronicle,
+        person_generator,
+        rng
+    };
+    Pipeline::default().run(&mut context).unwrap();
+}
+
+#[test]
+fn large_remarriage_age_gap_does_not_overflow() {
+    let config = SimulationConfig::default();
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let (mut population, mut person_generator, mut today) = found(300, &config, &mut rng);
+    population.set_marriage_rules(MarriageRules {
+        remarriage_chance: 1.,
+        remarriage_max_age_gap: u32::MAX,
+        ..MarriageRules::default()
+    });
+    for _ in 0..10 {
+        today.advance_years(1);
+        run_year(&mut population, &mut person_generator, today, &mut rng);
+    }
+    assert!(population.iter().any(|p| p.get_marriage_history().len() > 1));
+}
+
+// what a couple married in a year has in common, with the degree of kinship if they are related
+type Pairing = (i64, bool, bool, Option<u32>);
+
+// couples of persons who were unmarried in the population before
+fn new_pairings(before: &Population, after: &Population, today: Date) -> Vec<Pairing> {
+    let married: HashSet<u32> = before.iter()
+        .filter(|p| p.get_attr().is_married())
+        .map(|p| p.get_id())
+        .collect();
+    after.iter()
+        .filter(|p| p.get_attr().is_male() && !married.contains(&p.get_id()))
+        .filter_map(|groom| groom.get_spouse().and_then(|id| after.get_by_id(id)).map(|bride| (groom, bride)))
+        .map(|(groom, bride)| (
+            // age gap in bands of five years
+            (groom.get_age(&today) as i64 - bride.get_age(&today) as i64).div_euclid(5),
+            groom.get_social_class() == bride.get_social_class(),
+            groom.get_culture() == bride.get_culture(),
+            after.get_lineage().degree_of_relationship(groom.get_id(), bride.get_id(), DEFAULT_FORBIDDEN_DEGREE)
+        ))
+        .collect()
+}
+
+// relative frequencies of the keys
+fn frequencies<K: Hash + Eq>(keys: impl Iterator<Item = K>) -> HashMap<K, f64> {
+    let mut counts = HashMap::new();
+    let mut total = 0.;
+    for key in keys {
+        *counts.entry(key).or_insert(0.) += 1.;
+        total += 1.;
+    }
+    counts.values_mut().for_each(|count| *count /= total);
+    counts
+}
+
+// half the summed differences of the frequencies, 0 for equal and 1 for disjoint distributions
+fn total_variation<K: Hash + Eq>(a: &HashMap<K, f64>, b: &HashMap<K, f64>) -> f64 {
+    let only_b: f64 = b.iter().filter(|(key, _)| !a.contains_key(key)).map(|(_, freq)| freq).sum();
+    let shared: f64 = a.iter().map(|(key, freq)| (freq - b.get(key).cloned().unwrap_or(0.)).abs()).sum();
+    (shared + only_b) / 2.
+}
+
+// four families whose children married into the next family, and whose unmarried
+// grandchildren are siblings and cousins of each other, and outsiders of another culture
+fn kindred(today: Date) -> Population {
+    let classes = [SocialClass::Peasant, SocialClass::Burgher, SocialClass::Peasant, SocialClass::Nobility];
+    let mut population = Population::default();
+    let mut next_id = 0;
+    let mut person = |male: bool, age: i32, parents: Option<(u32, u32)>, class: SocialClass, culture: u16| {
+        let mut person = Person::new(next_id);
+        next_id += 1;
+        match male {
+            true => person.set_male(),
+            false => person.set_female()
+        }
+        let mut birthday = today;
+        birthday.set_year(today.get_year() - age);
+        person.set_birthday(birthday - (next_id * 7) % 300);
+        if let Some((father, mother)) = parents {
+            person.set_father(father);
+            person.set_mother(mother);
+        }
+        person.set_social_class(class);
+        person.set_culture(Culture(culture));
+        person
+    };
+    let mut founders = Vec::new();
+    for &class in classes.iter() {
+        let father = person(true, 75, None, class, 0);
+        let mother = person(false, 72, None, class, 0);
+        founders.push((father.get_id(), mother.get_id()));
+        population.add(father);
+        population.add(mother);
+    }
+    let mut sons = Vec::new();
+    let mut daughters = Vec::new();
+    for family in 0..4 {
+        let son = person(true, 48, Some(founders[family]), classes[family], 0);
+        let daughter = person(false, 46, Some(founders[family]), classes[family], 0);
+        sons.push(son.get_id());
+        daughters.push(daughter.get_id());
+        population.add(son);
+        population.add(daughter);
+    }
+    for family in 0..4 {
+        let parents = (sons[family], daughters[(family + 1) % 4]);
+        for child in 0..6 {
+            population.add(person(child % 2 == 0, 17 + 2 * child, Some(parents), classes[family], 0));
+        }
+    }
+    for outsider in 0..12 {
+        population.add(person(outsider % 2 == 0, 18 + outsider, None, classes[outsider as usize % 4], 1));
+    }
+    population.handle_fertility(today);
+    population
+}
+
+#[test]
+fn batched_marriages_pair_like_single_marriages() {
+    let today = Date::new(1000, 0, 0);
+    let mut population = kindred(today);
+    // cousins may marry each other, siblings not
+    population.set_forbidden_kinship_degree(2);
+
+    let mut batched = Vec::new();
+    let mut single = Vec::new();
+    for seed in 0..2000 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut married = population.clone();
+        married.marry_couples(8, today, &mut rng);
+        batched.extend(new_pairings(&population, &married, today));
+
+        let mut married = population.clone();
+        for _ in 0..8 {
+            married.random_marriage(today, &mut rng);
+        }
+        single.extend(new_pairings(&population, &married, today));
+    }
+    assert!(batched.len() as f64 > 0.95 * single.len() as f64);
+    assert!(single.iter().filter(|pairing| pairing.3.is_some()).count() > 1000);
+    assert!(batched.iter().chain(single.iter()).all(|pairing| pairing.3.is_none_or(|degree| degree > 2)));
+
+    let differences = [
+        total_variation(&frequencies(batched.iter().map(|p| p.0)), &frequencies(single.iter().map(|p| p.0))),
+        total_variation(&frequencies(batched.iter().map(|p| (p.1, p.2))), &frequencies(single.iter().map(|p| (p.1, p.2)))),
+        total_variation(&frequencies(batched.iter().map(|p| p.3)), &frequencies(single.iter().map(|p| p.3)))
+    ];
+    assert!(differences.iter().all(|&difference| difference < 0.03), "{:?}", differences);
+}
+
+#[test]
+fn due_pregnancies_end_in_their_year() {
+    let config = SimulationConfig::default();
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let (mut population, mut person_generator, mut today) = found(300, &config, &mut rng);
+    let mut births = 0;
+    for _ in 0..10 {
+        today.advance_years(1);
+        run_year(&mut population, &mut person_generator, today, &mut rng);
+        births += population.get_recent_events().iter().filter(|event| matches!(event, Event::Birth { .. })).count();
+        assert!(population.iter().all(|p| p.get_attr().get_due_date().is_none_or(|due| due > today)));
+    }
+    assert!(births > 0);
+}
@@ -0,0 +1,102 @@
+extern crate procedural_towns;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ Person, Population, PersonGenerator, PregnancyModel, PregnancyOutcome, AgeCurve, Culture };
+use procedural_towns::utility::{ Date, DAYS_PER_MONTH };
+
+fn adult(person_generator: &mut PersonGenerator, male: bool, age: i32, today: Date, rng: &mut StdRng) -> Person {
+    let mut person = person_generator.generate_random_person(Culture::default(), rng).unwrap();
+    match male {
+        true => person.set_male(),
+        false => person.set_female()
+    }
+    person.set_birthday(Date::new(today.get_year() - age, 0, 0));
+    person
+}
+
+// the man 0 and the woman 1, married and expecting a child, with the generator of their children
+fn expecting(today: Date, model: PregnancyModel, rng: &mut StdRng) -> (Population, PersonGenerator, Date) {
+    let mut person_generator = PersonGenerator::new().unwrap();
+    let mut population = Population::default();
+    population.set_pregnancy_model(model);
+    population.add(adult(&mut person_generator, true, 28, today, rng));
+    population.add(adult(&mut person_generator, false, 25, today, rng));
+    population.handle_fertility(today);
+    assert_eq!(population.marry_couples(1, today, rng), 1);
+    assert!(population.random_pregnancy(today, rng));
+    let due = population.get_by_id(1).unwrap().get_attr().get_due_date().unwrap();
+    (population, person_generator, due)
+}
+
+#[test]
+fn outcomes_follow_the_model() {
+    let mut rng = StdRng::seed_from_u64(4);
+    let model = PregnancyModel {
+        miscarriage_chance: AgeCurve::new(vec![(20, 1.), (21, 0.)]),
+        stillbirth_chance: AgeCurve::new(vec![(20, 0.), (21, 1.), (22, 0.)]),
+        twin_chance: 1.,
+        ..PregnancyModel::default()
+    };
+    let today = Date::new(1000, 0, 0);
+    for _ in 0..100 {
+        assert!(model.roll_outcome(20, &mut rng) == PregnancyOutcome::Miscarriage);
+        assert!(model.roll_outcome(21, &mut rng) == PregnancyOutcome::Stillbirth);
+        assert!(model.roll_outcome(22, &mut rng) == PregnancyOutcome::LiveBirth);
+        assert_eq!(model.roll_count(&mut rng), 2);
+
+        let miscarriage = model.roll_end(today, PregnancyOutcome::Miscarriage, &mut rng);
+        assert!(miscarriage >= today + DAYS_PER_MONTH && miscarriage < today + model.length / 2);
+        let birth = model.roll_end(today, PregnancyOutcome::LiveBirth, &mut rng);
+        assert!(birth >= today + (model.length - model.length_deviation) && birth < today + (model.length + model.length_deviation));
+    }
+    assert!(model.get_recovery_end(today, PregnancyOutcome::Miscarriage) == today + model.miscarriage_recovery);
+    assert!(model.get_recovery_end(today, PregnancyOutcome::Stillbirth) == today + model.postpartum_period);
+}
+
+#[test]
+fn mothers_recover_before_conceiving_again() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(5);
+    let model = PregnancyModel {
+        miscarriage_chance: AgeCurve::new(vec![(0, 0.)]),
+        stillbirth_chance: AgeCurve::new(vec![(0, 0.)]),
+        twin_chance: 1.,
+        ..PregnancyModel::default()
+    };
+    let postpartum_period = model.postpartum_period;
+    let (mut population, mut person_generator, due) = expecting(today, model, &mut rng);
+
+    population.handle_births(due - 1, &mut person_generator, &mut rng).unwrap();
+    assert_eq!(population.size(), 2);
+    population.handle_births(due, &mut person_generator, &mut rng).unwrap();
+    assert_eq!(population.size(), 4);
+    assert!(population.iter().filter(|p| p.get_birthday() == due).all(|child| child.get_mother() == Some(1)));
+
+    let recovered = due + postpartum_period;
+    population.handle_expired_attributes(recovered - 1);
+    assert!(!population.random_pregnancy(recovered - 1, &mut rng));
+    population.handle_expired_attributes(recovered);
+    assert!(population.random_pregnancy(recovered, &mut rng));
+}
+
+#[test]
+fn miscarriages_have_no_children() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(6);
+    let model = PregnancyModel {
+        miscarriage_chance: AgeCurve::new(vec![(0, 1.)]),
+        ..PregnancyModel::default()
+    };
+    let recovery = model.miscarriage_recovery;
+    let (mut population, mut person_generator, due) = expecting(today, model, &mut rng);
+    assert!(due < today + DAYS_PER_MONTH * 5);
+    population.handle_births(due, &mut person_generator, &mut rng).unwrap();
+    assert_eq!(population.size(), 2);
+    let mother = population.get_by_id(1).unwrap();
+    assert_eq!(mother.get_attr().get_due_date(), None);
+    assert!(mother.get_attr().is_postpartum());
+    population.handle_expired_attributes(due + recovery);
+    assert!(population.random_pregnancy(due + recovery, &mut rng));
+}
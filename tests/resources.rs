@@ -0,0 +1,99 @@
+extern crate procedural_towns;
+
+use std::fs;
+use std::path::PathBuf;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ PersonGenerator, Religion };
+use procedural_towns::utility::ResourceLocator;
+use procedural_towns::utility::application_error::ApplicationError;
+use procedural_towns::utility::file_error::FileError;
+
+// a resource directory with plain name lists and the given files
+fn resource_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("towns-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("names_first_male.txt"), "Aldo\n").unwrap();
+    fs::write(dir.join("names_first_female.txt"), "Berta\n").unwrap();
+    fs::write(dir.join("names_last.txt"), "Miller\n").unwrap();
+    for &(file, content) in files {
+        fs::write(dir.join(file), content).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn religions_are_read_from_the_resource_directory() {
+    let dir = resource_dir("religions", &[("religions.txt", "Moon Cult\n\nSea Covenant\n")]);
+    let generator = PersonGenerator::with_resources(&ResourceLocator::from_dir(&dir)).unwrap();
+    assert_eq!(generator.get_religion_count(), 2);
+    assert_eq!(generator.get_religion_name(Religion(1)), "Sea Covenant");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn religions_fall_back_to_the_embedded_list() {
+    let dir = resource_dir("no-religions", &[]);
+    let generator = PersonGenerator::with_resources(&ResourceLocator::from_dir(&dir)).unwrap();
+    assert_eq!(generator.get_religion_name(Religion(0)), "Old Faith");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn empty_religion_file_is_an_error() {
+    let dir = resource_dir("empty-religions", &[("religions.txt", "\n")]);
+    assert!(PersonGenerator::with_resources(&ResourceLocator::from_dir(&dir)).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn plain_name_lists_are_one_culture() {
+    let dir = resource_dir("plain-names", &[]);
+    let mut generator = PersonGenerator::with_resources(&ResourceLocator::from_dir(&dir)).unwrap();
+    assert_eq!(generator.get_culture_count(), 1);
+    let culture = generator.find_culture("common").unwrap();
+    let person = generator.generate_random_person(culture, &mut StdRng::seed_from_u64(15)).unwrap();
+    assert_eq!(generator.get_name(person.get_last_name()), "Miller");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn explicit_directory_without_names_is_an_error() {
+    let dir = std::env::temp_dir().join(format!("towns-no-names-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let resources = ResourceLocator::new(Some(&dir));
+    assert!(resources.is_explicit());
+    match PersonGenerator::with_resources(&resources) {
+        Err(ApplicationError::File(FileError::MissingResource(path))) => assert!(path.contains("cultures")),
+        Err(err) => panic!("unexpected error {}", err),
+        Ok(_) => panic!("names were found in an empty directory")
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn errors_name_the_broken_file() {
+    let dir = resource_dir("broken-names", &[("names_last.txt", " \n")]);
+    let err = PersonGenerator::with_resources(&ResourceLocator::from_dir(&dir)).err().unwrap();
+    assert!(err.to_string().contains("names_last.txt"), "{}", err);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn embedded_packs_are_used_without_a_directory() {
+    let resources = ResourceLocator::embedded();
+    assert!(resources.get_dir().is_none());
+    let mut generator = PersonGenerator::with_resources(&resources).unwrap();
+    assert_eq!(generator.get_culture_count(), 3);
+    assert_eq!(generator.get_religion_name(Religion(0)), "Old Faith");
+    let norse = generator.find_culture("norse").unwrap();
+    let names = include_str!("../resources/cultures/norse/first_male.txt").to_owned()
+        + include_str!("../resources/cultures/norse/first_female.txt");
+    let names: Vec<&str> = names.lines().map(|line| line.split(',').next().unwrap().trim()).collect();
+    let mut rng = StdRng::seed_from_u64(16);
+    for _ in 0..50 {
+        let person = generator.generate_random_person(norse, &mut rng).unwrap();
+        assert!(names.contains(&generator.get_name(person.get_first_name())));
+    }
+}
@@ -0,0 +1,109 @@
+extern crate procedural_towns;
+
+use std::fs;
+use std::sync::Arc;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ Person, Population, Event };
+use procedural_towns::script::ScriptEngine;
+use procedural_towns::town::Chronicle;
+use procedural_towns::utility::{ Date, ResourceLocator, SymbolTable };
+use procedural_towns::utility::script_error::ScriptError;
+
+// the persons 0, 1 and 2, aged 10, 20 and 30
+fn population(today: Date) -> Population {
+    let mut population = Population::default();
+    for id in 0..3 {
+        let mut person = Person::new(id);
+        person.set_birthday(Date::new(today.get_year() - 10 * (id as i32 + 1), 0, 0));
+        population.add(person);
+    }
+    population
+}
+
+fn run(engine: &ScriptEngine, population: &mut Population, chronicle: &mut Chronicle, today: Date) -> Result<(), ScriptError> {
+    let mut rng = StdRng::seed_from_u64(17);
+    engine.run_year("Testbury", today, population, Arc::new(SymbolTable::default()), chronicle, &mut rng)
+}
+
+#[test]
+fn scripts_change_the_town() {
+    let today = Date::new(1000, 0, 0);
+    let mut engine = ScriptEngine::new();
+    engine.add_script("alms", r#"
+        fn on_year(town) {
+            for id in town.persons() {
+                if town.age(id) >= 18 {
+                    town.give_coin(id, 5);
+                    town.set(id, "alms", town.get(id, "alms") + 1);
+                }
+            }
+            town.chronicle(town.name + " gave alms in " + town.year);
+            town.emit("alms", 2);
+        }
+    "#).unwrap();
+    let mut population = population(today);
+    let mut chronicle = Chronicle::default();
+    run(&engine, &mut population, &mut chronicle, today).unwrap();
+    run(&engine, &mut population, &mut chronicle, today).unwrap();
+
+    assert_eq!(population.get_by_id(0).unwrap().get_wealth().get_coin(), 0);
+    for id in 1..3 {
+        let person = population.get_by_id(id).unwrap();
+        assert_eq!(person.get_wealth().get_coin(), 10);
+        assert_eq!(person.get_custom("alms"), Some(2));
+    }
+    assert_eq!(chronicle.get_entries().len(), 2);
+    assert_eq!(chronicle.get_entries()[0].get_text(), "Testbury gave alms in 1000");
+    let emitted: Vec<(&str, Option<u32>)> = population.get_recent_events().iter()
+        .filter_map(|event| match event {
+            Event::Custom { name, person } => Some((name.as_str(), person.as_ref().map(|p| p.get_id()))),
+            _ => None
+        })
+        .collect();
+    assert_eq!(emitted, vec![("alms", Some(2)), ("alms", Some(2))]);
+}
+
+#[test]
+fn broken_scripts_are_errors() {
+    let today = Date::new(1000, 0, 0);
+    let mut engine = ScriptEngine::new();
+    assert!(matches!(engine.add_script("typo", "fn on_year(town) { town.size( }"), Err(ScriptError::Parse(..))));
+    assert!(engine.is_empty());
+
+    engine.add_script("stranger", "fn on_year(town) { town.give_coin(2, 1); town.give_coin(99, 1); }").unwrap();
+    let mut population = population(today);
+    match run(&engine, &mut population, &mut Chronicle::default(), today) {
+        Err(ScriptError::Runtime(script, _)) => assert_eq!(script, "stranger"),
+        other => panic!("unexpected result {:?}", other)
+    }
+    // the population is handed back to the town, with the changes made before the error
+    assert_eq!(population.size(), 3);
+    assert_eq!(population.get_by_id(2).unwrap().get_wealth().get_coin(), 1);
+}
+
+#[test]
+fn endless_scripts_are_stopped() {
+    let today = Date::new(1000, 0, 0);
+    let mut engine = ScriptEngine::new();
+    engine.add_script("endless", "fn on_year(town) { loop { town.random(); } }").unwrap();
+    let result = run(&engine, &mut population(today), &mut Chronicle::default(), today);
+    assert!(matches!(result, Err(ScriptError::Runtime(..))));
+}
+
+#[test]
+fn rules_run_in_order_of_their_files() {
+    let dir = std::env::temp_dir().join(format!("towns-rules-{}", std::process::id()));
+    fs::create_dir_all(dir.join("rules")).unwrap();
+    fs::write(dir.join("rules/2_double.rhai"), "fn on_year(town) { town.set(1, \"x\", town.get(1, \"x\") * 2); }").unwrap();
+    fs::write(dir.join("rules/1_add.rhai"), "fn on_year(town) { town.set(1, \"x\", town.get(1, \"x\") + 3); }").unwrap();
+    fs::write(dir.join("rules/notes.txt"), "not a script").unwrap();
+    fs::write(dir.join("rules/3_helper.rhai"), "fn helper(a, b) { a + b }").unwrap();
+    let engine = ScriptEngine::with_resources(&ResourceLocator::from_dir(&dir)).unwrap();
+    let today = Date::new(1000, 0, 0);
+    let mut population = population(today);
+    run(&engine, &mut population, &mut Chronicle::default(), today).unwrap();
+    assert_eq!(population.get_by_id(1).unwrap().get_custom("x"), Some(6));
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,127 @@
+extern crate procedural_towns;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::world::world::World;
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::person::{ Person, Population, Seasonality, SeasonWeights };
+use procedural_towns::town::Festival;
+use procedural_towns::utility::{ Date, Season, CalendarKind, ResourceLocator };
+
+// weights for events in only one season
+fn only(season: Season) -> SeasonWeights {
+    let weight = |other| match other == season {
+        true => 1.,
+        false => 0.
+    };
+    SeasonWeights {
+        winter: weight(Season::Winter),
+        spring: weight(Season::Spring),
+        summer: weight(Season::Summer),
+        autumn: weight(Season::Autumn)
+    }
+}
+
+#[test]
+fn years_begin_and_end_in_winter() {
+    let seasons: Vec<Season> = (0..12).map(|month| Season::of_month(month, 12)).collect();
+    assert_eq!(seasons, vec![
+        Season::Winter, Season::Winter, Season::Spring, Season::Spring, Season::Spring, Season::Summer,
+        Season::Summer, Season::Summer, Season::Autumn, Season::Autumn, Season::Autumn, Season::Winter
+    ]);
+    assert_eq!(Date::new(1000, 6, 10).get_season(), Season::Summer);
+}
+
+#[test]
+fn weeks_run_on_across_years() {
+    let gregorian = CalendarKind::Gregorian.get_calendar();
+    let start = Date::with_calendar(gregorian, 1999, 11, 20);
+    let mut previous = start.get_weekday();
+    for date in (1..40).map(|days| start + days) {
+        assert_eq!(date.get_weekday(), (previous + 1) % 7);
+        previous = date.get_weekday();
+    }
+    assert_eq!((start + 7 * 53).get_weekday(), start.get_weekday());
+}
+
+#[test]
+fn seasonal_dates_fall_in_their_season() {
+    let mut rng = StdRng::seed_from_u64(18);
+    let start = Date::new(1000, 4, 12);
+    for &season in Season::ALL.iter() {
+        for _ in 0..200 {
+            let date = only(season).random_date(start, &mut rng);
+            assert!(date >= start && date < start + start.get_days_in_year());
+            assert_eq!(date.get_season(), season);
+        }
+    }
+}
+
+#[test]
+fn weddings_follow_the_season() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(19);
+    let mut population = Population::default();
+    population.set_seasonality(Seasonality {
+        marriage: only(Season::Autumn),
+        ..Seasonality::default()
+    });
+    for id in 0..20 {
+        let mut person = Person::new(id);
+        match id % 2 {
+            0 => person.set_male(),
+            _ => person.set_female()
+        }
+        person.set_birthday(Date::new(970 + id as i32 % 6, 0, 0));
+        population.add(person);
+    }
+    population.handle_fertility(today);
+    assert_eq!(population.marry_couples(10, today, &mut rng), 10);
+    for person in population.iter() {
+        let wedding = person.get_marriage_history()[0].get_start();
+        assert_eq!(wedding.get_season(), Season::Autumn);
+        assert!(wedding < today && wedding >= today - today.get_days_in_year());
+    }
+}
+
+#[test]
+fn festivals_fall_back_to_the_last_day_of_the_month() {
+    let gregorian = CalendarKind::Gregorian.get_calendar();
+    let leap_day = Festival {
+        name: String::from("Leap Feast"),
+        month: 2,
+        day: 29
+    };
+    assert!(leap_day.get_date(gregorian, 2000) == Date::with_calendar(gregorian, 2000, 1, 28));
+    assert!(leap_day.get_date(gregorian, 2001) == Date::with_calendar(gregorian, 2001, 1, 27));
+    assert!(leap_day.get_last_date(Date::with_calendar(gregorian, 2001, 1, 26)) == Date::with_calendar(gregorian, 2000, 1, 28));
+    assert!(leap_day.get_last_date(Date::with_calendar(gregorian, 2001, 5, 0)) == Date::with_calendar(gregorian, 2001, 1, 27));
+}
+
+#[test]
+fn towns_celebrate_their_festivals() {
+    let config = SimulationConfig {
+        seed: Some(25),
+        festivals: vec![
+            Festival { name: String::from("Harvest Feast"), month: 9, day: 15 },
+            Festival { name: String::from("Midwinter"), month: 1, day: 1 }
+        ],
+        ..SimulationConfig::default()
+    };
+    let mut world = World::create_with_config(1, &ResourceLocator::new(None), &config).unwrap();
+    for _ in 0..2 {
+        world.progress().unwrap();
+    }
+    let town = &world.get_towns()[0];
+    let today = *town.get_date();
+    let celebrations: Vec<(Date, &str)> = town.get_chronicle().get_entries().iter()
+        .filter(|entry| entry.get_text().contains("celebrate"))
+        .map(|entry| (entry.get_date(), entry.get_text()))
+        .collect();
+    assert_eq!(celebrations.len(), 4);
+    assert!(celebrations.iter().all(|&(date, _)| date <= today && date > today - 2 * today.get_days_in_year()));
+    assert!(celebrations.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    assert!(celebrations.iter().any(|(_, text)| text.ends_with("the Harvest Feast")));
+    assert!(celebrations.iter().any(|(_, text)| text.ends_with("the Midwinter")));
+}
@@ -0,0 +1,82 @@
+extern crate procedural_towns;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ Person, Population, Wealth, Occupation, SocialClass };
+use procedural_towns::utility::Date;
+
+fn person(id: u32, male: bool, age: i32, class: SocialClass, today: Date) -> Person {
+    let mut person = Person::new(id);
+    match male {
+        true => person.set_male(),
+        false => person.set_female()
+    }
+    person.set_birthday(Date::new(today.get_year() - age, 0, 0));
+    person.set_social_class(class);
+    person
+}
+
+#[test]
+fn landowners_farm() {
+    let mut rng = StdRng::seed_from_u64(8);
+    for _ in 0..100 {
+        assert!(Occupation::choose(&Wealth::new(0, 1, 0), &mut rng) == Occupation::Farmer);
+    }
+    let farmer = Occupation::Farmer;
+    assert!(farmer.get_yearly_income(&Wealth::new(0, 5, 0)) > farmer.get_yearly_income(&Wealth::default()));
+    assert!(Occupation::Merchant.get_yearly_income(&Wealth::default()) > Occupation::Laborer.get_yearly_income(&Wealth::default()));
+}
+
+#[test]
+fn wealth_moves_between_peasants_and_burghers() {
+    let mut rng = StdRng::seed_from_u64(9);
+    let rich = Wealth::new(200, 0, 0);
+    let poor = Wealth::new(19, 0, 0);
+    for occupation in [Occupation::Craftsman, Occupation::Merchant].iter() {
+        assert_eq!(SocialClass::Peasant.get_mobility(Some(*occupation), &rich, false, &mut rng), Some(SocialClass::Burgher));
+        assert_eq!(SocialClass::Peasant.get_mobility(Some(*occupation), &poor, false, &mut rng), None);
+    }
+    assert_eq!(SocialClass::Peasant.get_mobility(Some(Occupation::Farmer), &rich, false, &mut rng), None);
+    assert_eq!(SocialClass::Burgher.get_mobility(Some(Occupation::Laborer), &poor, false, &mut rng), Some(SocialClass::Peasant));
+    assert_eq!(SocialClass::Burgher.get_mobility(Some(Occupation::Merchant), &rich, false, &mut rng), None);
+}
+
+#[test]
+fn adults_work_and_rise() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(10);
+    let mut population = Population::default();
+    let mut merchant = person(0, true, 30, SocialClass::Peasant, today);
+    merchant.set_occupation(Occupation::Merchant);
+    *merchant.get_wealth_mut() = Wealth::new(250, 0, 1);
+    population.add(merchant);
+    population.add(person(1, false, 10, SocialClass::Peasant, today));
+    population.handle_economy(today, &mut rng);
+
+    let merchant = population.get_by_id(0).unwrap();
+    assert_eq!(merchant.get_social_class(), SocialClass::Burgher);
+    assert!(merchant.get_wealth().get_value() > 350);
+    assert!(population.get_by_id(1).unwrap().get_occupation().is_none());
+}
+
+#[test]
+fn clergy_stay_unmarried() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(11);
+    let mut population = Population::default();
+    population.add(person(0, true, 25, SocialClass::Clergy, today));
+    population.add(person(1, false, 22, SocialClass::Peasant, today));
+    population.handle_fertility(today);
+    assert_eq!(population.marry_couples(1, today, &mut rng), 0);
+    assert!(!population.random_marriage(today, &mut rng));
+
+    // a bride takes the class of her groom
+    population.add(person(2, true, 26, SocialClass::Nobility, today));
+    population.handle_fertility(today);
+    assert_eq!(population.marry_couples(1, today, &mut rng), 1);
+    let bride = population.get_by_id(1).unwrap();
+    assert_eq!(bride.get_spouse(), Some(2));
+    assert_eq!(bride.get_social_class(), SocialClass::Nobility);
+    assert_eq!(population.get_by_id(0).unwrap().get_spouse(), None);
+}
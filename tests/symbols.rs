@@ -0,0 +1,38 @@
+extern crate procedural_towns;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::person::{ PersonGenerator, Culture };
+use procedural_towns::utility::{ Symbol, SymbolTable };
+
+#[test]
+fn names_are_stored_once() {
+    let mut symbols = SymbolTable::default();
+    let anna = symbols.intern("Anna");
+    assert_eq!(symbols.intern("Anna"), anna);
+    assert_ne!(symbols.intern("Berta"), anna);
+    assert_eq!(symbols.len(), 3);
+    assert_eq!(symbols.resolve(anna), "Anna");
+    assert_eq!(symbols.resolve(Symbol::UNKNOWN), "Unknown");
+    assert_eq!(std::mem::size_of::<Symbol>(), 4);
+}
+
+#[test]
+fn persons_are_named_through_their_generator() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let generator = PersonGenerator::new().unwrap();
+    let mut first = generator.split(0, 2);
+    let mut second = generator.split(1, 2);
+    let person = first.generate_random_person(Culture::default(), &mut rng).unwrap();
+    let name = first.get_name(person.get_first_name()).to_owned();
+    assert_ne!(name, "Unknown");
+    // split generators share the loaded names
+    assert_eq!(second.get_name(person.get_first_name()), name);
+
+    // a name added later only belongs to the generator it was added to
+    let added = first.intern("Zebedee");
+    assert_eq!(first.get_name(added), "Zebedee");
+    assert!(second.get_symbols().find("Zebedee").is_none());
+    assert_eq!(second.intern(&name), person.get_first_name());
+}
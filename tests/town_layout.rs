@@ -0,0 +1,55 @@
+extern crate procedural_towns;
+
+use std::collections::HashSet;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::map::{ TownLayout, Tile };
+use procedural_towns::person::Person;
+use procedural_towns::person::household::{ Household, group_households };
+use procedural_towns::utility::Date;
+
+// unrelated adults, each heading a household of their own
+fn households(count: u32, today: Date) -> Vec<Household> {
+    let persons: Vec<Person> = (0..count)
+        .map(|id| {
+            let mut person = Person::new(id);
+            person.set_birthday(Date::new(today.get_year() - 30, 0, 0));
+            person
+        })
+        .collect();
+    group_households(persons.iter(), today)
+}
+
+#[test]
+fn map_grows_when_plots_run_out() {
+    let today = Date::new(1000, 0, 0);
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut layout = TownLayout::generate(8, true, &mut rng);
+    let size = layout.get_width();
+    layout.update(&households(4, today), 1, &mut rng);
+    let streets = layout.get_streets();
+    let offset = |layout: &TownLayout| {
+        let square = layout.get_square();
+        let dwelling = layout.get_dwelling_of(0).unwrap().get_position();
+        (dwelling.x as i64 - square.x as i64, dwelling.y as i64 - square.y as i64)
+    };
+    let first = offset(&layout);
+
+    let households = households(2000, today);
+    layout.update(&households, 3, &mut rng);
+    assert!(layout.get_width() > size);
+    assert_eq!(layout.get_vacant_count(), 0);
+    assert_eq!(layout.get_temples().len(), 3);
+    let mut positions = HashSet::new();
+    for household in households.iter() {
+        let dwelling = layout.get_dwelling_of(household.get_head()).expect("household without a dwelling");
+        assert!(positions.insert(dwelling.get_position()));
+        assert_eq!(*layout.get_tiles().get(dwelling.get_position()), Tile::Dwelling);
+    }
+    // dwellings and streets keep their place, and the streets their names
+    assert_eq!(offset(&layout), first);
+    assert_eq!(*layout.get_tiles().get(layout.get_square()), Tile::Square);
+    let grown = layout.get_streets();
+    assert!(streets.iter().all(|street| grown.contains(street)));
+}
@@ -0,0 +1,157 @@
+extern crate procedural_towns;
+
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicBool, Ordering };
+
+use procedural_towns::world::world::World;
+use procedural_towns::config::SimulationConfig;
+use procedural_towns::person::{ Event, MarriageEnd, MarriageRules, MIN_MARRIAGE_AGE };
+use procedural_towns::simulation::{ SimulationPhase, PhaseContext };
+use procedural_towns::utility::{ ResourceLocator, CalendarKind };
+use procedural_towns::utility::application_error::ApplicationError;
+use procedural_towns::utility::simulation_error::SimulationError;
+
+// fails the update of one town after the other phases changed it
+struct FailingPhase {
+    town_name: String,
+    failing: Arc<AtomicBool>
+}
+
+impl SimulationPhase for FailingPhase {
+    fn get_name(&self) -> &str {
+        "failing"
+    }
+
+    fn run(&self, context: &mut PhaseContext) -> Result<(), ApplicationError> {
+        match context.town_name == self.town_name && self.failing.load(Ordering::SeqCst) {
+            true => Err(SimulationError::UnknownPerson(0).into()),
+            false => Ok(())
+        }
+    }
+}
+
+#[test]
+fn failed_progress_keeps_previous_state() {
+    let config = SimulationConfig {
+        seed: Some(11),
+        ..SimulationConfig::default()
+    };
+    let mut world = World::create_with_config(3, &ResourceLocator::new(None), &config).unwrap();
+    for _ in 0..5 {
+        world.progress().unwrap();
+    }
+    let before: Vec<(String, u32, usize)> = world.get_towns().iter()
+        .map(|town| (town.get_date().to_string(), town.get_population().size(), town.get_chronicle().get_entries().len()))
+        .collect();
+
+    let town_name = world.get_towns()[1].get_name().to_owned();
+    world.add_phase(Arc::new(FailingPhase { town_name, failing: Arc::new(AtomicBool::new(true)) }));
+    assert!(world.progress().is_err());
+    let after: Vec<(String, u32, usize)> = world.get_towns().iter()
+        .map(|town| (town.get_date().to_string(), town.get_population().size(), town.get_chronicle().get_entries().len()))
+        .collect();
+    assert_eq!(before, after);
+
+    let mut town = world.get_towns()[1].clone();
+    assert!(town.progress_year().is_err());
+    assert_eq!(town.get_date(), world.get_towns()[1].get_date());
+}
+
+// date, living persons, lineage size, chronicle and history length of a town
+type Fingerprint = (String, Vec<u32>, usize, Vec<String>, usize);
+
+fn fingerprint(world: &World) -> Vec<Fingerprint> {
+    world.get_towns().iter()
+        .map(|town| (
+            town.get_date().to_string(),
+            town.get_population().iter().map(|p| p.get_id()).collect(),
+            town.get_population().get_lineage().size(),
+            town.get_chronicle().get_entries().iter().map(|entry| entry.to_string()).collect(),
+            town.get_history().count()
+        ))
+        .collect()
+}
+
+#[test]
+fn failed_year_leaves_no_trace() {
+    let config = SimulationConfig {
+        seed: Some(12),
+        ..SimulationConfig::default()
+    };
+    let failing = Arc::new(AtomicBool::new(false));
+    let mut worlds = Vec::new();
+    for fail in [false, true] {
+        let mut world = World::create_with_config(3, &ResourceLocator::new(None), &config).unwrap();
+        for _ in 0..3 {
+            world.progress().unwrap();
+        }
+        let town_name = world.get_towns()[2].get_name().to_owned();
+        world.add_phase(Arc::new(FailingPhase { town_name, failing: failing.clone() }));
+        if fail {
+            failing.store(true, Ordering::SeqCst);
+            assert!(world.progress().is_err());
+            failing.store(false, Ordering::SeqCst);
+        }
+        for _ in 0..3 {
+            world.progress().unwrap();
+        }
+        worlds.push(fingerprint(&world));
+    }
+    assert!(worlds[0] == worlds[1]);
+}
+
+#[test]
+fn weddings_and_deaths_follow_each_life() {
+    let config = SimulationConfig {
+        seed: Some(5),
+        ..SimulationConfig::default()
+    };
+    let mourning_period = MarriageRules::default().mourning_period;
+    let mut world = World::create_with_config(2, &ResourceLocator::new(None), &config).unwrap();
+    for _ in 0..30 {
+        world.progress().unwrap();
+        for population in world.get_towns().iter().map(|town| town.get_population()) {
+            for person in population.iter() {
+                let mut earliest = person.get_birthday();
+                earliest.advance_years(MIN_MARRIAGE_AGE);
+                for marriage in person.get_marriage_history() {
+                    assert!(marriage.get_start() >= earliest, "{} married on {}", person, marriage.get_start());
+                    earliest = match marriage.get_end() {
+                        Some((end, MarriageEnd::Widowed)) => earliest.max(end + mourning_period),
+                        Some((end, MarriageEnd::Divorce)) => earliest.max(end),
+                        None => earliest
+                    };
+                }
+            }
+            for event in population.get_recent_events() {
+                if let Event::Death { ref person, date } = *event {
+                    assert!(date >= person.get_last_event(), "{} died on {}", person, date);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn worlds_use_their_own_calendar() {
+    let gregorian = SimulationConfig {
+        seed: Some(13),
+        calendar: CalendarKind::Gregorian,
+        ..SimulationConfig::default()
+    };
+    let simple = SimulationConfig {
+        seed: Some(13),
+        ..SimulationConfig::default()
+    };
+    let mut first = World::create_with_config(1, &ResourceLocator::new(None), &gregorian).unwrap();
+    let mut second = World::create_with_config(1, &ResourceLocator::new(None), &simple).unwrap();
+    first.progress().unwrap();
+    second.progress().unwrap();
+    let first_date = *first.get_towns()[0].get_date();
+    let second_date = *second.get_towns()[0].get_date();
+    assert_eq!(first_date.get_calendar().get_name(), "gregorian");
+    assert_eq!(second_date.get_calendar().get_name(), "simple");
+    assert!(first_date.get_days_in_year() >= 365);
+    assert_eq!(second_date.get_days_in_year(), 360);
+    assert!(first.get_towns()[0].get_population().iter().all(|person| person.get_birthday().get_calendar().get_name() == "gregorian"));
+}
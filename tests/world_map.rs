@@ -0,0 +1,82 @@
+extern crate procedural_towns;
+
+use std::collections::HashSet;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use procedural_towns::world::world::World;
+use procedural_towns::config::{ SimulationConfig, WorldConfig };
+use procedural_towns::map::{ WorldMap, Terrain };
+use procedural_towns::utility::ResourceLocator;
+
+fn generate(seed: u64) -> WorldMap {
+    WorldMap::generate(&WorldConfig::default(), &mut StdRng::seed_from_u64(seed))
+}
+
+#[test]
+fn maps_depend_on_the_seed() {
+    assert_eq!(generate(26).to_string(), generate(26).to_string());
+    assert_ne!(generate(26).to_string(), generate(27).to_string());
+}
+
+#[test]
+fn oceans_cover_the_configured_share() {
+    let config = WorldConfig::default();
+    let map = generate(28);
+    let terrain = map.get_terrain();
+    let tiles = (map.get_width() * map.get_height()) as f32;
+    let ocean = terrain.positions().filter(|&p| *terrain.get(p) == Terrain::Ocean).count() as f32;
+    assert!((ocean / tiles - config.ocean_ratio).abs() < 0.01, "ocean share {}", ocean / tiles);
+    assert!(terrain.positions().all(|p| (*terrain.get(p) == Terrain::Ocean) == (*map.get_elevation().get(p) < 0.)));
+    assert!(terrain.positions().any(|p| *terrain.get(p) == Terrain::River));
+}
+
+#[test]
+fn coasts_border_the_ocean() {
+    let map = generate(29);
+    let terrain = map.get_terrain();
+    for position in terrain.positions() {
+        let by_the_sea = terrain.get_neighbours(position).iter().any(|&n| *terrain.get(n) == Terrain::Ocean);
+        match *terrain.get(position) {
+            Terrain::Coast => assert!(by_the_sea),
+            Terrain::Ocean | Terrain::River => (),
+            other => assert!(!by_the_sea, "{:?} at the sea", other)
+        }
+    }
+}
+
+#[test]
+fn sites_are_fertile_and_apart() {
+    let map = generate(30);
+    let sites = map.find_sites(6, 10);
+    assert_eq!(sites.len(), 6);
+    assert!(sites.windows(2).all(|pair| pair[0].fertility >= pair[1].fertility));
+    for (i, site) in sites.iter().enumerate() {
+        assert!(site.terrain.is_habitable());
+        assert!(site.fertility > 0. && site.fertility <= 1.);
+        assert!(sites[i + 1..].iter().all(|other| site.distance(other) >= 10.));
+        assert_eq!(map.get_site(site.position).fertility, site.fertility);
+    }
+    // without room for all towns, they are placed closer together
+    let crowded = map.find_sites(40, 1000);
+    assert_eq!(crowded.len(), 40);
+    assert_eq!(crowded.iter().map(|site| site.position).collect::<HashSet<_>>().len(), 40);
+}
+
+#[test]
+fn towns_are_founded_on_the_map() {
+    let config = SimulationConfig {
+        seed: Some(31),
+        ..SimulationConfig::default()
+    };
+    let world = World::create_with_config(4, &ResourceLocator::new(None), &config).unwrap();
+    let terrain = world.get_map().get_terrain();
+    let positions: HashSet<_> = world.get_towns().iter().map(|town| town.get_site().position).collect();
+    assert_eq!(positions.len(), 4);
+    for town in world.get_towns() {
+        let site = town.get_site();
+        assert_eq!(*terrain.get(site.position), site.terrain);
+        assert!(site.terrain.is_habitable());
+    }
+    assert!(world.get_distance(0, 1) > 0.);
+}